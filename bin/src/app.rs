@@ -24,8 +24,6 @@ impl EthUIApp {
                 ethui_settings::commands::settings_set_dark_mode,
                 ethui_settings::commands::settings_set_fast_mode,
                 ethui_settings::commands::settings_finish_onboarding,
-                ethui_settings::commands::settings_set_alias,
-                ethui_settings::commands::settings_get_alias,
                 ethui_settings::commands::settings_test_alchemy_api_key,
                 ethui_settings::commands::settings_test_etherscan_api_key,
                 ethui_settings::commands::settings_test_rust_log,
@@ -38,6 +36,11 @@ impl EthUIApp {
                 ethui_networks::commands::networks_update,
                 ethui_networks::commands::networks_remove,
                 ethui_networks::commands::networks_chain_id_from_provider,
+                ethui_networks::commands::networks_get_secrets,
+                ethui_networks::commands::networks_unlock_secrets,
+                ethui_networks::commands::networks_lock_secrets,
+                ethui_networks::commands::networks_set_secret,
+                ethui_networks::commands::networks_remove_secret,
                 ethui_db::commands::db_get_contracts,
                 ethui_db::commands::db_get_newer_transactions,
                 ethui_db::commands::db_get_older_transactions,
@@ -50,8 +53,27 @@ impl EthUIApp {
                 ethui_db::commands::db_clear_erc20_blacklist,
                 ethui_db::commands::db_get_native_balance,
                 ethui_db::commands::db_get_erc721_tokens,
+                ethui_db::commands::db_get_address_book,
+                ethui_db::commands::db_save_address_book_entry,
+                ethui_db::commands::db_remove_address_book_entry,
+                ethui_db::commands::db_get_alias,
+                ethui_db::commands::db_set_alias,
+                ethui_db::commands::db_export_address_book,
+                ethui_db::commands::db_import_address_book,
+                ethui_db::commands::db_get_rpc_traffic,
+                ethui_db::commands::db_export_rpc_traffic,
+                ethui_db::commands::db_clear_rpc_traffic,
+                ethui_db::commands::db_get_recordings,
+                ethui_db::commands::db_remove_recording,
                 ethui_ws::commands::ws_peers_by_domain,
                 ethui_ws::commands::ws_peer_count,
+                ethui_ws::commands::ws_peer_stats,
+                ethui_ws::commands::ws_disconnect_peer,
+                ethui_ws::commands::ws_disconnect_domain,
+                ethui_ws::commands::ws_peer_emit_accounts_changed,
+                ethui_ws::commands::ws_peer_emit_chain_changed,
+                ethui_ws::commands::ws_list_pairings,
+                ethui_ws::commands::ws_revoke_pairing,
                 ethui_wallets::commands::wallets_get_all,
                 ethui_wallets::commands::wallets_get_current,
                 ethui_wallets::commands::wallets_get_current_address,
@@ -63,13 +85,40 @@ impl EthUIApp {
                 ethui_wallets::commands::wallets_get_wallet_addresses,
                 ethui_wallets::commands::wallets_get_mnemonic_addresses,
                 ethui_wallets::commands::wallets_validate_mnemonic,
+                ethui_wallets::commands::wallets_generate_mnemonic,
+                ethui_wallets::commands::wallets_create_generated,
+                ethui_wallets::commands::wallets_discover_addresses,
+                ethui_wallets::commands::wallets_list_foundry_keystores,
+                ethui_wallets::commands::wallets_import_foundry_keystore,
+                ethui_wallets::commands::wallets_get_dev_accounts,
+                ethui_wallets::commands::wallets_apply_dev_accounts,
+                ethui_wallets::commands::wallets_dismiss_dev_accounts,
                 ethui_wallets::commands::wallets_ledger_derive,
                 ethui_dialogs::commands::dialog_get_payload,
                 ethui_dialogs::commands::dialog_send,
                 ethui_rpc::commands::rpc_send_transaction,
                 ethui_rpc::commands::rpc_eth_call,
+                ethui_rpc::commands::rpc_resolve_address_label,
+                ethui_rpc::commands::rpc_resolve_ens_name,
+                ethui_rpc::commands::rpc_get_delegations,
+                ethui_rpc::commands::rpc_set_delegation,
+                ethui_rpc::commands::rpc_recording_start,
+                ethui_rpc::commands::rpc_recording_stop,
+                ethui_rpc::commands::rpc_recording_current,
+                ethui_rpc::commands::rpc_recording_replay,
                 ethui_connections::commands::connections_affinity_for,
                 ethui_connections::commands::connections_set_affinity,
+                ethui_connections::commands::connections_is_tab_scoped,
+                ethui_connections::commands::connections_set_tab_scoped,
+                ethui_connections::commands::connections_affinity_for_tab,
+                ethui_connections::commands::connections_set_tab_affinity,
+                ethui_connections::commands::connections_is_read_only,
+                ethui_connections::commands::connections_set_read_only,
+                ethui_connections::commands::connections_get_burner_config,
+                ethui_connections::commands::connections_set_burner_config,
+                ethui_connections::commands::connections_get_burners,
+                ethui_connections::commands::connections_set_burner,
+                ethui_connections::commands::connections_remove_burner,
                 ethui_sync::commands::sync_alchemy_is_network_supported,
                 ethui_sync::commands::sync_get_native_balance,
                 ethui_simulator::commands::simulator_run,
@@ -127,8 +176,12 @@ async fn init(app: &tauri::App, args: &Args) -> AppResult<()> {
     // otherwise the initial tracker won't be ready to spawn
     ethui_sync::init().await;
     ethui_settings::init(resource(app, "settings.json", args)).await?;
-    ethui_ws::init(args).await;
+    if let Err(e) = db.import_aliases().await {
+        tracing::warn!("failed to import aliases into the address book: {}", e);
+    }
+    ethui_ws::init(args, resource(app, "ws-pairings.json", args)).await?;
     ethui_connections::init(resource(app, "connections.json", args)).await;
+    ethui_ipc::init(resource(app, "ethui.ipc", args)).await;
     ethui_wallets::init(resource(app, "wallets.json", args)).await;
     ethui_networks::init(resource(app, "networks.json", args)).await;
     ethui_forge::init().await?;
@@ -29,6 +29,9 @@ pub enum AppError {
     #[error(transparent)]
     Settings(#[from] ethui_settings::Error),
 
+    #[error(transparent)]
+    Ws(#[from] ethui_ws::WsError),
+
     #[error("App already running")]
     NamedLock(#[from] named_lock::Error),
 
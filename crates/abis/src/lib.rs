@@ -18,6 +18,8 @@ sol! {
         event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId);
         function name() public view returns (string name);
         function symbol() public view returns (string symbol);
+        function ownerOf(uint256 tokenId) public view returns (address owner);
+        function balanceOf(address owner) public view returns (uint256 balance);
     }
 
     #[sol(rpc)]
@@ -25,3 +27,64 @@ sol! {
         function tokenURI(uint256 tokenId) public view returns (string uri);
     }
 }
+
+sol! {
+    #[sol(rpc)]
+    contract IERC1155 {
+        function balanceOf(address account, uint256 id) public view returns (uint256 balance);
+        function uri(uint256 id) public view returns (string uri);
+    }
+}
+
+sol! {
+    #[sol(rpc)]
+    contract IERC165 {
+        function supportsInterface(bytes4 interfaceId) external view returns (bool supported);
+    }
+}
+
+sol! {
+    #[sol(rpc)]
+    contract ISafe {
+        function nonce() public view returns (uint256 nonce);
+        function getThreshold() public view returns (uint256 threshold);
+        function getOwners() public view returns (address[] memory owners);
+        function execTransaction(
+            address to,
+            uint256 value,
+            bytes calldata data,
+            uint8 operation,
+            uint256 safeTxGas,
+            uint256 baseGas,
+            uint256 gasPrice,
+            address gasToken,
+            address refundReceiver,
+            bytes memory signatures
+        ) public payable returns (bool success);
+    }
+}
+
+sol! {
+    #[sol(rpc)]
+    contract IEntryPoint {
+        function getNonce(address sender, uint192 key) public view returns (uint256 nonce);
+    }
+
+    #[sol(rpc)]
+    contract ISimpleAccount {
+        function execute(address dest, uint256 value, bytes calldata func) external;
+    }
+}
+
+sol! {
+    #[sol(rpc)]
+    contract IENSRegistry {
+        function resolver(bytes32 node) external view returns (address resolver);
+    }
+
+    #[sol(rpc)]
+    contract IENSResolver {
+        function addr(bytes32 node) external view returns (address addr);
+        function name(bytes32 node) external view returns (string name);
+    }
+}
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use ethui_types::{ui_events, Address, Affinity, DedupChainId, Network, B256};
+use ethui_types::{ui_events, Address, Affinity, DedupChainId, Json, Network, B256};
 pub use internal_msgs::*;
 use once_cell::sync::Lazy;
 use tokio::sync::{broadcast, oneshot, Mutex, RwLock};
@@ -9,8 +9,12 @@ pub use ui_msgs::*;
 /// Supported messages
 #[derive(Debug, Clone)]
 pub enum InternalMsg {
-    ChainChanged(DedupChainId, Option<String>, Affinity),
-    AccountsChanged(Vec<Address>),
+    /// new chain, and the domain & tab it applies to
+    ChainChanged(DedupChainId, Option<String>, Option<u32>, Affinity),
+    /// new accounts, and the domain they apply to. `None` means all domains without a burner
+    AccountsChanged(Vec<Address>, Option<String>),
+    /// a domain's CAIP-25 session changed. includes its new `sessionScopes`
+    SessionChanged(String, Json),
     SettingsUpdated,
 
     AddressAdded(Address),
@@ -68,12 +72,38 @@ mod internal_msgs {
         domain: Option<String>,
         affinity: Affinity,
     ) {
-        send(ChainChanged(dedup_chain_id, domain, affinity)).await;
+        send(ChainChanged(dedup_chain_id, domain, None, affinity)).await;
+    }
+
+    /// Broadcasts `ChainChanged` events for a single tab of a domain
+    pub async fn tab_chain_changed(
+        dedup_chain_id: DedupChainId,
+        domain: String,
+        tab_id: u32,
+        affinity: Affinity,
+    ) {
+        send(ChainChanged(
+            dedup_chain_id,
+            Some(domain),
+            Some(tab_id),
+            affinity,
+        ))
+        .await;
     }
 
     /// Broadcasts `AccountsChanged` events
     pub async fn accounts_changed(addresses: Vec<Address>) {
-        send(AccountsChanged(addresses)).await;
+        send(AccountsChanged(addresses, None)).await;
+    }
+
+    /// Broadcasts `AccountsChanged` events for a single domain
+    pub async fn domain_accounts_changed(domain: String, addresses: Vec<Address>) {
+        send(AccountsChanged(addresses, Some(domain))).await;
+    }
+
+    /// Broadcasts `SessionChanged` events
+    pub async fn session_changed(domain: String, session_scopes: Json) {
+        send(SessionChanged(domain, session_scopes)).await;
     }
 
     /// Broadcasts `SettingsUpdated` events
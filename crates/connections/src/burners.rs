@@ -0,0 +1,219 @@
+//! Per-site burner addresses
+//!
+//! When enabled, each new domain gets its own address, derived from a designated HD wallet at
+//! `<derivation_path>/<site-index>`. The mapping is kept in `Store`, so returning to a site
+//! reuses the same address
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+
+use ethui_types::{Address, GlobalState};
+use ethui_wallets::{Wallet, WalletControl as _, Wallets};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use crate::{Error, Result, Store};
+
+/// Default base path for burners. Uses a separate account from the one HD wallets use by
+/// default, so burners never collide with regular addresses
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/1'/0";
+
+/// Serializes the allocation of new burners per domain, so that concurrent requests from the
+/// same domain don't each derive their own address
+///
+/// Allocating may prompt the user to unlock the burner wallet, so other domains don't wait on it
+static ALLOCATIONS: Lazy<Mutex<HashMap<String, Arc<Mutex<()>>>>> = Lazy::new(Default::default);
+
+/// Indexes being derived, but not yet stored, so that concurrent allocations for different
+/// domains don't end up with the same one
+static IN_FLIGHT: Lazy<Mutex<BTreeSet<u32>>> = Lazy::new(Default::default);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BurnerConfig {
+    /// name of the HD wallet burners are derived from
+    pub wallet: String,
+    pub derivation_path: String,
+
+    /// index the next new domain will get
+    #[serde(default)]
+    pub next_index: u32,
+}
+
+/// The address assigned to a domain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Burner {
+    pub wallet: String,
+    pub index: u32,
+    pub path: String,
+    pub address: Address,
+}
+
+/// Returns the burner for a domain, allocating a new one if burners are enabled and the domain
+/// has none yet
+///
+/// Allocating may require unlocking the burner wallet. Unless `prompt` is set, that fails with
+/// `WalletLocked` instead
+pub async fn burner_for(domain: &str, prompt: bool) -> Result<Option<Burner>> {
+    if let Some(burner) = Store::read().await.get_burner(domain) {
+        return Ok(Some(burner));
+    }
+
+    let _guard = lock_domain(domain).await;
+
+    let config = {
+        let store = Store::read().await;
+
+        // may have been allocated while waiting for the lock
+        if let Some(burner) = store.get_burner(domain) {
+            return Ok(Some(burner));
+        }
+
+        match store.get_burner_config() {
+            Some(config) => config,
+            None => return Ok(None),
+        }
+    };
+
+    let index = reserve_index(&config).await;
+    let burner = derive(&config.wallet, &config.derivation_path, index, prompt).await;
+    IN_FLIGHT.lock().await.remove(&index);
+
+    let burner = burner?;
+    assign(domain, &burner).await?;
+
+    Ok(Some(burner))
+}
+
+/// Assigns the burner at a specific index to a domain, replacing its current one
+pub async fn set_burner(domain: &str, index: u32) -> Result<Burner> {
+    let _guard = lock_domain(domain).await;
+
+    let config = Store::read()
+        .await
+        .get_burner_config()
+        .ok_or(Error::BurnersDisabled)?;
+
+    let burner = derive(&config.wallet, &config.derivation_path, index, true).await?;
+    assign(domain, &burner).await?;
+
+    ethui_broadcast::domain_accounts_changed(domain.into(), vec![burner.address]).await;
+
+    Ok(burner)
+}
+
+/// Forgets a domain's burner. If burners are still enabled, the domain is given a fresh one,
+/// as long as that doesn't require unlocking the burner wallet. Otherwise the domain is left
+/// without accounts, and gets a new burner on its next request for them
+///
+/// The address is kept in its wallet, since it may still hold funds
+pub async fn remove_burner(domain: &str) -> Result<()> {
+    let removed = {
+        let mut store = Store::write().await;
+        let removed = store.inner.burners.remove(domain);
+        store.save()?;
+        removed
+    };
+
+    if removed.is_some() {
+        let accounts = match burner_for(domain, false).await {
+            Ok(Some(burner)) => vec![burner.address],
+            Ok(None) => {
+                let wallets = Wallets::read().await;
+                vec![wallets.get_current_wallet().get_current_address().await]
+            }
+            Err(Error::Wallets(ethui_wallets::Error::WalletLocked(_))) => vec![],
+            Err(e) => return Err(e),
+        };
+
+        ethui_broadcast::domain_accounts_changed(domain.into(), accounts).await;
+    }
+
+    Ok(())
+}
+
+/// Designates the wallet burners are derived from, or disables burners altogether
+///
+/// Existing assignments are kept either way
+pub async fn set_config(wallet: Option<String>, derivation_path: Option<String>) -> Result<()> {
+    let config = match wallet {
+        Some(wallet) => {
+            match Wallets::read().await.get(&wallet) {
+                Some(Wallet::HDWallet(_)) => (),
+                Some(_) => return Err(Error::NotAnHDWallet(wallet)),
+                None => return Err(Error::InvalidWalletName(wallet)),
+            };
+
+            let derivation_path = derivation_path.unwrap_or_else(|| DEFAULT_DERIVATION_PATH.into());
+            let current = Store::read().await.get_burner_config();
+
+            // keep counting from where we were, unless the derivation changed
+            let next_index = current
+                .filter(|c| c.wallet == wallet && c.derivation_path == derivation_path)
+                .map(|c| c.next_index)
+                .unwrap_or_default();
+
+            Some(BurnerConfig {
+                wallet,
+                derivation_path,
+                next_index,
+            })
+        }
+        None => None,
+    };
+
+    let mut store = Store::write().await;
+    store.inner.burner = config;
+    store.save()
+}
+
+/// Waits for any other allocation for the same domain
+async fn lock_domain(domain: &str) -> OwnedMutexGuard<()> {
+    let lock = ALLOCATIONS
+        .lock()
+        .await
+        .entry(domain.into())
+        .or_default()
+        .clone();
+
+    lock.lock_owned().await
+}
+
+/// Picks the next index that's neither stored nor being derived
+async fn reserve_index(config: &BurnerConfig) -> u32 {
+    let mut in_flight = IN_FLIGHT.lock().await;
+
+    let index = match in_flight.last() {
+        Some(last) => config.next_index.max(last + 1),
+        None => config.next_index,
+    };
+
+    in_flight.insert(index);
+    index
+}
+
+/// Stores a domain's burner, making sure its index isn't handed out again
+async fn assign(domain: &str, burner: &Burner) -> Result<()> {
+    let mut store = Store::write().await;
+    store.inner.burners.insert(domain.into(), burner.clone());
+    if let Some(ref mut config) = store.inner.burner {
+        config.next_index = config.next_index.max(burner.index + 1);
+    }
+    store.save()
+}
+
+async fn derive(wallet: &str, derivation_path: &str, index: u32, prompt: bool) -> Result<Burner> {
+    let path = format!("{}/{}", derivation_path, index);
+    let address = Wallets::add_burner(wallet, &path, prompt).await?;
+
+    Ok(Burner {
+        wallet: wallet.into(),
+        index,
+        path,
+        address,
+    })
+}
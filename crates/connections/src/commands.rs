@@ -1,7 +1,12 @@
+use std::collections::HashMap;
+
 use ethui_networks::Networks;
 use ethui_types::{Affinity, GlobalState};
 
-use crate::{Error, Result, Store};
+use crate::{
+    burners::{self, Burner, BurnerConfig},
+    Error, Result, Store,
+};
 
 #[tauri::command]
 pub async fn connections_affinity_for(domain: String) -> Affinity {
@@ -28,3 +33,87 @@ pub async fn connections_set_affinity(domain: &str, affinity: Affinity) -> Resul
 
     Ok(())
 }
+
+#[tauri::command]
+pub async fn connections_is_tab_scoped(domain: String) -> bool {
+    Store::read().await.is_tab_scoped(&domain)
+}
+
+/// Lets each tab of a domain keep its own affinity, instead of sharing the domain's
+#[tauri::command]
+pub async fn connections_set_tab_scoped(domain: String, enabled: bool) -> Result<()> {
+    Store::write().await.set_tab_scoped(&domain, enabled)
+}
+
+#[tauri::command]
+pub async fn connections_affinity_for_tab(domain: String, tab_id: u32) -> Affinity {
+    Store::read().await.get_tab_affinity(&domain, Some(tab_id))
+}
+
+#[tauri::command]
+pub async fn connections_set_tab_affinity(
+    domain: String,
+    tab_id: u32,
+    affinity: Affinity,
+) -> Result<()> {
+    let dedup_chain_id = match affinity {
+        Affinity::Sticky(dedup_chain_id) => {
+            let chain_id = dedup_chain_id.chain_id();
+
+            if !Networks::read().await.validate_chain_id(chain_id) {
+                return Err(Error::InvalidChainId(chain_id));
+            }
+
+            dedup_chain_id
+        }
+        _ => Networks::read().await.get_current().dedup_chain_id(),
+    };
+
+    Store::write()
+        .await
+        .set_tab_affinity(&domain, tab_id, affinity);
+    ethui_broadcast::tab_chain_changed(dedup_chain_id, domain, tab_id, affinity).await;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn connections_is_read_only(key: String) -> bool {
+    Store::read().await.is_read_only(&key)
+}
+
+/// Restricts a domain, or a client such as `client:ipc`, to read-only methods
+#[tauri::command]
+pub async fn connections_set_read_only(key: String, read_only: bool) -> Result<()> {
+    Store::write().await.set_read_only(&key, read_only)
+}
+
+#[tauri::command]
+pub async fn connections_get_burner_config() -> Option<BurnerConfig> {
+    Store::read().await.get_burner_config()
+}
+
+/// Enables burner addresses, derived from the given HD wallet, or disables them if no wallet is
+/// given
+#[tauri::command]
+pub async fn connections_set_burner_config(
+    wallet: Option<String>,
+    derivation_path: Option<String>,
+) -> Result<()> {
+    burners::set_config(wallet, derivation_path).await
+}
+
+#[tauri::command]
+pub async fn connections_get_burners() -> HashMap<String, Burner> {
+    Store::read().await.get_burners().clone()
+}
+
+#[tauri::command]
+pub async fn connections_set_burner(domain: String, index: u32) -> Result<Burner> {
+    burners::set_burner(&domain, index).await
+}
+
+#[tauri::command]
+pub async fn connections_remove_burner(domain: String) -> Result<()> {
+    burners::remove_burner(&domain).await
+}
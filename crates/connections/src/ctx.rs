@@ -1,7 +1,14 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
 use ethui_networks::Networks;
-use ethui_types::{Affinity, DedupChainId, GlobalState, Network};
+use ethui_types::{Address, Affinity, DedupChainId, GlobalState, Network};
+use ethui_wallets::{WalletControl as _, Wallets};
 
 use crate::{
+    burners::{self, Burner},
     permissions::{Permission, PermissionRequest, RequestedPermission},
     Error, Result, Store,
 };
@@ -14,78 +21,201 @@ use crate::{
 pub struct Ctx {
     /// The domain associated with a connection
     pub domain: Option<String>,
+    /// Identifies domain-less connections, such as `client:ipc`, so they can have their own affinity
+    /// and permissions
+    pub client: Option<String>,
+    /// The browser tab associated with a connection, if known
+    pub tab_id: Option<u32>,
+    /// Identifies the individual connection, e.g. the socket address of a WebSocket peer
+    pub peer: Option<String>,
     pub permissions: Vec<Permission>,
+    /// Restricts the connection to methods that neither sign nor change any state
+    /// Shared by all clones, so that lifting it (e.g. once a client is paired) also applies to
+    /// requests already in flight
+    pub read_only: Arc<AtomicBool>,
+    /// Routes calls to this chain regardless of affinity. Set for calls made through a CAIP-25
+    /// session's `wallet_invokeMethod`
+    pub scope: Option<DedupChainId>,
 }
 
 impl jsonrpc_core::Metadata for Ctx {}
 
 impl Ctx {
+    /// Key under which this connection's affinity & permissions are stored
+    /// Either its domain or, for domain-less connections, its client name
+    pub fn key(&self) -> Option<&String> {
+        self.domain.as_ref().or(self.client.as_ref())
+    }
+
     pub async fn get_affinity(&self) -> Affinity {
-        if let Some(ref domain) = self.domain {
-            Store::read().await.get_affinity(domain)
+        if let Some(domain) = self.key() {
+            Store::read().await.get_tab_affinity(domain, self.tab_id)
         } else {
             Default::default()
         }
     }
 
+    /// Sets the affinity of this connection's tab, if its domain is tab-scoped, or of the whole
+    /// domain otherwise
     pub async fn set_affinity(&mut self, affinity: Affinity) -> Result<()> {
-        if let Some(ref domain) = self.domain {
-            Store::write().await.set_affinity(domain, affinity)?;
+        if let Some(domain) = self.key() {
+            let mut store = Store::write().await;
+
+            match self.scoped_tab(&store) {
+                Some(tab_id) => store.set_tab_affinity(domain, tab_id, affinity),
+                None => store.set_affinity(domain, affinity)?,
+            }
         }
 
         Ok(())
     }
 
+    /// Restricts this connection, and all of its clones, to read-only methods, or lifts the
+    /// restriction
+    pub fn set_read_only(&self, read_only: bool) {
+        self.read_only.store(read_only, Ordering::Relaxed);
+    }
+
+    /// Whether this connection is restricted to read-only methods, either by itself or by its
+    /// domain's settings
+    pub async fn is_read_only(&self) -> bool {
+        if self.read_only.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        match self.key() {
+            Some(key) => Store::read().await.is_read_only(key),
+            None => false,
+        }
+    }
+
+    /// The tab whose affinity is changed by this connection, if any
+    fn scoped_tab(&self, store: &Store) -> Option<u32> {
+        let domain = self.domain.as_ref()?;
+        self.tab_id.filter(|_| store.is_tab_scoped(domain))
+    }
+
+    /// The burner address assigned to this connection's domain, if burners are enabled
+    /// A new one is allocated on the domain's first request
+    ///
+    /// Never asks to unlock the burner wallet. If allocating requires it, this fails with
+    /// `WalletLocked` instead
+    pub async fn burner(&self) -> Result<Option<Burner>> {
+        match self.domain {
+            Some(ref domain) => burners::burner_for(domain, false).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Same as `burner`, but asks the user to unlock the burner wallet if needed
+    /// Only meant for requests that already involve the user, such as signing
+    pub async fn unlock_burner(&self) -> Result<Option<Burner>> {
+        match self.domain {
+            Some(ref domain) => burners::burner_for(domain, true).await,
+            None => Ok(None),
+        }
+    }
+
+    /// The address exposed to this connection: its domain's burner, if any, or the globally
+    /// selected one
+    pub async fn current_address(&self) -> Result<Address> {
+        if let Some(burner) = self.burner().await? {
+            return Ok(burner.address);
+        }
+
+        let wallets = Wallets::read().await;
+        Ok(wallets.get_current_wallet().get_current_address().await)
+    }
+
+    /// The accounts exposed to this connection, which are also those authorized in every scope
+    /// of its session
+    ///
+    /// Read-only connections get none. Neither do those whose burner can't be derived yet,
+    /// because its wallet is locked
+    pub async fn exposed_accounts(&self) -> Result<Vec<Address>> {
+        if self.is_read_only().await {
+            return Ok(vec![]);
+        }
+
+        match self.current_address().await {
+            Ok(address) => Ok(vec![address]),
+            Err(Error::Wallets(ethui_wallets::Error::WalletLocked(_))) => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+
     pub async fn network(&self) -> Network {
-        let chain_id = self.chain_id().await;
+        let dedup_chain_id = self.dedup_chain_id().await;
+        let networks = Networks::read().await;
 
-        Networks::read()
-            .await
-            .get_network(chain_id)
+        networks
+            .get_network_by_dedup_chain_id(dedup_chain_id)
+            .or_else(|| networks.get_network(dedup_chain_id.chain_id()))
             .unwrap()
-            .clone()
     }
 
-    pub async fn switch_chain(&mut self, new_chain_id: u32) -> Result<()> {
-        if self.chain_id().await == new_chain_id {
+    /// Switches to a specific network, out of all those sharing its chain id
+    pub async fn switch_chain(&mut self, new_dedup_chain_id: DedupChainId) -> Result<()> {
+        if self.dedup_chain_id().await == new_dedup_chain_id {
             return Ok(());
         }
 
-        if Networks::read().await.validate_chain_id(new_chain_id) {
-            let dedup_id = Networks::read().await.get_lowest_dedup_id(new_chain_id);
+        let exists = Networks::read()
+            .await
+            .get_network_by_dedup_chain_id(new_dedup_chain_id)
+            .is_some();
+
+        if exists {
             match self.get_affinity().await {
                 // If affinity is not set, or sticky, update local affinity, and publish event
                 Affinity::Unset | Affinity::Sticky(_) => {
-                    let internal_id: DedupChainId = (new_chain_id, 0).into();
-                    let affinity = internal_id.into();
+                    let affinity = new_dedup_chain_id.into();
                     self.set_affinity(affinity).await?;
 
-                    ethui_broadcast::chain_changed(internal_id, self.domain.clone(), affinity)
-                        .await;
+                    let tab_id = self.scoped_tab(&*Store::read().await);
+                    match (self.key().cloned(), tab_id) {
+                        (Some(domain), Some(tab_id)) => {
+                            ethui_broadcast::tab_chain_changed(
+                                new_dedup_chain_id,
+                                domain,
+                                tab_id,
+                                affinity,
+                            )
+                            .await
+                        }
+                        (domain, _) => {
+                            ethui_broadcast::chain_changed(new_dedup_chain_id, domain, affinity)
+                                .await
+                        }
+                    }
                 }
 
                 // If current affinity is global, there's nothing to update on this Ctx, and the
                 // domain is irrelevant in the update,
                 Affinity::Global => {
-                    ethui_broadcast::chain_changed(
-                        (new_chain_id, dedup_id).into(),
-                        None,
-                        Affinity::Global,
-                    )
-                    .await;
+                    ethui_broadcast::chain_changed(new_dedup_chain_id, None, Affinity::Global)
+                        .await;
                 }
             };
 
             Ok(())
         } else {
-            Err(Error::InvalidChainId(new_chain_id))
+            Err(Error::InvalidChainId(new_dedup_chain_id.chain_id()))
         }
     }
 
     pub async fn chain_id(&self) -> u32 {
+        self.dedup_chain_id().await.chain_id()
+    }
+
+    pub async fn dedup_chain_id(&self) -> DedupChainId {
+        if let Some(scope) = self.scope {
+            return scope;
+        }
+
         match self.get_affinity().await {
-            Affinity::Sticky(dedup_chain_id) => dedup_chain_id.chain_id(),
-            _ => Networks::read().await.get_current().chain_id(),
+            Affinity::Sticky(dedup_chain_id) => dedup_chain_id,
+            _ => Networks::read().await.get_current().dedup_chain_id(),
         }
     }
 
@@ -94,7 +224,7 @@ impl Ctx {
         let ret = request.clone().into_request_permissions_result();
 
         let new_permissions: Vec<_> = request
-            .into_permissions(self.domain.clone().unwrap())
+            .into_permissions(self.key().cloned().unwrap_or_default())
             .collect();
 
         self.permissions.extend(new_permissions);
@@ -8,6 +8,18 @@ pub enum Error {
 
     #[error("invalid chain id: {0}")]
     InvalidChainId(u32),
+
+    #[error(transparent)]
+    Wallets(#[from] ethui_wallets::Error),
+
+    #[error("unknown wallet: {0}")]
+    InvalidWalletName(String),
+
+    #[error("burner addresses can only be derived from HD wallets. `{0}` is not one")]
+    NotAnHDWallet(String),
+
+    #[error("burner addresses are disabled")]
+    BurnersDisabled,
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -6,7 +6,7 @@ use ethui_types::GlobalState;
 use once_cell::sync::OnceCell;
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-use crate::{migrations::load_and_migrate, store::Store};
+use crate::{migrations::load_and_migrate, sessions::Session, store::Store, Ctx};
 
 static STORE: OnceCell<RwLock<Store>> = OnceCell::new();
 
@@ -46,9 +46,28 @@ async fn receiver() -> ! {
             use InternalMsg::*;
 
             if let NetworkRemoved(network) = msg {
-                let mut store = Store::write().await;
-                store.on_chain_removed(network.dedup_chain_id());
+                let removed = Store::write()
+                    .await
+                    .on_chain_removed(network.dedup_chain_id());
+
+                // if saving fails, the pruned state is still saved along with the next change
+                if let Ok(sessions) = removed {
+                    notify_sessions_changed(sessions).await;
+                }
             }
         }
     }
 }
+
+/// Lets domains know which of their session's scopes are left
+async fn notify_sessions_changed(sessions: Vec<(String, Session)>) {
+    for (domain, session) in sessions {
+        let ctx = Ctx {
+            domain: Some(domain.clone()),
+            ..Default::default()
+        };
+        let accounts = ctx.exposed_accounts().await.unwrap_or_default();
+
+        ethui_broadcast::session_changed(domain, session.session_scopes(&accounts)).await;
+    }
+}
@@ -1,9 +1,11 @@
+pub mod burners;
 pub mod commands;
 mod ctx;
 mod error;
 mod init;
 mod migrations;
 pub mod permissions;
+pub mod sessions;
 mod store;
 pub mod utils;
 
@@ -56,6 +56,7 @@ pub(crate) fn load_and_migrate(pathbuf: &PathBuf) -> Result<Store> {
     let store = Store {
         inner: run_migrations(store),
         file: path.to_path_buf(),
+        tabs: Default::default(),
     };
 
     store.save()?;
@@ -72,6 +73,7 @@ fn run_migrations(store: Versions) -> SerializedStore {
         Versions::V1(v1) => SerializedStore {
             affinities: migrate_affinities_from_v1_to_v2(v1.affinities),
             version: ConstI64,
+            ..Default::default()
         },
         Versions::V2(latest) => latest,
     }
@@ -92,17 +94,17 @@ fn migrate_affinities_from_v1_to_v2(
 
 #[cfg(test)]
 mod tests {
-    use ethui_types::Affinity;
-    use serde_json::json;
     use std::{
         fs::File,
         io::{BufReader, Write},
     };
-    use tempfile::NamedTempFile;
 
-    use crate::store::SerializedStore;
+    use ethui_types::Affinity;
+    use serde_json::json;
+    use tempfile::NamedTempFile;
 
     use super::load_and_migrate;
+    use crate::store::SerializedStore;
 
     #[test]
     fn it_converts_from_v0_to_v1() {
@@ -200,4 +202,34 @@ mod tests {
             assert_eq!(localhost, &Affinity::Sticky((313337, 0).into()));
         }
     }
+
+    #[test]
+    fn it_keeps_burners_in_v2() {
+        let mut tempfile = NamedTempFile::new().unwrap();
+        let store = json!({
+            "version": 2,
+            "affinities": {},
+            "burner": {
+                "wallet": "main",
+                "derivationPath": "m/44'/60'/1'/0",
+                "nextIndex": 1
+            },
+            "burners": {
+                "app.uniswap.org": {
+                    "wallet": "main",
+                    "index": 0,
+                    "path": "m/44'/60'/1'/0/0",
+                    "address": "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+                }
+            }
+        });
+
+        write!(tempfile, "{}", store).unwrap();
+
+        let store = load_and_migrate(&tempfile.path().to_path_buf()).unwrap();
+
+        assert_eq!(store.get_burner_config().unwrap().next_index, 1);
+        assert_eq!(store.get_burner("app.uniswap.org").unwrap().index, 0);
+        assert!(store.get_burner("etherscan.io").is_none());
+    }
 }
@@ -0,0 +1,117 @@
+//! CAIP-25 multichain sessions
+//!
+//! A session authorizes a domain on several chains at once. Unlike affinity, which gives a
+//! domain a single active chain, calls within a session name the chain they're meant for, via
+//! `wallet_invokeMethod`. Sessions are kept in `Store`, so they survive reconnections
+
+use std::collections::BTreeMap;
+
+use ethui_types::{Address, DedupChainId, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// The only namespace supported for now
+pub const NAMESPACE: &str = "eip155";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    /// maps CAIP-2 chain ids (e.g. `eip155:1`) to what's authorized on them
+    pub scopes: BTreeMap<String, SessionScope>,
+
+    /// `sessionProperties` given by the dapp, returned as-is
+    #[serde(default)]
+    pub properties: serde_json::Map<String, Json>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionScope {
+    /// the network calls in this scope are routed to
+    pub chain: DedupChainId,
+    pub methods: Vec<String>,
+    pub notifications: Vec<String>,
+}
+
+impl Session {
+    /// `sessionScopes` as returned to dapps, with the given accounts authorized on every scope
+    pub fn session_scopes(&self, accounts: &[Address]) -> Json {
+        let scopes: serde_json::Map<String, Json> = self
+            .scopes
+            .iter()
+            .map(|(scope, s)| {
+                let accounts: Vec<_> = accounts
+                    .iter()
+                    .map(|a| format!("{}:{}", scope, a.to_checksum(None)))
+                    .collect();
+
+                let value = json!({
+                    "methods": s.methods,
+                    "notifications": s.notifications,
+                    "accounts": accounts,
+                });
+
+                (scope.clone(), value)
+            })
+            .collect();
+
+        Json::Object(scopes)
+    }
+
+    pub fn scope(&self, scope: &str) -> Option<&SessionScope> {
+        self.scopes.get(scope)
+    }
+}
+
+/// Parses a CAIP-2 chain id, e.g. `eip155:1`. Returns `None` for other namespaces
+pub fn parse_scope(scope: &str) -> Option<u32> {
+    let (namespace, reference) = scope.split_once(':')?;
+
+    if namespace != NAMESPACE {
+        return None;
+    }
+
+    reference.parse().ok()
+}
+
+pub fn scope_for(chain_id: u32) -> String {
+    format!("{}:{}", NAMESPACE, chain_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use ethui_types::address;
+
+    use super::*;
+
+    #[test]
+    fn parses_eip155_scopes_only() {
+        assert_eq!(parse_scope("eip155:1"), Some(1));
+        assert_eq!(parse_scope(&scope_for(31337)), Some(31337));
+        assert_eq!(parse_scope("eip155"), None);
+        assert_eq!(parse_scope("eip155:0x1"), None);
+        assert_eq!(parse_scope("solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp"), None);
+    }
+
+    #[test]
+    fn lists_accounts_per_scope() {
+        let mut session = Session::default();
+        session.scopes.insert(
+            "eip155:1".into(),
+            SessionScope {
+                chain: (1, 0).into(),
+                methods: vec!["eth_call".into()],
+                notifications: vec![],
+            },
+        );
+
+        let account = address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+        let scopes = session.session_scopes(&[account]);
+
+        assert_eq!(
+            scopes["eip155:1"]["accounts"][0],
+            "eip155:1:0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+        assert_eq!(scopes["eip155:1"]["methods"][0], "eth_call");
+    }
+}
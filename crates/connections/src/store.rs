@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs::File,
     path::{Path, PathBuf},
 };
@@ -7,7 +7,12 @@ use std::{
 use ethui_types::{Affinity, DedupChainId};
 use serde::{Deserialize, Serialize};
 
-use crate::{migrations::LatestVersion, Result};
+use crate::{
+    burners::{Burner, BurnerConfig},
+    migrations::LatestVersion,
+    sessions::Session,
+    Result,
+};
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase", default)]
@@ -16,6 +21,25 @@ pub struct SerializedStore {
     // rule is currently a domain, but may eventually grow
     // TODO: removing networks will cause some affinities to become invalid. need to clean them up
     pub(crate) affinities: HashMap<String, Affinity>,
+
+    // domains whose tabs each keep their own affinity
+    pub(crate) tab_scoped: HashSet<String>,
+
+    // domains or clients restricted to read-only methods
+    pub(crate) read_only: HashSet<String>,
+
+    /// when set, new domains get their own burner address
+    pub(crate) burner: Option<BurnerConfig>,
+
+    // maps domain -> burner address
+    pub(crate) burners: HashMap<String, Burner>,
+
+    // maps domain -> CAIP-25 session
+    pub(crate) sessions: HashMap<String, Session>,
+
+    // maps domain -> chain_id -> network picked when several share that chain id
+    pub(crate) chain_choices: HashMap<String, HashMap<u32, DedupChainId>>,
+
     pub version: LatestVersion,
 }
 
@@ -24,6 +48,10 @@ pub struct Store {
     pub(crate) file: PathBuf,
 
     pub(crate) inner: SerializedStore,
+
+    // maps (domain, tab_id) -> current_chain_id, for tab-scoped domains
+    // not persisted, since tab ids don't survive browser restarts
+    pub(crate) tabs: HashMap<(String, u32), Affinity>,
 }
 
 impl Store {
@@ -45,6 +73,105 @@ impl Store {
         Ok(())
     }
 
+    /// Affinity of a single tab, falling back to its domain's if the tab has none
+    pub fn get_tab_affinity(&self, domain: &str, tab_id: Option<u32>) -> Affinity {
+        tab_id
+            .and_then(|tab_id| self.tabs.get(&(domain.to_string(), tab_id)))
+            .cloned()
+            .unwrap_or_else(|| self.get_affinity(domain))
+    }
+
+    pub fn has_tab_affinity(&self, domain: &str, tab_id: u32) -> bool {
+        self.tabs.contains_key(&(domain.to_string(), tab_id))
+    }
+
+    pub fn set_tab_affinity(&mut self, domain: &str, tab_id: u32, affinity: Affinity) {
+        let key = (domain.to_string(), tab_id);
+
+        match affinity {
+            Affinity::Unset => self.tabs.remove(&key),
+            affinity => self.tabs.insert(key, affinity),
+        };
+    }
+
+    /// Drops a tab's own affinity, so that it follows its domain again
+    pub fn remove_tab_affinity(&mut self, domain: &str, tab_id: u32) {
+        self.tabs.remove(&(domain.to_string(), tab_id));
+    }
+
+    pub fn is_tab_scoped(&self, domain: &str) -> bool {
+        self.inner.tab_scoped.contains(domain)
+    }
+
+    pub fn set_tab_scoped(&mut self, domain: &str, enabled: bool) -> Result<()> {
+        if enabled {
+            self.inner.tab_scoped.insert(domain.to_string());
+        } else {
+            self.inner.tab_scoped.remove(domain);
+            self.tabs.retain(|(d, _), _| d != domain);
+        }
+        self.save()
+    }
+
+    pub fn is_read_only(&self, key: &str) -> bool {
+        self.inner.read_only.contains(key)
+    }
+
+    pub fn set_read_only(&mut self, key: &str, read_only: bool) -> Result<()> {
+        if read_only {
+            self.inner.read_only.insert(key.to_string());
+        } else {
+            self.inner.read_only.remove(key);
+        }
+        self.save()
+    }
+
+    pub fn get_burner(&self, domain: &str) -> Option<Burner> {
+        self.inner.burners.get(domain).cloned()
+    }
+
+    pub fn get_burners(&self) -> &HashMap<String, Burner> {
+        &self.inner.burners
+    }
+
+    pub fn get_burner_config(&self) -> Option<BurnerConfig> {
+        self.inner.burner.clone()
+    }
+
+    pub fn get_session(&self, domain: &str) -> Option<Session> {
+        self.inner.sessions.get(domain).cloned()
+    }
+
+    pub fn set_session(&mut self, domain: &str, session: Session) -> Result<()> {
+        self.inner.sessions.insert(domain.to_string(), session);
+        self.save()
+    }
+
+    pub fn remove_session(&mut self, domain: &str) -> Result<Option<Session>> {
+        let removed = self.inner.sessions.remove(domain);
+        self.save()?;
+
+        Ok(removed)
+    }
+
+    /// The network a domain chose to switch to, out of several sharing a chain id
+    pub fn get_chain_choice(&self, domain: &str, chain_id: u32) -> Option<DedupChainId> {
+        self.inner
+            .chain_choices
+            .get(domain)
+            .and_then(|choices| choices.get(&chain_id))
+            .copied()
+    }
+
+    pub fn set_chain_choice(&mut self, domain: &str, dedup_chain_id: DedupChainId) -> Result<()> {
+        self.inner
+            .chain_choices
+            .entry(domain.to_string())
+            .or_default()
+            .insert(dedup_chain_id.chain_id(), dedup_chain_id);
+        self.save()
+    }
+
     // Persists current state to disk
     pub(crate) fn save(&self) -> Result<()> {
         let pathbuf = self.file.clone();
@@ -58,9 +185,139 @@ impl Store {
 
     /// Whenever a chain is removed, we need to clear all affinities to that chain
     /// otherwise, new connections from the same website will fail
-    pub(crate) fn on_chain_removed(&mut self, internal_id: DedupChainId) {
+    ///
+    /// Session scopes on that chain are dropped as well. Returns the sessions that lost any, by
+    /// domain
+    pub(crate) fn on_chain_removed(
+        &mut self,
+        internal_id: DedupChainId,
+    ) -> Result<Vec<(String, Session)>> {
         self.inner
             .affinities
             .retain(|_, affinity| *affinity != internal_id.into());
+        self.tabs
+            .retain(|_, affinity| *affinity != internal_id.into());
+
+        let mut changed = vec![];
+        for (domain, session) in self.inner.sessions.iter_mut() {
+            let before = session.scopes.len();
+            session.scopes.retain(|_, scope| scope.chain != internal_id);
+
+            if session.scopes.len() != before {
+                changed.push((domain.clone(), session.clone()));
+            }
+        }
+
+        for choices in self.inner.chain_choices.values_mut() {
+            choices.retain(|_, choice| *choice != internal_id);
+        }
+
+        self.save()?;
+
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::NamedTempFile;
+
+    use super::*;
+
+    fn store() -> (Store, NamedTempFile) {
+        let file = NamedTempFile::new().unwrap();
+        let store = Store {
+            file: file.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        (store, file)
+    }
+
+    #[test]
+    fn tab_affinity_falls_back_to_domain() {
+        let (mut store, _file) = store();
+        let domain_affinity = Affinity::Sticky((1, 0).into());
+        let tab_affinity = Affinity::Sticky((31337, 0).into());
+
+        store.set_affinity("localhost", domain_affinity).unwrap();
+        store.set_tab_affinity("localhost", 7, tab_affinity);
+
+        assert_eq!(store.get_tab_affinity("localhost", Some(7)), tab_affinity);
+        assert_eq!(
+            store.get_tab_affinity("localhost", Some(8)),
+            domain_affinity
+        );
+        assert_eq!(store.get_tab_affinity("localhost", None), domain_affinity);
+
+        store.remove_tab_affinity("localhost", 7);
+        assert_eq!(
+            store.get_tab_affinity("localhost", Some(7)),
+            domain_affinity
+        );
+    }
+
+    #[test]
+    fn disabling_tab_scope_clears_tab_affinities() {
+        let (mut store, _file) = store();
+
+        store.set_tab_scoped("localhost", true).unwrap();
+        store.set_tab_affinity("localhost", 7, Affinity::Global);
+        store.set_tab_affinity("example.com", 7, Affinity::Global);
+        assert!(store.is_tab_scoped("localhost"));
+
+        store.set_tab_scoped("localhost", false).unwrap();
+
+        assert!(!store.is_tab_scoped("localhost"));
+        assert!(!store.has_tab_affinity("localhost", 7));
+        assert!(store.has_tab_affinity("example.com", 7));
+    }
+
+    #[test]
+    fn removing_a_chain_forgets_choices() {
+        let (mut store, _file) = store();
+
+        store
+            .set_chain_choice("localhost", (31337, 1).into())
+            .unwrap();
+        assert_eq!(
+            store.get_chain_choice("localhost", 31337),
+            Some((31337, 1).into())
+        );
+
+        store.on_chain_removed((31337, 1).into()).unwrap();
+        assert_eq!(store.get_chain_choice("localhost", 31337), None);
+    }
+
+    #[test]
+    fn removing_a_chain_prunes_and_saves_sessions() {
+        let (mut store, file) = store();
+
+        let scope = |chain: DedupChainId| crate::sessions::SessionScope {
+            chain,
+            methods: vec![],
+            notifications: vec![],
+        };
+        let session = Session {
+            scopes: [
+                ("eip155:1".to_string(), scope((1, 0).into())),
+                ("eip155:31337".to_string(), scope((31337, 1).into())),
+            ]
+            .into(),
+            properties: Default::default(),
+        };
+        store.set_session("localhost", session).unwrap();
+
+        let changed = store.on_chain_removed((31337, 1).into()).unwrap();
+
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].0, "localhost");
+        assert_eq!(
+            changed[0].1.scopes.keys().collect::<Vec<_>>(),
+            vec!["eip155:1"]
+        );
+
+        let saved = std::fs::read_to_string(file.path()).unwrap();
+        assert!(!saved.contains("eip155:31337"));
     }
 }
@@ -2,10 +2,22 @@ use ethui_types::{Affinity, GlobalState};
 
 use crate::Store;
 
-// checks if a domain matches the given affinity
+// checks if a domain should receive an `accountsChanged` event meant for the given domain
+// domain-less events refer to the globally selected account, which domains with a burner don't use
+pub async fn accounts_match(own_domain: Option<String>, other_domain: &Option<String>) -> bool {
+    match (other_domain, own_domain) {
+        (Some(_), own_domain) => own_domain == *other_domain,
+        (None, Some(own_domain)) => Store::read().await.get_burner(&own_domain).is_none(),
+        (None, None) => true,
+    }
+}
+
+// checks if a domain (and tab) matches the given affinity
 pub async fn affinity_matches(
     own_domain: Option<String>,
+    own_tab: Option<u32>,
     other_domain: &Option<String>,
+    other_tab: Option<u32>,
     affinity: Affinity,
 ) -> bool {
     use Affinity::*;
@@ -15,14 +27,27 @@ pub async fn affinity_matches(
     match affinity {
         // if affinity is global/undefined, we match against any other global/undefined peer
         Unset | Global => {
-            let current_affinity = own_domain.as_ref().map(|d| store.get_affinity(d));
+            let current_affinity = own_domain
+                .as_ref()
+                .map(|d| store.get_tab_affinity(d, own_tab));
 
             current_affinity
                 .map(|a| a.is_unset() || a.is_global())
                 .unwrap_or(true)
         }
 
-        // if affinity is sticky, we only match against peers on the same domain
-        Sticky(_) => own_domain == *other_domain,
+        // a tab's own affinity only concerns that tab
+        Sticky(_) if other_tab.is_some() => own_domain == *other_domain && own_tab == other_tab,
+
+        // if affinity is sticky, we only match against peers on the same domain, unless they
+        // have their own tab affinity
+        Sticky(_) => {
+            let has_own = match (&own_domain, own_tab) {
+                (Some(d), Some(t)) => store.has_tab_affinity(d, t),
+                _ => false,
+            };
+
+            own_domain == *other_domain && !has_own
+        }
     }
 }
@@ -1,7 +1,8 @@
 use alloy::json_abi::JsonAbi;
 use ethui_types::{
-    events::Tx, transactions::Transaction, Address, Contract, Erc721TokenData, TokenBalance,
-    TokenMetadata, UINotify, B256, U256,
+    events::Tx, transactions::Transaction, Address, AddressBookEntry, Contract, Erc721TokenData,
+    Json, Recording, RpcTrafficEntry, RpcTrafficFilter, TokenBalance, TokenMetadata, UINotify,
+    B256, U256,
 };
 
 use super::Result;
@@ -135,3 +136,106 @@ pub async fn db_clear_erc20_blacklist(
 
     Ok(())
 }
+
+/// Lists address book entries, optionally only those visible on a given network
+#[tauri::command]
+pub async fn db_get_address_book(
+    chain_id: Option<u32>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<AddressBookEntry>> {
+    db.get_address_book(chain_id).await
+}
+
+#[tauri::command]
+pub async fn db_save_address_book_entry(
+    entry: AddressBookEntry,
+    db: tauri::State<'_, Db>,
+) -> Result<()> {
+    db.save_address_book_entry(&entry).await?;
+    ethui_broadcast::ui_notify(UINotify::AddressBookUpdated).await;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn db_remove_address_book_entry(
+    address: Address,
+    chain_id: Option<u32>,
+    db: tauri::State<'_, Db>,
+) -> Result<()> {
+    db.remove_address_book_entry(address, chain_id).await?;
+    ethui_broadcast::ui_notify(UINotify::AddressBookUpdated).await;
+
+    Ok(())
+}
+
+/// Gets the name of an address from the address book
+#[tauri::command]
+pub async fn db_get_alias(
+    chain_id: Option<u32>,
+    address: Address,
+    db: tauri::State<'_, Db>,
+) -> Result<Option<String>> {
+    db.get_alias(chain_id, address).await
+}
+
+/// Names an address on all networks. An empty alias clears it
+#[tauri::command]
+pub async fn db_set_alias(
+    address: Address,
+    alias: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<()> {
+    db.set_alias(address, alias).await?;
+    ethui_broadcast::ui_notify(UINotify::AddressBookUpdated).await;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn db_export_address_book(db: tauri::State<'_, Db>) -> Result<String> {
+    db.export_address_book_csv().await
+}
+
+/// Imports address book entries from CSV. Returns how many were imported
+#[tauri::command]
+pub async fn db_import_address_book(csv: String, db: tauri::State<'_, Db>) -> Result<usize> {
+    let count = db.import_address_book_csv(&csv).await?;
+    ethui_broadcast::ui_notify(UINotify::AddressBookUpdated).await;
+
+    Ok(count)
+}
+
+/// Lists JSON-RPC calls recorded by the traffic inspector, most recent first
+#[tauri::command]
+pub async fn db_get_rpc_traffic(
+    filter: Option<RpcTrafficFilter>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<RpcTrafficEntry>> {
+    db.get_rpc_traffic(&filter.unwrap_or_default()).await
+}
+
+/// Exports recorded JSON-RPC calls as a HAR-like document
+#[tauri::command]
+pub async fn db_export_rpc_traffic(
+    filter: Option<RpcTrafficFilter>,
+    db: tauri::State<'_, Db>,
+) -> Result<Json> {
+    db.export_rpc_traffic_har(&filter.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn db_clear_rpc_traffic(db: tauri::State<'_, Db>) -> Result<()> {
+    db.clear_rpc_traffic().await
+}
+
+/// Lists recorded dapp sessions, most recent first
+#[tauri::command]
+pub async fn db_get_recordings(db: tauri::State<'_, Db>) -> Result<Vec<Recording>> {
+    db.get_recordings().await
+}
+
+#[tauri::command]
+pub async fn db_remove_recording(id: i64, db: tauri::State<'_, Db>) -> Result<()> {
+    db.remove_recording(id).await
+}
@@ -22,6 +22,9 @@ pub enum Error {
 
     #[error("not found")]
     NotFound,
+
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
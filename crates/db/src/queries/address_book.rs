@@ -0,0 +1,322 @@
+use std::str::FromStr;
+
+use ethui_settings::Settings;
+use ethui_types::{AddressBookEntry, GlobalState};
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, Row};
+use tracing::instrument;
+
+use crate::{DbInner, Result};
+
+/// `chain_id` value used for entries that apply to all networks
+const ALL_CHAINS: u32 = 0;
+
+/// Key in the `kv` table marking legacy aliases as already imported
+const ALIASES_IMPORTED_KEY: &str = "address_book_aliases_imported";
+
+impl DbInner {
+    /// Lists address book entries. If `chain_id` is given, only entries for that network, or for
+    /// all networks, are included
+    pub async fn get_address_book(&self, chain_id: Option<u32>) -> Result<Vec<AddressBookEntry>> {
+        let rows = match chain_id {
+            Some(chain_id) => {
+                sqlx::query(
+                    r#" SELECT * FROM address_book
+                        WHERE chain_id = ? OR chain_id = ?
+                        ORDER BY name "#,
+                )
+                .bind(chain_id)
+                .bind(ALL_CHAINS)
+                .fetch_all(self.pool())
+                .await?
+            }
+            None => {
+                sqlx::query(r#"SELECT * FROM address_book ORDER BY name"#)
+                    .fetch_all(self.pool())
+                    .await?
+            }
+        };
+
+        Ok(rows.iter().map(entry_from_row).collect())
+    }
+
+    /// Finds the entry for an address, preferring one scoped to the given network over a global
+    /// one
+    pub async fn get_address_book_entry(
+        &self,
+        chain_id: u32,
+        address: ethui_types::Address,
+    ) -> Result<Option<AddressBookEntry>> {
+        let row = sqlx::query(
+            r#" SELECT * FROM address_book
+                WHERE address = ? AND (chain_id = ? OR chain_id = ?)
+                ORDER BY chain_id DESC
+                LIMIT 1 "#,
+        )
+        .bind(format!("0x{:x}", address))
+        .bind(chain_id)
+        .bind(ALL_CHAINS)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row.as_ref().map(entry_from_row))
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub async fn save_address_book_entry(&self, entry: &AddressBookEntry) -> Result<()> {
+        sqlx::query(
+            r#" INSERT OR REPLACE INTO address_book (address, chain_id, name, tags, notes)
+                VALUES (?,?,?,?,?) "#,
+        )
+        .bind(format!("0x{:x}", entry.address))
+        .bind(entry.chain_id.unwrap_or(ALL_CHAINS))
+        .bind(entry.name.trim())
+        .bind(serde_json::to_string(&entry.tags)?)
+        .bind(&entry.notes)
+        .execute(self.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_address_book_entry(
+        &self,
+        address: ethui_types::Address,
+        chain_id: Option<u32>,
+    ) -> Result<()> {
+        sqlx::query(r#"DELETE FROM address_book WHERE address = ? AND chain_id = ?"#)
+            .bind(format!("0x{:x}", address))
+            .bind(chain_id.unwrap_or(ALL_CHAINS))
+            .execute(self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn export_address_book_csv(&self) -> Result<String> {
+        entries_to_csv(&self.get_address_book(None).await?)
+    }
+
+    /// Imports entries from CSV, replacing existing entries for the same address and network
+    /// Returns the number of imported entries
+    pub async fn import_address_book_csv(&self, csv: &str) -> Result<usize> {
+        let entries = entries_from_csv(csv)?;
+
+        for entry in entries.iter() {
+            self.save_address_book_entry(entry).await?;
+        }
+
+        Ok(entries.len())
+    }
+
+    /// Name of an address, as seen from the given network. Without one, only global entries
+    /// are considered
+    pub async fn get_alias(
+        &self,
+        chain_id: Option<u32>,
+        address: ethui_types::Address,
+    ) -> Result<Option<String>> {
+        let entry = self
+            .get_address_book_entry(chain_id.unwrap_or(ALL_CHAINS), address)
+            .await?;
+
+        Ok(entry.map(|e| e.name))
+    }
+
+    /// Names an address on all networks, keeping any tags and notes it already had
+    /// An empty alias removes the entry
+    pub async fn set_alias(
+        &self,
+        address: ethui_types::Address,
+        alias: Option<String>,
+    ) -> Result<()> {
+        let alias = alias.map(|v| v.trim().to_owned()).filter(|v| !v.is_empty());
+
+        let Some(name) = alias else {
+            return self.remove_address_book_entry(address, None).await;
+        };
+
+        let entry = match self.get_address_book_entry(ALL_CHAINS, address).await? {
+            Some(entry) => AddressBookEntry { name, ..entry },
+            None => AddressBookEntry {
+                address,
+                chain_id: None,
+                name,
+                tags: vec![],
+                notes: None,
+            },
+        };
+
+        self.save_address_book_entry(&entry).await
+    }
+
+    /// Moves aliases from settings into the address book, as global entries
+    ///
+    /// Aliases that were already copied over by an earlier version are dropped instead, so
+    /// those removed from the address book since then don't come back. Entries and the
+    /// imported flag are written in a single transaction, and aliases are only removed from
+    /// settings once it's committed, so a failure halfway loses nothing
+    pub async fn import_aliases(&self) -> Result<()> {
+        let aliases = Settings::read().await.aliases();
+        if aliases.is_empty() {
+            return Ok(());
+        }
+
+        let imported = self
+            .kv_get::<_, bool>(&ALIASES_IMPORTED_KEY)
+            .await?
+            .unwrap_or(false);
+
+        if !imported {
+            let mut tx = self.tx().await?;
+
+            for (address, name) in aliases {
+                sqlx::query(
+                    r#" INSERT OR REPLACE INTO address_book (address, chain_id, name, tags, notes)
+                        VALUES (?,?,?,'[]',NULL) "#,
+                )
+                .bind(format!("0x{:x}", address))
+                .bind(ALL_CHAINS)
+                .bind(name.trim())
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            sqlx::query(
+                r#" INSERT INTO kv (key, value)
+                    VALUES (?,?)
+                    ON CONFLICT(key) DO UPDATE SET value = excluded.value "#,
+            )
+            .bind(serde_json::to_string(&ALIASES_IMPORTED_KEY)?)
+            .bind(serde_json::to_string(&true)?)
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+        }
+
+        Settings::write().await.clear_aliases().await?;
+        Ok(())
+    }
+}
+
+fn entry_from_row(row: &SqliteRow) -> AddressBookEntry {
+    let chain_id: u32 = row.get("chain_id");
+    let tags: String = row.get("tags");
+
+    AddressBookEntry {
+        address: ethui_types::Address::from_str(row.get("address")).unwrap(),
+        chain_id: (chain_id != ALL_CHAINS).then_some(chain_id),
+        name: row.get("name"),
+        tags: serde_json::from_str(&tags).unwrap_or_default(),
+        notes: row.get("notes"),
+    }
+}
+
+/// Flat representation of an entry, with tags separated by `;`
+#[derive(Debug, Serialize, Deserialize)]
+struct CsvRow {
+    address: ethui_types::Address,
+    chain_id: Option<u32>,
+    name: String,
+    tags: String,
+    notes: Option<String>,
+}
+
+fn entries_to_csv(entries: &[AddressBookEntry]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+
+    for entry in entries {
+        writer.serialize(CsvRow {
+            address: entry.address,
+            chain_id: entry.chain_id,
+            name: entry.name.clone(),
+            tags: entry.tags.join(";"),
+            notes: entry.notes.clone(),
+        })?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn entries_from_csv(csv: &str) -> Result<Vec<AddressBookEntry>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(csv.as_bytes());
+
+    reader
+        .deserialize::<CsvRow>()
+        .map(|row| {
+            let row = row?;
+
+            Ok(AddressBookEntry {
+                address: row.address,
+                chain_id: row.chain_id.filter(|c| *c != ALL_CHAINS),
+                name: row.name,
+                tags: row
+                    .tags
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from)
+                    .collect(),
+                notes: row.notes.filter(|n| !n.is_empty()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ethui_types::address;
+
+    use super::*;
+
+    #[test]
+    fn csv_roundtrip() {
+        let entries = vec![
+            AddressBookEntry {
+                address: address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
+                chain_id: None,
+                name: "vitalik".into(),
+                tags: vec!["people".into(), "ens".into()],
+                notes: Some("has commas, and \"quotes\"".into()),
+            },
+            AddressBookEntry {
+                address: address!("0x5FbDB2315678afecb367f032d93F642f64180aa3"),
+                chain_id: Some(31337),
+                name: "counter".into(),
+                tags: vec![],
+                notes: None,
+            },
+        ];
+
+        let csv = entries_to_csv(&entries).unwrap();
+
+        assert!(csv.starts_with("address,chain_id,name,tags,notes\n"));
+        assert_eq!(entries_from_csv(&csv).unwrap(), entries);
+    }
+
+    #[test]
+    fn csv_import_is_lenient() {
+        let csv = "address,chain_id,name,tags,notes\n\
+                   0x5FbDB2315678afecb367f032d93F642f64180aa3, 0 , deployer , a; b ;,\n";
+
+        let entries = entries_from_csv(csv).unwrap();
+
+        assert_eq!(entries[0].chain_id, None);
+        assert_eq!(entries[0].name, "deployer");
+        assert_eq!(entries[0].tags, vec!["a", "b"]);
+        assert_eq!(entries[0].notes, None);
+    }
+
+    #[test]
+    fn csv_import_rejects_invalid_addresses() {
+        let csv = "address,chain_id,name,tags,notes\nnot-an-address,,foo,,\n";
+
+        assert!(entries_from_csv(csv).is_err());
+    }
+}
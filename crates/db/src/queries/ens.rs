@@ -0,0 +1,98 @@
+//! Cache of ENS lookups
+//!
+//! Both successful and failed lookups are cached for `TTL`, so that unnamed addresses don't cause
+//! a round-trip every time they are displayed. Resolution itself happens elsewhere, against the
+//! user's mainnet network
+
+use std::{
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use ethui_types::Address;
+use sqlx::Row;
+
+use crate::{DbInner, Result};
+
+const TTL: Duration = Duration::from_secs(60 * 60);
+
+impl DbInner {
+    /// Cached forward resolution of an ENS name
+    ///
+    /// `None` if there's no fresh entry, `Some(None)` if the name is known not to resolve
+    pub async fn get_cached_ens_name(&self, name: &str) -> Result<Option<Option<Address>>> {
+        let row = sqlx::query(r#"SELECT address, resolved_at FROM ens_names WHERE name = ?"#)
+            .bind(name)
+            .fetch_optional(self.pool())
+            .await?;
+
+        Ok(row.filter(|r| is_fresh(r.get("resolved_at"))).map(|r| {
+            let address: Option<String> = r.get("address");
+            address.and_then(|a| Address::from_str(&a).ok())
+        }))
+    }
+
+    pub async fn cache_ens_name(&self, name: &str, address: Option<Address>) -> Result<()> {
+        sqlx::query(
+            r#" INSERT OR REPLACE INTO ens_names (name, address, resolved_at)
+                VALUES (?,?,?) "#,
+        )
+        .bind(name)
+        .bind(address.map(|a| format!("0x{:x}", a)))
+        .bind(now())
+        .execute(self.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Cached reverse resolution of an address
+    ///
+    /// `None` if there's no fresh entry, `Some(None)` if the address is known to have no name
+    pub async fn get_cached_ens_reverse(&self, address: Address) -> Result<Option<Option<String>>> {
+        let row = sqlx::query(r#"SELECT name, resolved_at FROM ens_reverse WHERE address = ?"#)
+            .bind(format!("0x{:x}", address))
+            .fetch_optional(self.pool())
+            .await?;
+
+        Ok(row
+            .filter(|r| is_fresh(r.get("resolved_at")))
+            .map(|r| r.get("name")))
+    }
+
+    pub async fn cache_ens_reverse(&self, address: Address, name: Option<&str>) -> Result<()> {
+        sqlx::query(
+            r#" INSERT OR REPLACE INTO ens_reverse (address, name, resolved_at)
+                VALUES (?,?,?) "#,
+        )
+        .bind(format!("0x{:x}", address))
+        .bind(name)
+        .bind(now())
+        .execute(self.pool())
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+fn is_fresh(resolved_at: i64) -> bool {
+    now() - resolved_at < TTL.as_secs() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_expires_after_ttl() {
+        assert!(is_fresh(now()));
+        assert!(!is_fresh(now() - TTL.as_secs() as i64));
+    }
+}
@@ -1,10 +1,14 @@
+mod address_book;
 mod alchemy;
 mod chain_tip;
 mod contracts;
+mod ens;
 mod erc1155;
 mod erc20;
 mod erc721;
 mod events;
 mod kv;
 mod native_balance;
+mod recordings;
+mod rpc_traffic;
 mod transactions;
@@ -0,0 +1,77 @@
+use ethui_types::Recording;
+use sqlx::{sqlite::SqliteRow, Row};
+
+use crate::{DbInner, Error, Result};
+
+impl DbInner {
+    /// Stores a new recording, returning its id
+    pub async fn insert_recording(&self, recording: &Recording) -> Result<i64> {
+        let res = sqlx::query(
+            r#" INSERT INTO recordings (name, domain, created_at, network, steps)
+                VALUES (?,?,?,?,?) "#,
+        )
+        .bind(&recording.name)
+        .bind(&recording.domain)
+        .bind(recording.created_at as i64)
+        .bind(serde_json::to_string(&recording.network)?)
+        .bind(serde_json::to_string(&recording.steps)?)
+        .execute(self.pool())
+        .await?;
+
+        Ok(res.last_insert_rowid())
+    }
+
+    /// Lists all recordings, most recent first
+    pub async fn get_recordings(&self) -> Result<Vec<Recording>> {
+        let rows = sqlx::query(r#"SELECT * FROM recordings ORDER BY id DESC"#)
+            .fetch_all(self.pool())
+            .await?;
+
+        rows.iter().map(recording_from_row).collect()
+    }
+
+    pub async fn get_recording(&self, id: i64) -> Result<Recording> {
+        let row = sqlx::query(r#"SELECT * FROM recordings WHERE id = ?"#)
+            .bind(id)
+            .fetch_optional(self.pool())
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        recording_from_row(&row)
+    }
+
+    /// Replaces the snapshot a recording is replayed from
+    pub async fn set_recording_snapshot(&self, id: i64, snapshot: &str) -> Result<()> {
+        sqlx::query(
+            r#" UPDATE recordings
+                SET network = json_set(network, '$.snapshot', ?)
+                WHERE id = ? "#,
+        )
+        .bind(snapshot)
+        .bind(id)
+        .execute(self.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_recording(&self, id: i64) -> Result<()> {
+        sqlx::query(r#"DELETE FROM recordings WHERE id = ?"#)
+            .bind(id)
+            .execute(self.pool())
+            .await?;
+
+        Ok(())
+    }
+}
+
+fn recording_from_row(row: &SqliteRow) -> Result<Recording> {
+    Ok(Recording {
+        id: row.get("id"),
+        name: row.get("name"),
+        domain: row.get("domain"),
+        created_at: row.get::<i64, _>("created_at") as u64,
+        network: serde_json::from_str(row.get("network"))?,
+        steps: serde_json::from_str(row.get("steps"))?,
+    })
+}
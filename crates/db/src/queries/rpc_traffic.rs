@@ -0,0 +1,270 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use ethui_types::{Json, RpcTrafficEntry, RpcTrafficFilter};
+use serde_json::json;
+use sqlx::{sqlite::SqliteRow, QueryBuilder, Row, Sqlite};
+
+use crate::{DbInner, Result};
+
+/// Maximum number of recorded calls. Older ones are dropped as new ones come in
+const RPC_TRAFFIC_CAPACITY: i64 = 10_000;
+
+/// The buffer is trimmed back to capacity once every this many inserts, rather than on each one
+const PRUNE_INTERVAL: u64 = 500;
+
+/// Inserts since startup, to know when to prune
+static INSERTS: AtomicU64 = AtomicU64::new(0);
+
+/// Default number of entries returned when listing
+const DEFAULT_LIMIT: u32 = 500;
+
+impl DbInner {
+    /// Records a call, periodically dropping the oldest ones once the buffer is full
+    pub async fn insert_rpc_traffic(&self, entry: &RpcTrafficEntry) -> Result<()> {
+        sqlx::query(
+            r#" INSERT INTO rpc_traffic
+                    (timestamp, source, peer, origin, chain_id, url, method, params, result, error, latency_ms)
+                VALUES (?,?,?,?,?,?,?,?,?,?,?) "#,
+        )
+        .bind(entry.timestamp as i64)
+        .bind(entry.source.as_str())
+        .bind(&entry.peer)
+        .bind(&entry.origin)
+        .bind(entry.chain_id)
+        .bind(&entry.url)
+        .bind(&entry.method)
+        .bind(entry.params.to_string())
+        .bind(entry.result.as_ref().map(|r| r.to_string()))
+        .bind(entry.error.as_ref().map(|e| e.to_string()))
+        .bind(entry.latency_ms as i64)
+        .execute(self.pool())
+        .await?;
+
+        if INSERTS.fetch_add(1, Ordering::Relaxed) % PRUNE_INTERVAL == 0 {
+            self.prune_rpc_traffic().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Drops the oldest recorded calls beyond capacity
+    async fn prune_rpc_traffic(&self) -> Result<()> {
+        sqlx::query(
+            r#" DELETE FROM rpc_traffic
+                WHERE id <= (SELECT MAX(id) FROM rpc_traffic) - ? "#,
+        )
+        .bind(RPC_TRAFFIC_CAPACITY)
+        .execute(self.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lists recorded calls matching the filter, most recent first
+    pub async fn get_rpc_traffic(&self, filter: &RpcTrafficFilter) -> Result<Vec<RpcTrafficEntry>> {
+        let mut query: QueryBuilder<Sqlite> =
+            QueryBuilder::new("SELECT * FROM rpc_traffic WHERE 1=1");
+
+        if let Some(source) = filter.source {
+            query.push(" AND source = ").push_bind(source.as_str());
+        }
+        if let Some(ref origin) = filter.origin {
+            query.push(" AND origin = ").push_bind(origin);
+        }
+        if let Some(ref peer) = filter.peer {
+            query.push(" AND peer = ").push_bind(peer);
+        }
+        if let Some(chain_id) = filter.chain_id {
+            query.push(" AND chain_id = ").push_bind(chain_id);
+        }
+        if let Some(ref method) = filter.method {
+            query
+                .push(" AND method LIKE ")
+                .push_bind(format!("%{}%", method));
+        }
+        if filter.errors_only {
+            query.push(" AND error IS NOT NULL");
+        }
+        if let Some(since) = filter.since {
+            query.push(" AND timestamp >= ").push_bind(since as i64);
+        }
+        if let Some(until) = filter.until {
+            query.push(" AND timestamp <= ").push_bind(until as i64);
+        }
+
+        query
+            .push(" ORDER BY id DESC LIMIT ")
+            .push_bind(filter.limit.unwrap_or(DEFAULT_LIMIT));
+
+        let rows = query.build().fetch_all(self.pool()).await?;
+
+        Ok(rows.iter().filter_map(traffic_from_row).collect())
+    }
+
+    pub async fn clear_rpc_traffic(&self) -> Result<()> {
+        sqlx::query(r#"DELETE FROM rpc_traffic"#)
+            .execute(self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Exports recorded calls matching the filter as a HAR-like document
+    pub async fn export_rpc_traffic_har(&self, filter: &RpcTrafficFilter) -> Result<Json> {
+        let mut entries = self.get_rpc_traffic(filter).await?;
+
+        // HAR entries are in chronological order
+        entries.reverse();
+
+        Ok(traffic_to_har(&entries))
+    }
+}
+
+/// Rows with an unknown source are skipped, rather than failing the whole listing
+fn traffic_from_row(row: &SqliteRow) -> Option<RpcTrafficEntry> {
+    let json = |column: &str| {
+        row.get::<Option<String>, _>(column)
+            .and_then(|v| serde_json::from_str(&v).ok())
+    };
+
+    let source = match row.get::<String, _>("source").parse() {
+        Ok(source) => source,
+        Err(e) => {
+            tracing::warn!("skipping recorded RPC call: {}", e);
+            return None;
+        }
+    };
+
+    Some(RpcTrafficEntry {
+        id: row.get("id"),
+        timestamp: row.get::<i64, _>("timestamp") as u64,
+        source,
+        peer: row.get("peer"),
+        origin: row.get("origin"),
+        chain_id: row.get("chain_id"),
+        url: row.get("url"),
+        method: row.get("method"),
+        params: json("params").unwrap_or(Json::Null),
+        result: json("result"),
+        error: json("error"),
+        latency_ms: row.get::<i64, _>("latency_ms") as u64,
+    })
+}
+
+/// Each call becomes a HAR entry, with the JSON-RPC request and response as its bodies
+/// Fields HAR has no place for go in an `_ethui` extension field
+fn traffic_to_har(entries: &[RpcTrafficEntry]) -> Json {
+    let entries: Vec<_> = entries
+        .iter()
+        .map(|entry| {
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": entry.id,
+                "method": entry.method,
+                "params": entry.params,
+            });
+
+            let response = match entry.error {
+                Some(ref error) => json!({ "jsonrpc": "2.0", "id": entry.id, "error": error }),
+                None => json!({ "jsonrpc": "2.0", "id": entry.id, "result": entry.result }),
+            };
+
+            let url = entry.url.clone().unwrap_or_else(|| {
+                format!("ethui://{}", entry.origin.as_deref().unwrap_or("unknown"))
+            });
+
+            json!({
+                "startedDateTime": chrono::DateTime::from_timestamp_millis(entry.timestamp as i64)
+                    .unwrap_or_default()
+                    .to_rfc3339(),
+                "time": entry.latency_ms,
+                "request": {
+                    "method": "POST",
+                    "url": url,
+                    "httpVersion": "JSON-RPC/2.0",
+                    "headers": [],
+                    "queryString": [],
+                    "cookies": [],
+                    "headersSize": -1,
+                    "bodySize": -1,
+                    "postData": {
+                        "mimeType": "application/json",
+                        "text": request.to_string(),
+                    },
+                },
+                "response": {
+                    "status": 200,
+                    "statusText": if entry.error.is_some() { "Error" } else { "OK" },
+                    "httpVersion": "JSON-RPC/2.0",
+                    "headers": [],
+                    "cookies": [],
+                    "redirectURL": "",
+                    "headersSize": -1,
+                    "bodySize": -1,
+                    "content": {
+                        "size": -1,
+                        "mimeType": "application/json",
+                        "text": response.to_string(),
+                    },
+                },
+                "cache": {},
+                "timings": { "send": 0, "wait": entry.latency_ms, "receive": 0 },
+                "_ethui": {
+                    "source": entry.source,
+                    "peer": entry.peer,
+                    "origin": entry.origin,
+                    "chainId": entry.chain_id,
+                },
+            })
+        })
+        .collect();
+
+    json!({
+        "log": {
+            "version": "1.2",
+            "creator": {
+                "name": "ethui",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "entries": entries,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ethui_types::RpcTrafficSource;
+
+    use super::*;
+
+    #[test]
+    fn exports_calls_as_har_entries() {
+        let entry = RpcTrafficEntry {
+            id: 1,
+            timestamp: 0,
+            source: RpcTrafficSource::Upstream,
+            peer: None,
+            origin: Some("app.uniswap.org".into()),
+            chain_id: Some(1),
+            url: Some("http://localhost:8545/".into()),
+            method: "eth_call".into(),
+            params: json!([{ "to": "0x0000000000000000000000000000000000000000" }, "latest"]),
+            result: None,
+            error: Some(json!({ "code": 3, "message": "execution reverted" })),
+            latency_ms: 42,
+        };
+
+        let har = traffic_to_har(&[entry]);
+        let har_entry = &har["log"]["entries"][0];
+
+        assert_eq!(har_entry["startedDateTime"], "1970-01-01T00:00:00+00:00");
+        assert_eq!(har_entry["time"], 42);
+        assert_eq!(har_entry["request"]["url"], "http://localhost:8545/");
+        assert_eq!(har_entry["_ethui"]["source"], "upstream");
+
+        let response: Json =
+            serde_json::from_str(har_entry["response"]["content"]["text"].as_str().unwrap())
+                .unwrap();
+        assert_eq!(response["error"]["code"], 3);
+    }
+}
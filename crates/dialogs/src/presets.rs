@@ -29,6 +29,15 @@ pub(super) static PRESETS: Lazy<HashMap<String, Preset>> = Lazy::new(|| {
         },
     );
 
+    presets.insert(
+        "authorization-review".into(),
+        Preset {
+            title: "Review Delegation".into(),
+            w: 600.0,
+            h: 600.0,
+        },
+    );
+
     presets.insert(
         "wallet-unlock".into(),
         Preset {
@@ -38,6 +47,24 @@ pub(super) static PRESETS: Lazy<HashMap<String, Preset>> = Lazy::new(|| {
         },
     );
 
+    presets.insert(
+        "secrets-unlock".into(),
+        Preset {
+            title: "Unlock Network Secrets".into(),
+            w: 400.0,
+            h: 235.0,
+        },
+    );
+
+    presets.insert(
+        "ws-pair".into(),
+        Preset {
+            title: "Connect Client".into(),
+            w: 400.0,
+            h: 300.0,
+        },
+    );
+
     presets.insert(
         "chain-add".into(),
         Preset {
@@ -56,6 +83,15 @@ pub(super) static PRESETS: Lazy<HashMap<String, Preset>> = Lazy::new(|| {
         },
     );
 
+    presets.insert(
+        "session-create".into(),
+        Preset {
+            title: "Connect to Multiple Networks".into(),
+            w: 450.0,
+            h: 500.0,
+        },
+    );
+
     presets.insert(
         "erc20-add".into(),
         Preset {
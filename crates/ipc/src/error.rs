@@ -0,0 +1,10 @@
+#[derive(thiserror::Error, Debug)]
+pub enum IpcError {
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+
+    #[error("serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+pub type IpcResult<T> = std::result::Result<T, IpcError>;
@@ -0,0 +1,16 @@
+use std::path::PathBuf;
+
+/// Starts listening on the given socket path
+///
+/// Only supported on unix. Elsewhere, this is a no-op
+pub async fn init(path: PathBuf) {
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        if let Err(e) = crate::server::server_loop(path).await {
+            tracing::error!("IPC server failed: {}", e);
+        }
+    });
+
+    #[cfg(not(unix))]
+    tracing::warn!(?path, "IPC endpoint is only supported on unix");
+}
@@ -0,0 +1,18 @@
+//! Unix domain socket endpoint, similar to geth's `.ipc`
+//!
+//! Lets local tooling reach ethui without a TCP port, and without exposing the RPC handler to
+//! browser pages. Requests are served by the same `ethui_rpc::Handler` as the WebSocket server,
+//! under a domain-less context identified as `client:ipc`, with its own affinity and permissions
+
+mod error;
+mod init;
+#[cfg(unix)]
+mod server;
+
+pub use error::{IpcError, IpcResult};
+pub use init::init;
+
+/// Client name under which IPC connections store their affinity & permissions
+///
+/// Shares a namespace with domains, so it must never be a valid host name
+pub const CLIENT: &str = "client:ipc";
@@ -0,0 +1,200 @@
+use std::{
+    fs,
+    os::unix::fs::{DirBuilderExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+
+use jsonrpc_core::{ErrorCode, Failure, Id, Output, Request, Response, Version};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{UnixListener, UnixStream},
+};
+use tracing::debug;
+
+use crate::{IpcResult, CLIENT};
+
+/// Largest accepted message, in bytes
+const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+pub(crate) async fn server_loop(path: PathBuf) -> IpcResult<()> {
+    // a socket left behind by a previous run would prevent binding
+    if path.exists() {
+        fs::remove_file(&path)?;
+    }
+
+    let listener = bind(&path)?;
+
+    debug!(?path, "IPC server listening");
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream).await {
+                debug!("IPC connection closed: {}", e);
+            }
+        });
+    }
+}
+
+/// Binds the socket so that only the current user may ever connect to it
+///
+/// The socket is created inside a private directory, restricted, and only then moved into
+/// place, so it's never reachable with the default permissions
+fn bind(path: &Path) -> IpcResult<UnixListener> {
+    let dir = path.with_extension("ipc.d");
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::DirBuilder::new().mode(0o700).create(&dir)?;
+
+    let tmp = dir.join("socket");
+    let result = UnixListener::bind(&tmp).and_then(|listener| {
+        fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600))?;
+        fs::rename(&tmp, path)?;
+        Ok(listener)
+    });
+
+    fs::remove_dir_all(&dir)?;
+    Ok(result?)
+}
+
+/// Requests are handled one at a time, in the order they arrive
+///
+/// Messages don't need to be delimited. Complete JSON values are extracted from the stream as
+/// they come in. Replies are newline-delimited
+async fn handle_connection(mut stream: UnixStream) -> IpcResult<()> {
+    let handler = ethui_rpc::Handler::for_client(CLIENT);
+    let mut buf = Vec::new();
+
+    loop {
+        if stream.read_buf(&mut buf).await? == 0 {
+            return Ok(());
+        }
+
+        let (requests, consumed) = split(&buf);
+        buf.drain(..consumed);
+
+        for request in requests {
+            let response = match request {
+                Ok(request) => handler.handle(request).await,
+                Err(e) => Some(failure(e)),
+            };
+
+            // notifications get no reply
+            if let Some(response) = response {
+                write(&mut stream, &response).await?;
+            }
+        }
+
+        if buf.len() > MAX_MESSAGE_SIZE {
+            let error = jsonrpc_core::Error {
+                code: ErrorCode::InvalidRequest,
+                message: format!("message exceeds {} bytes", MAX_MESSAGE_SIZE),
+                data: None,
+            };
+            write(&mut stream, &failure(error)).await?;
+
+            return Ok(());
+        }
+    }
+}
+
+/// Extracts all complete messages from the buffer
+/// Returns them, along with the number of bytes consumed
+fn split(buf: &[u8]) -> (Vec<Result<Request, jsonrpc_core::Error>>, usize) {
+    let mut stream = serde_json::Deserializer::from_slice(buf).into_iter::<serde_json::Value>();
+    let mut messages = vec![];
+
+    let consumed = loop {
+        match stream.next() {
+            Some(Ok(value)) => messages.push(
+                serde_json::from_value(value).map_err(|_| jsonrpc_core::Error::invalid_request()),
+            ),
+
+            // incomplete message. wait for more data
+            Some(Err(e)) if e.is_eof() => break stream.byte_offset(),
+
+            // there's no telling where the next message starts, so everything is discarded
+            Some(Err(_)) => {
+                messages.push(Err(jsonrpc_core::Error::parse_error()));
+                break buf.len();
+            }
+
+            None => break stream.byte_offset(),
+        }
+    };
+
+    (messages, consumed)
+}
+
+fn failure(error: jsonrpc_core::Error) -> Response {
+    Response::Single(Output::Failure(Failure {
+        jsonrpc: Some(Version::V2),
+        error,
+        id: Id::Null,
+    }))
+}
+
+async fn write(stream: &mut UnixStream, response: &Response) -> IpcResult<()> {
+    let mut bytes = serde_json::to_vec(response)?;
+    bytes.push(b'\n');
+    stream.write_all(&bytes).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALL: &str = r#"{"jsonrpc":"2.0","id":1,"method":"eth_chainId"}"#;
+
+    #[test]
+    fn splits_undelimited_messages() {
+        let buf = format!("{}{}\n{}", CALL, CALL, &CALL[..10]);
+
+        let (messages, consumed) = split(buf.as_bytes());
+
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().all(|m| m.is_ok()));
+        assert_eq!(buf[consumed..].trim_start(), &CALL[..10]);
+    }
+
+    #[test]
+    fn discards_malformed_input() {
+        let buf = format!("{}not json{}", CALL, CALL);
+
+        let (messages, consumed) = split(buf.as_bytes());
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[1].as_ref().unwrap_err().code,
+            ErrorCode::ParseError
+        );
+        assert_eq!(consumed, buf.len());
+    }
+
+    #[tokio::test]
+    async fn binds_socket_private_to_the_user() {
+        let path = std::env::temp_dir().join(format!("ethui-test-{}.ipc", std::process::id()));
+
+        let _listener = bind(&path).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!path.with_extension("ipc.d").exists());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_requests() {
+        let (messages, _) = split(b"42");
+
+        assert_eq!(
+            messages[0].as_ref().unwrap_err().code,
+            ErrorCode::InvalidRequest
+        );
+    }
+}
@@ -1,19 +1,24 @@
 use ethui_types::{GlobalState, Network, NewNetworkParams};
 
-use super::{Networks, Result};
+use super::{Networks, Result, SecretsInfo};
 
 #[tauri::command]
 pub async fn networks_get_current() -> Result<Network> {
     let networks = Networks::read().await;
 
-    Ok(networks.get_current().clone())
+    Ok(networks.get_current().redacted())
 }
 
 #[tauri::command]
 pub async fn networks_get_list() -> Result<Vec<Network>> {
     let networks = Networks::read().await;
 
-    Ok(networks.inner.networks.values().cloned().collect())
+    Ok(networks
+        .inner
+        .networks
+        .values()
+        .map(Network::redacted)
+        .collect())
 }
 
 #[tauri::command]
@@ -22,7 +27,7 @@ pub async fn networks_set_current(network: String) -> Result<Network> {
 
     networks.set_current_by_name(network).await?;
 
-    Ok(networks.get_current().clone())
+    Ok(networks.get_current().redacted())
 }
 
 #[tauri::command]
@@ -52,3 +57,30 @@ pub async fn networks_chain_id_from_provider(url: String) -> Result<u64> {
 
     networks.chain_id_from_provider(url).await
 }
+
+#[tauri::command]
+pub async fn networks_get_secrets() -> SecretsInfo {
+    Networks::read().await.secrets_info()
+}
+
+#[tauri::command]
+pub async fn networks_unlock_secrets(password: String) -> Result<()> {
+    Networks::unlock_secrets(&password).await
+}
+
+#[tauri::command]
+pub async fn networks_lock_secrets() {
+    Networks::write().await.lock_secrets().await
+}
+
+#[tauri::command]
+pub async fn networks_set_secret(name: String, value: String, password: String) -> Result<()> {
+    let mut networks = Networks::write().await;
+    networks.set_secret(name, value, &password).await
+}
+
+#[tauri::command]
+pub async fn networks_remove_secret(name: String, password: String) -> Result<()> {
+    let mut networks = Networks::write().await;
+    networks.remove_secret(&name, &password).await
+}
@@ -1,4 +1,5 @@
 use alloy::transports::{RpcError, TransportErrorKind};
+use ethui_types::redact_urls;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -28,6 +29,24 @@ pub enum Error {
 
     #[error("Not exists")]
     NotExists,
+
+    #[error("Network must have at least one RPC endpoint")]
+    NoEndpoints,
+
+    #[error("Invalid password")]
+    InvalidPassword,
+
+    #[error("Failed to encrypt secrets: {0}")]
+    Encryption(String),
+
+    #[error("Secrets are locked")]
+    SecretsLocked,
+
+    #[error("Unknown secret: {0}")]
+    UnknownSecret(String),
+
+    #[error("Secret {0} is still used by network {1}")]
+    SecretInUse(String, String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -37,6 +56,7 @@ impl serde::Serialize for Error {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(self.to_string().as_ref())
+        // transport errors may include the URL they failed to reach, secrets and all
+        serializer.serialize_str(&redact_urls(&self.to_string()))
     }
 }
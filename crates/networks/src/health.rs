@@ -0,0 +1,156 @@
+//! Background health checks of RPC endpoints
+//!
+//! Endpoints of the current network, and of any network used recently, are periodically asked
+//! for their latest block. The results are kept in each endpoint's `EndpointHealth`, which
+//! `Network::get_provider` relies on to pick a healthy one. Idle networks aren't checked, so
+//! that public RPCs nobody is using don't get constant background traffic
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use alloy::{
+    providers::{Provider as _, ProviderBuilder},
+    rpc::client::ClientBuilder,
+};
+use ethui_types::{EndpointHealth, EndpointSecret, GlobalState, RpcEndpoint};
+use tokio::{task::JoinSet, time::timeout};
+use url::Url;
+
+use crate::Networks;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Networks that had no client built for them in this long are no longer checked
+const IDLE_AFTER: Duration = Duration::from_secs(300);
+
+/// How many of the most recent checks the error rate is computed over
+const ERROR_RATE_WINDOW: usize = 20;
+
+/// Outcome of a single check: the latest block, and how long it took to get it
+type Check = Option<(u64, u64)>;
+
+/// Identifies an endpoint. Endpoints that share a URL but authenticate with different secrets
+/// may well be in different health, e.g. if one of the keys ran out of credits
+pub(crate) type EndpointKey = (Url, Vec<EndpointSecret>);
+
+pub(crate) fn key(endpoint: &RpcEndpoint) -> EndpointKey {
+    (endpoint.url.clone(), endpoint.secrets.clone())
+}
+
+pub(crate) async fn monitor() -> ! {
+    let mut history: HashMap<EndpointKey, VecDeque<Check>> = HashMap::new();
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let endpoints: HashMap<EndpointKey, RpcEndpoint> = {
+            let networks = Networks::read().await;
+            let current = networks.get_current().dedup_chain_id();
+
+            networks
+                .inner
+                .networks
+                .values()
+                .filter(|n| n.dedup_chain_id() == current || n.used_within(IDLE_AFTER))
+                .flat_map(|n| n.endpoints.iter())
+                // checking them without their secrets would only count against their health
+                .filter(|e| !e.is_locked())
+                .map(|e| (key(e), e.clone()))
+                .collect()
+        };
+        history.retain(|key, _| endpoints.contains_key(key));
+
+        let mut set = JoinSet::new();
+        for endpoint in endpoints.into_values() {
+            set.spawn(check(endpoint));
+        }
+
+        while let Some(res) = set.join_next().await {
+            let Ok((key, check)) = res else {
+                continue;
+            };
+
+            let checks = history.entry(key).or_default();
+            checks.push_back(check);
+            if checks.len() > ERROR_RATE_WINDOW {
+                checks.pop_front();
+            }
+        }
+
+        let health = history
+            .iter()
+            .map(|(key, checks)| (key.clone(), health(checks)))
+            .collect();
+
+        Networks::write().await.set_endpoints_health(&health).await;
+    }
+}
+
+async fn check(endpoint: RpcEndpoint) -> (EndpointKey, Check) {
+    let client = ClientBuilder::default().transport(endpoint.transport(), false);
+    let provider = ProviderBuilder::new()
+        .disable_recommended_fillers()
+        .on_client(client);
+
+    let started = Instant::now();
+    let res = match timeout(CHECK_TIMEOUT, provider.get_block_number()).await {
+        Ok(Ok(block)) => Some((block, started.elapsed().as_millis() as u64)),
+        _ => None,
+    };
+
+    (key(&endpoint), res)
+}
+
+/// Summarizes recent checks, most recent last. Block and latency come from the most recent
+/// successful one
+fn health(checks: &VecDeque<Check>) -> EndpointHealth {
+    let failures = checks.iter().filter(|c| c.is_none()).count();
+    let last_success = checks.iter().rev().find_map(|c| *c);
+
+    EndpointHealth {
+        block_number: last_success.map(|(block, _)| block),
+        latency_ms: last_success.map(|(_, latency)| latency),
+        error_rate: failures as f64 / checks.len().max(1) as f64,
+        checked_at: Some(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_recent_checks() {
+        let checks: VecDeque<Check> = vec![Some((10, 30)), None, Some((12, 40)), None].into();
+        let health = health(&checks);
+
+        assert_eq!(health.block_number, Some(12));
+        assert_eq!(health.latency_ms, Some(40));
+        assert_eq!(health.error_rate, 0.5);
+    }
+
+    #[test]
+    fn tells_endpoints_apart_by_their_secrets() {
+        let url: Url = "https://rpc.test/v2".parse().unwrap();
+        let with_key = |secret: &str| {
+            let mut endpoint = RpcEndpoint::new(url.clone(), 0);
+            endpoint.secrets = vec![EndpointSecret::Path {
+                secret: secret.into(),
+            }];
+            endpoint
+        };
+
+        assert_eq!(key(&with_key("a")), key(&with_key("a")));
+        assert_ne!(key(&with_key("a")), key(&with_key("b")));
+        assert_ne!(key(&with_key("a")), key(&RpcEndpoint::new(url, 0)));
+    }
+}
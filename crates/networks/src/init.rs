@@ -7,9 +7,7 @@ use once_cell::sync::OnceCell;
 use serde_constant::ConstI64;
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-use crate::migrations::load_and_migrate;
-use crate::Networks;
-use crate::SerializedNetworks;
+use crate::{health, migrations::load_and_migrate, Networks, SerializedNetworks};
 
 static NETWORKS: OnceCell<RwLock<Networks>> = OnceCell::new();
 
@@ -27,7 +25,9 @@ pub async fn init(pathbuf: PathBuf) {
                 networks: networks.into_iter().map(|n| (n.name.clone(), n)).collect(),
                 current,
                 version: ConstI64,
+                secrets: None,
             },
+            unlocked: None,
             file: pathbuf,
         }
     };
@@ -37,6 +37,7 @@ pub async fn init(pathbuf: PathBuf) {
     NETWORKS.set(RwLock::new(res)).unwrap();
 
     tokio::spawn(async { receiver().await });
+    tokio::spawn(async { health::monitor().await });
 }
 
 #[async_trait]
@@ -57,7 +58,7 @@ async fn receiver() -> ! {
         if let Ok(msg) = rx.recv().await {
             use InternalMsg::*;
 
-            if let ChainChanged(dedup_chain_id, _domain, affinity) = msg {
+            if let ChainChanged(dedup_chain_id, _domain, _tab_id, affinity) = msg {
                 ethui_broadcast::ui_notify(UINotify::PeersUpdated).await;
                 if affinity.is_global() || affinity.is_unset() {
                     // TODO: handle this error
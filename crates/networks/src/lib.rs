@@ -1,7 +1,10 @@
 pub mod commands;
 mod error;
+mod health;
 mod init;
 mod migrations;
+pub mod probe;
+mod secrets;
 
 use std::{
     collections::HashMap,
@@ -13,12 +16,19 @@ use alloy::{
     network::Ethereum,
     providers::{Provider, ProviderBuilder, RootProvider},
 };
-use ethui_types::{Affinity, DedupChainId, Network, NewNetworkParams, UINotify};
+use ethui_crypto::EncryptedData;
+use ethui_types::{
+    Affinity, DedupChainId, EndpointHealth, Network, NewNetworkParams, SecretValues, UINotify,
+};
 pub use init::init;
 use migrations::LatestVersion;
 use serde::{Deserialize, Serialize};
+use url::Url;
 
-pub use self::error::{Error, Result};
+pub use self::{
+    error::{Error, Result},
+    secrets::SecretsInfo,
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SerializedNetworks {
@@ -28,12 +38,19 @@ pub struct SerializedNetworks {
 
     // global affinity will point to the current network
     pub current: String,
+
+    /// secrets referenced by network endpoints, encrypted with a user-chosen password
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<EncryptedData<SecretValues>>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Networks {
     pub inner: SerializedNetworks,
 
+    /// decrypted `inner.secrets`, once unlocked
+    unlocked: Option<SecretValues>,
+
     file: PathBuf,
 }
 
@@ -107,6 +124,10 @@ impl Networks {
         &self.inner.networks[&self.inner.current]
     }
 
+    pub fn get_all(&self) -> impl Iterator<Item = &Network> {
+        self.inner.networks.values()
+    }
+
     pub fn get_network(&self, chain_id: u32) -> Option<Network> {
         self.inner
             .networks
@@ -115,6 +136,23 @@ impl Networks {
             .cloned()
     }
 
+    /// All networks with the given chain id, e.g. several local anvil nodes
+    pub fn get_networks_by_chain_id(&self, chain_id: u32) -> impl Iterator<Item = Network> + '_ {
+        self.inner
+            .networks
+            .values()
+            .filter(move |n| n.chain_id() == chain_id)
+            .cloned()
+    }
+
+    pub fn get_network_by_dedup_chain_id(&self, dedup_chain_id: DedupChainId) -> Option<Network> {
+        self.inner
+            .networks
+            .values()
+            .find(|n| n.dedup_chain_id() == dedup_chain_id)
+            .cloned()
+    }
+
     pub fn get_network_by_name(&self, name: &str) -> Option<Network> {
         self.inner
             .networks
@@ -129,13 +167,47 @@ impl Networks {
             return Err(Error::AlreadyExists);
         }
 
+        let deduplication_id = self.get_chain_id_count(network.dedup_chain_id.chain_id());
+        self.insert_network(network.into_network(deduplication_id))
+            .await
+    }
+
+    /// Adds a network alongside existing ones with the same chain id, under a new dedup id
+    pub async fn add_duplicate_network(
+        &mut self,
+        network: NewNetworkParams,
+    ) -> Result<DedupChainId> {
+        let chain_id = network.dedup_chain_id.chain_id();
+        let deduplication_id = self
+            .get_networks_by_chain_id(chain_id)
+            .map(|n| n.dedup_chain_id().dedup_id() + 1)
+            .max()
+            .unwrap_or(0);
+
+        let network = network.into_network(deduplication_id);
+        let dedup_chain_id = network.dedup_chain_id();
+        self.insert_network(network).await?;
+
+        Ok(dedup_chain_id)
+    }
+
+    /// Adds an RPC endpoint to an existing network, as its least preferred one
+    pub async fn add_http_url(&mut self, name: &str, url: Url) -> Result<()> {
+        let mut network = self.get_network_by_name(name).ok_or(Error::NotExists)?;
+
+        if network.endpoints.iter().any(|e| probe::same_rpc(&e.url, &url)) {
+            return Ok(());
+        }
+
+        network.add_endpoint(url);
+        self.update_network(name, network).await
+    }
+
+    async fn insert_network(&mut self, network: Network) -> Result<()> {
         if self.inner.networks.contains_key(&network.name) {
             return Err(Error::AlreadyExists);
         }
 
-        let deduplication_id = self.get_chain_id_count(network.dedup_chain_id.chain_id());
-        let network = network.into_network(deduplication_id);
-
         self.inner
             .networks
             .insert(network.name.clone(), network.clone());
@@ -147,7 +219,14 @@ impl Networks {
         Ok(())
     }
 
-    pub async fn update_network(&mut self, old_name: &str, network: Network) -> Result<()> {
+    pub async fn update_network(&mut self, old_name: &str, mut network: Network) -> Result<()> {
+        if network.endpoints.is_empty() {
+            return Err(Error::NoEndpoints);
+        }
+
+        self.restore_redacted(old_name, &mut network);
+        self.unlock_network(&mut network)?;
+
         if network.name != old_name && self.inner.networks.contains_key(&network.name) {
             return Err(Error::AlreadyExists);
         }
@@ -169,6 +248,34 @@ impl Networks {
         Ok(())
     }
 
+    /// Updates endpoints with the latest results of the health monitor
+    ///
+    /// Endpoints it no longer checks are reset to unchecked, rather than judged on stale
+    /// results. Not persisted, since health is only meaningful while running
+    pub(crate) async fn set_endpoints_health(
+        &mut self,
+        results: &HashMap<health::EndpointKey, EndpointHealth>,
+    ) {
+        let mut changed = false;
+
+        for network in self.inner.networks.values_mut() {
+            let previous = network.http_url().ok();
+
+            for endpoint in network.endpoints.iter_mut() {
+                endpoint.health = results
+                    .get(&health::key(endpoint))
+                    .cloned()
+                    .unwrap_or_default();
+            }
+
+            changed |= network.http_url().ok() != previous;
+        }
+
+        if changed {
+            ethui_broadcast::ui_notify(UINotify::NetworksChanged).await;
+        }
+    }
+
     pub async fn remove_network(&mut self, name: &str) -> Result<()> {
         let network = self.inner.networks.remove(name);
 
@@ -190,8 +297,8 @@ impl Networks {
         Ok(())
     }
 
-    pub fn get_current_provider(&self) -> RootProvider<Ethereum> {
-        self.get_current().get_provider()
+    pub fn get_current_provider(&self) -> Result<RootProvider<Ethereum>> {
+        Ok(self.get_current().get_provider()?)
     }
 
     pub async fn chain_id_from_provider(&self, url: String) -> Result<u64> {
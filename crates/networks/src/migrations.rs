@@ -1,4 +1,4 @@
-use ethui_types::Network;
+use ethui_types::{DedupChainId, Network, RpcEndpoint};
 use serde::{Deserialize, Serialize};
 use serde_constant::ConstI64;
 use serde_json::json;
@@ -13,7 +13,7 @@ use url::Url;
 use crate::Result;
 use crate::{Networks, SerializedNetworks};
 
-pub type LatestVersion = ConstI64<3>;
+pub type LatestVersion = ConstI64<4>;
 
 #[derive(Debug, Deserialize, Serialize)]
 struct SerializedNetworksV0 {
@@ -36,13 +36,21 @@ struct SerializedNetworksV2 {
     version: ConstI64<2>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+struct SerializedNetworksV3 {
+    current: String,
+    networks: HashMap<String, NetworkV3>,
+    version: ConstI64<3>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(untagged)]
 enum Versions {
     V0(SerializedNetworksV0),
     V1(SerializedNetworksV1),
     V2(SerializedNetworksV2),
-    V3(SerializedNetworks),
+    V3(SerializedNetworksV3),
+    V4(SerializedNetworks),
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -68,6 +76,19 @@ pub struct NetworkV1 {
     pub decimals: u32,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NetworkV3 {
+    pub dedup_chain_id: DedupChainId,
+    pub name: String,
+    pub explorer_url: Option<String>,
+    pub http_url: Url,
+    #[serde(default)]
+    pub extra_http_urls: Vec<Url>,
+    pub ws_url: Option<Url>,
+    pub currency: String,
+    pub decimals: u32,
+}
+
 pub(crate) fn load_and_migrate(pathbuf: &PathBuf) -> Result<Networks> {
     let path = Path::new(&pathbuf);
     let file = File::open(path)?;
@@ -81,8 +102,12 @@ pub(crate) fn load_and_migrate(pathbuf: &PathBuf) -> Result<Networks> {
 
     let networks: Versions = serde_json::from_value(networks)?;
 
+    let mut inner = run_migrations(networks);
+    avoid_current_without_endpoints(&mut inner);
+
     let networks = Networks {
-        inner: run_migrations(networks),
+        inner,
+        unlocked: None,
         file: path.to_path_buf(),
     };
 
@@ -111,12 +136,45 @@ fn run_migrations(networks: Versions) -> SerializedNetworks {
 
             run_migrations(v2)
         }
-        Versions::V2(v2) => SerializedNetworks {
-            current: v2.current,
-            networks: migrate_networks_from_v2_to_v3(v2.networks),
+        Versions::V2(v2) => {
+            let v3 = Versions::V3(SerializedNetworksV3 {
+                current: v2.current,
+                networks: migrate_networks_from_v2_to_v3(v2.networks),
+                version: ConstI64,
+            });
+
+            run_migrations(v3)
+        }
+        Versions::V3(v3) => SerializedNetworks {
+            current: v3.current,
+            networks: migrate_networks_from_v3_to_v4(v3.networks),
             version: ConstI64,
+            secrets: None,
         },
-        Versions::V3(latest) => latest,
+        Versions::V4(latest) => latest,
+    }
+}
+
+/// Networks edited by hand may be left with no endpoints. They're kept, so that the user can add
+/// one again, but requests made on them fail. To not leave every connection in that state, the
+/// current network is moved to one that can be reached, if any
+fn avoid_current_without_endpoints(networks: &mut SerializedNetworks) {
+    let usable = |n: &Network| !n.endpoints.is_empty();
+
+    if networks.networks.get(&networks.current).is_some_and(usable) {
+        return;
+    }
+
+    let mut names: Vec<_> = networks
+        .networks
+        .values()
+        .filter(|n| usable(n))
+        .map(|n| n.name.clone())
+        .collect();
+    names.sort();
+
+    if let Some(name) = names.into_iter().next() {
+        networks.current = name;
     }
 }
 
@@ -146,17 +204,47 @@ fn migrate_networks_from_v1_to_v2(
 
 fn migrate_networks_from_v2_to_v3(
     networks: HashMap<String, NetworkV1>,
-) -> HashMap<String, Network> {
+) -> HashMap<String, NetworkV3> {
     networks
         .into_iter()
         .map(|(name, network)| {
             (
                 name,
-                Network {
+                NetworkV3 {
                     dedup_chain_id: (network.chain_id, network.deduplication_id).into(),
                     name: network.name,
                     explorer_url: network.explorer_url,
                     http_url: network.http_url,
+                    extra_http_urls: vec![],
+                    ws_url: network.ws_url,
+                    currency: network.currency,
+                    decimals: network.decimals,
+                },
+            )
+        })
+        .collect::<HashMap<String, NetworkV3>>()
+}
+
+/// The single `http_url` becomes the preferred endpoint, followed by any extra ones in order
+fn migrate_networks_from_v3_to_v4(
+    networks: HashMap<String, NetworkV3>,
+) -> HashMap<String, Network> {
+    networks
+        .into_iter()
+        .map(|(name, network)| {
+            let endpoints = std::iter::once(network.http_url)
+                .chain(network.extra_http_urls)
+                .enumerate()
+                .map(|(priority, url)| RpcEndpoint::new(url, priority as u32))
+                .collect();
+
+            (
+                name,
+                Network {
+                    dedup_chain_id: network.dedup_chain_id,
+                    name: network.name,
+                    explorer_url: network.explorer_url,
+                    endpoints,
                     ws_url: network.ws_url,
                     currency: network.currency,
                     decimals: network.decimals,
@@ -168,6 +256,7 @@ fn migrate_networks_from_v2_to_v3(
 
 #[cfg(test)]
 mod tests {
+    use ethui_types::RpcEndpoint;
     use serde_json::json;
     use std::{
         fs::File,
@@ -180,7 +269,7 @@ mod tests {
     use super::load_and_migrate;
 
     #[test]
-    fn it_converts_from_v0_to_v3() {
+    fn it_converts_from_v0_to_v4() {
         let mut tempfile = NamedTempFile::new().unwrap();
 
         let networks_v0 = json!({
@@ -205,15 +294,15 @@ mod tests {
             let reader = BufReader::new(file);
 
             let updated_networks: serde_json::Value = serde_json::from_reader(reader).unwrap();
-            assert_eq!(updated_networks["version"], 3);
+            assert_eq!(updated_networks["version"], 4);
         }
     }
 
     #[test]
-    fn it_returns_v3_from_v3() {
+    fn it_migrates_v3_urls_to_endpoints() {
         let mut tempfile = NamedTempFile::new().unwrap();
 
-        let networks_v0 = json!({
+        let networks_v3 = json!({
             "version": 3,
             "current": "Anvil",
             "networks": {
@@ -222,6 +311,7 @@ mod tests {
                     "name": "Mainnet",
                     "explorer_url": "https://etherscan.io/search?q=",
                     "http_url": "https://eth.llamarpc.com/",
+                    "extra_http_urls": ["https://rpc.example.com/"],
                     "ws_url": null,
                     "currency": "ETH",
                     "decimals": 18
@@ -229,15 +319,85 @@ mod tests {
             }
         });
 
-        write!(tempfile, "{}", networks_v0).unwrap();
+        write!(tempfile, "{}", networks_v3).unwrap();
 
-        if let Ok(_networks) = load_and_migrate(&tempfile.path().to_path_buf()) {
-            let file = File::open(tempfile.path()).unwrap();
-            let reader = BufReader::new(file);
+        let networks = load_and_migrate(&tempfile.path().to_path_buf()).unwrap();
+        let mainnet = networks.get_network_by_name("Mainnet").unwrap();
 
-            let updated_networks: serde_json::Value = serde_json::from_reader(reader).unwrap();
-            assert_eq!(updated_networks["version"], 3);
-        }
+        assert_eq!(serde_json::to_value(&networks.inner).unwrap()["version"], 4);
+        assert_eq!(
+            mainnet.endpoints,
+            vec![
+                RpcEndpoint::new("https://eth.llamarpc.com/".parse().unwrap(), 0),
+                RpcEndpoint::new("https://rpc.example.com/".parse().unwrap(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_returns_v4_from_v4() {
+        let mut tempfile = NamedTempFile::new().unwrap();
+
+        let networks_v4 = json!({
+            "version": 4,
+            "current": "Anvil",
+            "networks": {
+                "Anvil": {
+                    "dedup_chain_id": {"chain_id": 31337, "dedup_id": 0},
+                    "name": "Anvil",
+                    "explorer_url": null,
+                    "endpoints": [{"url": "http://localhost:8545/", "priority": 0}],
+                    "ws_url": null,
+                    "currency": "ETH",
+                    "decimals": 18
+                },
+            }
+        });
+
+        write!(tempfile, "{}", networks_v4).unwrap();
+
+        let networks = load_and_migrate(&tempfile.path().to_path_buf()).unwrap();
+        let anvil = networks.get_network_by_name("Anvil").unwrap();
+
+        assert_eq!(anvil.http_url().unwrap().as_str(), "http://localhost:8545/");
+    }
+
+    #[test]
+    fn it_keeps_networks_without_endpoints() {
+        let mut tempfile = NamedTempFile::new().unwrap();
+
+        let networks_v4 = json!({
+            "version": 4,
+            "current": "Broken",
+            "networks": {
+                "Anvil": {
+                    "dedup_chain_id": {"chain_id": 31337, "dedup_id": 0},
+                    "name": "Anvil",
+                    "explorer_url": null,
+                    "endpoints": [{"url": "http://localhost:8545/", "priority": 0}],
+                    "ws_url": null,
+                    "currency": "ETH",
+                    "decimals": 18
+                },
+                "Broken": {
+                    "dedup_chain_id": {"chain_id": 1, "dedup_id": 0},
+                    "name": "Broken",
+                    "explorer_url": null,
+                    "endpoints": [],
+                    "ws_url": null,
+                    "currency": "ETH",
+                    "decimals": 18
+                },
+            }
+        });
+
+        write!(tempfile, "{}", networks_v4).unwrap();
+
+        let networks = load_and_migrate(&tempfile.path().to_path_buf()).unwrap();
+        let broken = networks.get_network_by_name("Broken").unwrap();
+
+        assert!(broken.http_url().is_err());
+        assert_eq!(networks.inner.current, "Anvil");
     }
 
     #[test]
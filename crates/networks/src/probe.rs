@@ -0,0 +1,168 @@
+//! Probing of RPC endpoints, before they're trusted to serve a network
+
+use std::time::{Duration, Instant};
+
+use alloy::providers::{ext::AnvilApi as _, Provider as _, ProviderBuilder};
+use serde::Serialize;
+use tokio::{task::JoinSet, time::timeout};
+use url::Url;
+
+/// How long an endpoint has to answer each probing request
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcProbe {
+    pub url: Url,
+
+    /// reported by `eth_chainId`. `None` if the endpoint couldn't be reached
+    pub chain_id: Option<u64>,
+
+    /// round trip of the `eth_chainId` request
+    pub latency_ms: Option<u64>,
+
+    /// whether the endpoint answers `anvil_nodeInfo`
+    pub anvil: bool,
+}
+
+/// Probes a single HTTP endpoint
+pub async fn probe(url: Url) -> RpcProbe {
+    let provider = ProviderBuilder::new()
+        .disable_recommended_fillers()
+        .on_http(url.clone());
+
+    let started = Instant::now();
+    let chain_id = match timeout(PROBE_TIMEOUT, provider.get_chain_id()).await {
+        Ok(Ok(chain_id)) => Some(chain_id),
+        _ => None,
+    };
+    let latency_ms = chain_id.map(|_| started.elapsed().as_millis() as u64);
+
+    let anvil = chain_id.is_some()
+        && matches!(
+            timeout(PROBE_TIMEOUT, provider.anvil_node_info()).await,
+            Ok(Ok(_))
+        );
+
+    RpcProbe {
+        url,
+        chain_id,
+        latency_ms,
+        anvil,
+    }
+}
+
+/// Probes all HTTP endpoints concurrently. Others, such as WebSockets, are ignored
+pub async fn probe_all(urls: impl IntoIterator<Item = Url>) -> Vec<RpcProbe> {
+    let mut set = JoinSet::new();
+
+    for url in urls.into_iter().filter(|u| u.scheme().starts_with("http")) {
+        set.spawn(probe(url));
+    }
+
+    let mut probes = vec![];
+    while let Some(res) = set.join_next().await {
+        if let Ok(probe) = res {
+            probes.push(probe);
+        }
+    }
+
+    probes
+}
+
+/// The endpoint that answered with a different chain id, if any
+pub fn mismatched(probes: &[RpcProbe], chain_id: u64) -> Option<&RpcProbe> {
+    probes
+        .iter()
+        .find(|p| p.chain_id.is_some_and(|id| id != chain_id))
+}
+
+/// The fastest endpoint serving the given chain id
+pub fn fastest(probes: &[RpcProbe], chain_id: u64) -> Option<&RpcProbe> {
+    probes
+        .iter()
+        .filter(|p| p.chain_id == Some(chain_id))
+        .min_by_key(|p| p.latency_ms)
+}
+
+/// Whether two URLs point at the same RPC, ignoring differences that don't change where
+/// requests go, such as a trailing slash or an explicit default port
+pub fn same_rpc(a: &Url, b: &Url) -> bool {
+    normalize(a) == normalize(b)
+}
+
+/// A URL without a trailing slash or fragment. Parsing already lowercases the scheme and host,
+/// and drops default ports
+fn normalize(url: &Url) -> Url {
+    let mut url = url.clone();
+    let path = url.path().trim_end_matches('/').to_owned();
+
+    url.set_path(&path);
+    url.set_fragment(None);
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(url: &str, chain_id: Option<u64>, latency_ms: Option<u64>) -> RpcProbe {
+        RpcProbe {
+            url: url.parse().unwrap(),
+            chain_id,
+            latency_ms,
+            anvil: false,
+        }
+    }
+
+    #[test]
+    fn picks_fastest_matching_endpoint() {
+        let probes = vec![
+            probe("http://slow.test", Some(10), Some(300)),
+            probe("http://down.test", None, None),
+            probe("http://fast.test", Some(10), Some(20)),
+        ];
+
+        assert_eq!(
+            fastest(&probes, 10).unwrap().url.as_str(),
+            "http://fast.test/"
+        );
+        assert_eq!(mismatched(&probes, 10), None);
+        assert_eq!(fastest(&probes, 1), None);
+    }
+
+    #[test]
+    fn finds_mismatched_endpoints() {
+        let probes = vec![
+            probe("http://right.test", Some(10), Some(30)),
+            probe("http://wrong.test", Some(1), Some(10)),
+        ];
+
+        assert_eq!(
+            mismatched(&probes, 10).unwrap().url.as_str(),
+            "http://wrong.test/"
+        );
+    }
+
+    #[test]
+    fn compares_normalized_urls() {
+        let url = |s: &str| s.parse::<Url>().unwrap();
+
+        assert!(same_rpc(
+            &url("https://RPC.example.com:443/v1/"),
+            &url("https://rpc.example.com/v1")
+        ));
+        assert!(same_rpc(
+            &url("http://localhost:8545"),
+            &url("http://localhost:8545/")
+        ));
+        assert!(!same_rpc(
+            &url("http://localhost:8545"),
+            &url("http://localhost:8546")
+        ));
+        assert!(!same_rpc(
+            &url("https://rpc.example.com/v1?key=a"),
+            &url("https://rpc.example.com/v1?key=b")
+        ));
+    }
+}
@@ -1,6 +1,14 @@
 use alloy::primitives::Bytes;
+use ethui_connections::Ctx;
+use ethui_types::{Address, AddressLabel, Recording};
+use serde_json::json;
 
-use super::{Handler, Result};
+use super::{
+    delegations::{self, Delegation},
+    ens, methods, recorder,
+    replay::{self, ReplayReport, ReplayTarget},
+    Handler, Result,
+};
 
 #[tauri::command]
 pub async fn rpc_send_transaction(params: serde_json::Value) -> Result<serde_json::Value> {
@@ -11,3 +19,71 @@ pub async fn rpc_send_transaction(params: serde_json::Value) -> Result<serde_jso
 pub async fn rpc_eth_call(params: serde_json::Value) -> Result<Bytes> {
     Ok(Handler::send_call(params, Default::default()).await?)
 }
+
+/// Finds a human-readable name for an address, from the address book or ENS
+#[tauri::command]
+pub async fn rpc_resolve_address_label(
+    chain_id: u32,
+    address: Address,
+) -> Result<Option<AddressLabel>> {
+    ens::resolve_address_label(chain_id, address).await
+}
+
+#[tauri::command]
+pub async fn rpc_resolve_ens_name(name: String) -> Result<Option<Address>> {
+    ens::resolve_name(&name).await
+}
+
+/// Lists the EIP-7702 delegation of an account on every network
+#[tauri::command]
+pub async fn rpc_get_delegations(address: Address) -> Vec<Delegation> {
+    delegations::get_delegations(address).await
+}
+
+/// Delegates an account to a contract on the current network, by sending a type-4 transaction
+/// from the account itself. Delegating to the zero address clears the delegation
+#[tauri::command]
+pub async fn rpc_set_delegation(address: Address, delegate: Address) -> Result<serde_json::Value> {
+    let ctx = Ctx::default();
+
+    let authorization = methods::SignAuthorization::build(&ctx)
+        .set_from(address)
+        .set_delegate(delegate)
+        .self_sponsored()
+        .build()
+        .await?
+        .finish()
+        .await?;
+
+    let params = json!({
+        "from": address,
+        "to": address,
+        "authorizationList": [authorization],
+    });
+
+    Ok(Handler::send_transaction(params, ctx).await?)
+}
+
+/// Starts recording a dapp session. If a domain is given, only its calls are recorded
+#[tauri::command]
+pub async fn rpc_recording_start(name: String, domain: Option<String>) -> Result<()> {
+    recorder::start(name, domain).await
+}
+
+/// Stops recording, and stores the recording
+#[tauri::command]
+pub async fn rpc_recording_stop() -> Result<Recording> {
+    recorder::stop().await
+}
+
+#[tauri::command]
+pub async fn rpc_recording_current() -> Option<Recording> {
+    recorder::current().await
+}
+
+/// Replays a stored recording against anvil, reporting transactions whose outcome changed
+#[tauri::command]
+pub async fn rpc_recording_replay(id: i64, target: ReplayTarget) -> Result<ReplayReport> {
+    let recording = ethui_db::get().get_recording(id).await?;
+    replay::replay(&recording, target).await
+}
@@ -0,0 +1,66 @@
+use alloy::providers::Provider as _;
+use ethui_networks::Networks;
+use ethui_types::{Address, GlobalState};
+use serde::Serialize;
+use tracing::warn;
+
+/// Code prefix set by EIP-7702 on delegated accounts, followed by the delegate's address
+const DELEGATION_DESIGNATOR: [u8; 3] = [0xef, 0x01, 0x00];
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delegation {
+    pub chain_id: u32,
+    pub network: String,
+
+    /// the contract the account currently delegates to, if any
+    pub delegate: Option<Address>,
+
+    /// the delegate's name, if known
+    pub contract_name: Option<String>,
+}
+
+/// Fetches the current EIP-7702 delegation of an account, on every known network
+/// Networks that can't be reached are skipped
+pub async fn get_delegations(address: Address) -> Vec<Delegation> {
+    let networks: Vec<_> = Networks::read().await.get_all().cloned().collect();
+
+    let mut res = vec![];
+
+    for network in networks {
+        let code = match network.get_provider() {
+            Ok(provider) => provider.get_code_at(address).await,
+            Err(e) => Err(e),
+        };
+
+        let code = match code {
+            Ok(code) => code,
+            Err(e) => {
+                warn!(network = %network.name, "failed to fetch code: {}", e);
+                continue;
+            }
+        };
+
+        let delegate = (code.len() == 23 && code.starts_with(&DELEGATION_DESIGNATOR))
+            .then(|| Address::from_slice(&code[3..]));
+
+        let contract_name = match delegate {
+            Some(delegate) => ethui_db::get()
+                .get_contract(network.chain_id(), delegate)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|c| c.name),
+            None => None,
+        };
+
+        res.push(Delegation {
+            chain_id: network.chain_id(),
+            network: network.name.clone(),
+            delegate,
+            contract_name,
+        });
+    }
+
+    res
+}
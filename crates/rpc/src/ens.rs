@@ -0,0 +1,169 @@
+//! Address labels, from the address book or ENS
+//!
+//! ENS is resolved through the user's mainnet network, and cached in the database
+
+use alloy::primitives::{address, keccak256, B256};
+use ethui_abis::{IENSRegistry, IENSResolver};
+use ethui_networks::Networks;
+use ethui_types::{Address, AddressLabel, AddressLabelSource, GlobalState, Network};
+use tracing::warn;
+
+use crate::Result;
+
+/// ENS registry, deployed at the same address on mainnet and most testnets
+const ENS_REGISTRY: Address = address!("0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e");
+
+/// Finds a human-readable name for an address
+///
+/// Address book entries take precedence over ENS. ENS failures are logged and ignored, since
+/// labels are only informative
+pub async fn resolve_address_label(
+    chain_id: u32,
+    address: Address,
+) -> Result<Option<AddressLabel>> {
+    let db = ethui_db::get();
+
+    if let Some(entry) = db.get_address_book_entry(chain_id, address).await? {
+        return Ok(Some(AddressLabel {
+            name: entry.name,
+            source: AddressLabelSource::AddressBook,
+        }));
+    }
+
+    match lookup_address(address).await {
+        Ok(name) => Ok(name.map(|name| AddressLabel {
+            name,
+            source: AddressLabelSource::Ens,
+        })),
+        Err(e) => {
+            warn!(?address, "ENS reverse lookup failed: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Forward resolution of an ENS name
+pub async fn resolve_name(name: &str) -> Result<Option<Address>> {
+    let name = name.trim().to_lowercase();
+    let db = ethui_db::get();
+
+    if let Some(cached) = db.get_cached_ens_name(&name).await? {
+        return Ok(cached);
+    }
+
+    let address = resolve_name_on(&mainnet().await, &name).await?;
+    db.cache_ens_name(&name, address).await?;
+
+    Ok(address)
+}
+
+/// Reverse resolution of an address into its primary ENS name
+pub async fn lookup_address(address: Address) -> Result<Option<String>> {
+    let db = ethui_db::get();
+
+    if let Some(cached) = db.get_cached_ens_reverse(address).await? {
+        return Ok(cached);
+    }
+
+    let name = lookup_address_on(&mainnet().await, address).await?;
+    db.cache_ens_reverse(address, name.as_deref()).await?;
+
+    Ok(name)
+}
+
+/// The user's mainnet network, or the default one if it was removed
+async fn mainnet() -> Network {
+    Networks::read()
+        .await
+        .get_network(1)
+        .unwrap_or_else(|| Network::mainnet(0))
+}
+
+async fn resolve_name_on(network: &Network, name: &str) -> Result<Option<Address>> {
+    let provider = network.get_provider()?;
+    let node = namehash(name);
+
+    let resolver = IENSRegistry::new(ENS_REGISTRY, provider.clone())
+        .resolver(node)
+        .call()
+        .await?
+        .resolver;
+
+    if resolver.is_zero() {
+        return Ok(None);
+    }
+
+    let address = IENSResolver::new(resolver, provider.clone())
+        .addr(node)
+        .call()
+        .await?
+        .addr;
+
+    Ok((!address.is_zero()).then_some(address))
+}
+
+/// Reverse records can be set to any name, so the name must resolve back to the same address to
+/// be considered valid
+async fn lookup_address_on(network: &Network, address: Address) -> Result<Option<String>> {
+    let provider = network.get_provider()?;
+    let node = namehash(&format!("{:x}.addr.reverse", address));
+
+    let resolver = IENSRegistry::new(ENS_REGISTRY, provider.clone())
+        .resolver(node)
+        .call()
+        .await?
+        .resolver;
+
+    if resolver.is_zero() {
+        return Ok(None);
+    }
+
+    let name = IENSResolver::new(resolver, provider.clone())
+        .name(node)
+        .call()
+        .await?
+        .name;
+
+    if name.is_empty() || resolve_name_on(network, &name).await? != Some(address) {
+        return Ok(None);
+    }
+
+    Ok(Some(name))
+}
+
+/// EIP-137 namehash
+///
+/// Names are expected to be normalized already. Only lowercasing is done by callers
+fn namehash(name: &str) -> B256 {
+    let mut node = B256::ZERO;
+
+    if name.is_empty() {
+        return node;
+    }
+
+    for label in name.rsplit('.') {
+        node = keccak256([node.as_slice(), keccak256(label.as_bytes()).as_slice()].concat());
+    }
+
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::b256;
+
+    use super::*;
+
+    #[test]
+    fn namehash_matches_eip137() {
+        assert_eq!(namehash(""), B256::ZERO);
+        assert_eq!(
+            namehash("eth"),
+            b256!("0x93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae")
+        );
+        assert_eq!(
+            namehash("foo.eth"),
+            b256!("0xde9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f")
+        );
+    }
+}
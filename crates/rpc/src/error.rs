@@ -1,5 +1,5 @@
 use alloy::transports::{RpcError, TransportErrorKind};
-use ethui_types::Address;
+use ethui_types::{redact_urls, Address};
 use jsonrpc_core::ErrorCode;
 
 #[derive(thiserror::Error, Debug)]
@@ -16,6 +16,12 @@ pub enum Error {
     #[error("Unknown wallet: {0}")]
     WalletNotFound(Address),
 
+    #[error("Method {0} is not available to read-only connections")]
+    Unauthorized(String),
+
+    #[error("Wallet `{0}` is watch-only and cannot sign")]
+    WatchOnlyWallet(String),
+
     #[error("Error building signer: {0}")]
     SignerBuild(String),
 
@@ -28,6 +34,9 @@ pub enum Error {
     #[error(transparent)]
     Transport(#[from] alloy::transports::TransportError),
 
+    #[error(transparent)]
+    Contract(#[from] alloy::contract::Error),
+
     #[error(transparent)]
     EthuiWallets(#[from] ethui_wallets::Error),
 
@@ -92,6 +101,42 @@ pub enum Error {
 
     #[error("Unable to verify ownership. Possibly because the standard is not supported or the user's currently selected network does not match the chain of the asset in question.")]
     ErcInvalid,
+
+    #[error("A session is already being recorded")]
+    AlreadyRecording,
+
+    #[error("No session is being recorded")]
+    NotRecording,
+
+    #[error("Recording has no snapshot to revert to")]
+    NoSnapshot,
+
+    #[error("Failed to revert to snapshot {0}")]
+    SnapshotRevert(String),
+
+    #[error("Failed to start anvil: {0}")]
+    AnvilSpawn(String),
+
+    #[error(transparent)]
+    Db(#[from] ethui_db::Error),
+
+    #[error("Requested scope {0} is not supported")]
+    UnsupportedScope(String),
+
+    #[error("None of the requested scopes are supported")]
+    NoSupportedScopes,
+
+    #[error("Scope {0} is not authorized")]
+    ScopeNotAuthorized(String),
+
+    #[error("Method {0} is not authorized in scope {1}")]
+    MethodNotAuthorized(String, String),
+
+    #[error("Sessions can only be created by connections with a known domain or client")]
+    NoSessionKey,
+
+    #[error("RPC {0} serves chain {2}, not {1}")]
+    ChainIdMismatch(url::Url, u64, u64),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -110,8 +155,22 @@ impl From<Error> for jsonrpc_core::Error {
             | Error::DecimalsInvalid
             | Error::TokenInvalid
             | Error::SymbolMissing
-            | Error::SymbolInvalid => ErrorCode::InvalidParams,
-            Error::WalletNotFound(..) => ErrorCode::ServerError(4100),
+            | Error::SymbolInvalid
+            | Error::ChainIdMismatch(..) => ErrorCode::InvalidParams,
+            Error::WalletNotFound(..)
+            | Error::Unauthorized(..)
+            | Error::WatchOnlyWallet(..)
+            | Error::EthuiWallets(ethui_wallets::Error::WatchOnly(..))
+            | Error::Network(ethui_networks::Error::SecretsLocked) => ErrorCode::ServerError(4100),
+            Error::ScopeNotAuthorized(..)
+            | Error::MethodNotAuthorized(..)
+            | Error::NoSessionKey => ErrorCode::ServerError(4100),
+            // https://github.com/ChainAgnostic/CAIPs/blob/main/CAIPs/caip-25.md#trusted-failure-codes
+            Error::UnsupportedScope(..) | Error::NoSupportedScopes => ErrorCode::ServerError(5100),
+            // https://eips.ethereum.org/EIPS/eip-1193#provider-errors
+            Error::EthuiWallets(
+                ethui_wallets::Error::WalletCantSign | ethui_wallets::Error::CantSignHash(..),
+            ) => ErrorCode::ServerError(4200),
             Error::NetworkInvalid => ErrorCode::ServerError(4901),
             // https://github.com/MetaMask/metamask-mobile/blob/5fe6aceffcf4c80ed1f3530282640aebcd201935/app/core/RPCMethods/wallet_switchEthereumChain.js#L88C11-L88C15
             Error::UnrecognizedChainId(_) => ErrorCode::ServerError(4902),
@@ -121,7 +180,8 @@ impl From<Error> for jsonrpc_core::Error {
         Self {
             code,
             data: None,
-            message: value.to_string(),
+            // transport errors may include the URL they failed to reach, secrets and all
+            message: redact_urls(&value.to_string()),
         }
     }
 }
@@ -141,9 +201,13 @@ pub(crate) fn alloy_to_jsonrpc_error(e: RpcError<TransportErrorKind>) -> jsonrpc
         jsonrpc_core::Error {
             code: ErrorCode::ServerError(e.code),
             data: e.data.clone().map(|d| serde_json::to_value(d).unwrap()),
-            message: e.message.to_string(),
+            message: redact_urls(&e.message),
         }
     } else {
-        jsonrpc_core::Error::internal_error()
+        jsonrpc_core::Error {
+            code: ErrorCode::InternalError,
+            data: None,
+            message: redact_urls(&e.to_string()),
+        }
     }
 }
@@ -0,0 +1,177 @@
+//! Optional recording of JSON-RPC traffic, for debugging
+//!
+//! When enabled in settings, every call handled by `Handler` is stored, along with each
+//! upstream call made to a node on its behalf
+//!
+//! Callers check `enabled` once per call, and only record if it was set
+//!
+//! Messages and signatures of signing methods are never stored
+
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use ethui_connections::Ctx;
+use ethui_settings::Settings;
+use ethui_types::{redact_url, GlobalState, Json, Network, RpcTrafficEntry, RpcTrafficSource};
+use jsonrpc_core::Params;
+use serde_json::json;
+
+/// Methods whose params hold a message to sign, and whose results are signatures
+const SIGNING_METHODS: &[&str] = &[
+    "eth_sign",
+    "personal_sign",
+    "eth_signTypedData",
+    "eth_signTypedData_v3",
+    "eth_signTypedData_v4",
+    "eth_signTransaction",
+];
+
+/// Stored in place of redacted values
+const REDACTED: &str = "[redacted]";
+
+/// Records a call made by a dapp
+pub(crate) async fn record(
+    ctx: &Ctx,
+    method: &str,
+    params: &Params,
+    result: &jsonrpc_core::Result<serde_json::Value>,
+    started: Instant,
+) {
+    let chain_id = ctx.chain_id().await;
+    let call = Call {
+        method,
+        params,
+        result,
+        started,
+    };
+
+    save(entry(ctx, RpcTrafficSource::Dapp, chain_id, None, call));
+}
+
+/// Records a call made to a node, on behalf of a dapp
+pub(crate) async fn record_upstream(
+    ctx: &Ctx,
+    network: &Network,
+    method: &str,
+    params: &Params,
+    result: &jsonrpc_core::Result<serde_json::Value>,
+    started: Instant,
+) {
+    let url = network.http_url().ok().map(|url| redact_url(&url));
+    let call = Call {
+        method,
+        params,
+        result,
+        started,
+    };
+
+    save(entry(
+        ctx,
+        RpcTrafficSource::Upstream,
+        network.chain_id(),
+        url,
+        call,
+    ));
+}
+
+pub(crate) async fn enabled() -> bool {
+    Settings::read().await.rpc_inspector()
+}
+
+/// A call about to be recorded
+struct Call<'a> {
+    method: &'a str,
+    params: &'a Params,
+    result: &'a jsonrpc_core::Result<serde_json::Value>,
+    started: Instant,
+}
+
+fn entry(
+    ctx: &Ctx,
+    source: RpcTrafficSource,
+    chain_id: u32,
+    url: Option<String>,
+    call: Call,
+) -> RpcTrafficEntry {
+    let (params, result, error) = redact(call.method, call.params, call.result);
+
+    let latency = call.started.elapsed();
+
+    // the time the call was made, rather than when it completed
+    let timestamp = SystemTime::now()
+        .checked_sub(latency)
+        .unwrap_or(UNIX_EPOCH)
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    RpcTrafficEntry {
+        id: 0,
+        timestamp: timestamp.as_millis() as u64,
+        source,
+        peer: ctx.peer.clone(),
+        origin: ctx.key().cloned(),
+        chain_id: Some(chain_id),
+        url,
+        method: call.method.into(),
+        params,
+        result,
+        error,
+        latency_ms: latency.as_millis() as u64,
+    }
+}
+
+/// Serializes a call's params & outcome, leaving out the messages and signatures of signing
+/// methods, which are not stored
+fn redact(
+    method: &str,
+    params: &Params,
+    result: &jsonrpc_core::Result<serde_json::Value>,
+) -> (Json, Option<Json>, Option<Json>) {
+    let signing = SIGNING_METHODS.contains(&method);
+
+    let params = if signing {
+        json!(REDACTED)
+    } else {
+        serde_json::to_value(params).unwrap_or_default()
+    };
+
+    match result {
+        Ok(_) if signing => (params, Some(json!(REDACTED)), None),
+        Ok(result) => (params, Some(result.clone()), None),
+        Err(e) => (params, None, serde_json::to_value(e).ok()),
+    }
+}
+
+/// Stored in the background, so recording doesn't delay responses
+fn save(entry: RpcTrafficEntry) {
+    tokio::spawn(async move {
+        if let Err(e) = ethui_db::get().insert_rpc_traffic(&entry).await {
+            tracing::warn!("failed to record RPC traffic: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_signing_methods() {
+        let params = Params::Array(vec![json!("0xdeadbeef"), json!("0x0000")]);
+
+        let (params, result, error) = redact("personal_sign", &params, &Ok(json!("0x1234")));
+
+        assert_eq!(params, json!(REDACTED));
+        assert_eq!(result, Some(json!(REDACTED)));
+        assert_eq!(error, None);
+    }
+
+    #[test]
+    fn keeps_other_methods() {
+        let params = Params::Array(vec![json!("0x0000"), json!("latest")]);
+
+        let (params, result, _) = redact("eth_getBalance", &params, &Ok(json!("0x1")));
+
+        assert_eq!(params, json!(["0x0000", "latest"]));
+        assert_eq!(result, Some(json!("0x1")));
+    }
+}
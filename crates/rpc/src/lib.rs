@@ -1,66 +1,184 @@
 pub mod commands;
+mod delegations;
+pub mod ens;
 mod error;
+mod inspector;
 mod methods;
+pub mod recorder;
+pub mod replay;
+mod secrets;
 
-use std::collections::HashMap;
+use std::time::Instant;
 
 use alloy::{dyn_abi::TypedData, hex, primitives::Bytes, providers::Provider as _};
-use ethui_connections::{permissions::PermissionRequest, Ctx};
-use ethui_types::GlobalState;
-use ethui_wallets::{WalletControl, Wallets};
-use jsonrpc_core::{MetaIoHandler, Params};
+use ethui_connections::{burners::Burner, permissions::PermissionRequest, Ctx, Store};
+use ethui_types::{Address, GlobalState};
+use ethui_wallets::{Wallet, WalletControl, Wallets};
+use jsonrpc_core::{Call, Id, MetaIoHandler, MethodCall, Output, Params, Version};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
 use serde_json::json;
 use tracing::info;
 
 pub use self::error::{Error, Result};
 
+/// Methods available to read-only connections. None of them sign, or change any state
+const READ_ONLY_METHODS: &[&str] = &[
+    "eth_blockNumber",
+    "eth_getBalance",
+    "eth_getStorageAt",
+    "eth_getTransactionCount",
+    "eth_getCode",
+    "eth_call",
+    "eth_estimateGas",
+    "eth_protocolVersion",
+    "eth_syncing",
+    "eth_mining",
+    "net_version",
+    "eth_getBlockTransactionCountByHash",
+    "eth_getBlockTransactionCountByNumber",
+    "eth_getUncleCountByBlockHash",
+    "eth_getUncleCountByBlockNumber",
+    "eth_getBlockByHash",
+    "eth_getBlockByNumber",
+    "eth_getTransactionByHash",
+    "eth_getTransactionByBlockHashAndIndex",
+    "eth_getTransactionByBlockNumberAndIndex",
+    "eth_getTransactionReceipt",
+    "eth_getUncleByBlockHashAndIndex",
+    "eth_getUncleByBlockNumberAndIndex",
+    "eth_newFilter",
+    "eth_newBlockFilter",
+    "eth_newPendingFilter",
+    "eth_uninstallFilter",
+    "eth_getFilterLogs",
+    "eth_getLogs",
+    "eth_accounts",
+    "eth_chainId",
+    "wallet_getPermissions",
+    "metamask_getProviderState",
+];
+
+/// Every method, shared by all handlers. Calls depend only on the `Ctx` they're given
+static IO: Lazy<MetaIoHandler<Ctx>> = Lazy::new(|| {
+    let mut io = MetaIoHandler::default();
+    Handler::add_handlers(&mut io);
+    io
+});
+
 pub struct Handler {
-    io: MetaIoHandler<Ctx>,
     ctx: Ctx,
 }
 
 impl Handler {
-    pub fn new(domain: Option<String>) -> Self {
-        let mut res = Self {
-            io: MetaIoHandler::default(),
-            ctx: Ctx {
-                domain,
-                ..Default::default()
-            },
-        };
-        res.add_handlers();
-        res
+    pub fn new(domain: Option<String>, tab_id: Option<u32>) -> Self {
+        Self::with_ctx(Ctx {
+            domain,
+            tab_id,
+            ..Default::default()
+        })
+    }
+
+    /// Handler for a domain-less connection, such as the IPC socket
+    pub fn for_client(client: &str) -> Self {
+        Self::with_ctx(Ctx {
+            client: Some(client.into()),
+            ..Default::default()
+        })
+    }
+
+    fn with_ctx(ctx: Ctx) -> Self {
+        Self { ctx }
+    }
+
+    /// Restricts the connection to `READ_ONLY_METHODS`
+    pub fn set_read_only(&self, read_only: bool) {
+        self.ctx.set_read_only(read_only);
+    }
+
+    /// Identifies the individual connection in recorded traffic
+    pub fn set_peer(&mut self, peer: String) {
+        self.ctx.peer = Some(peer);
     }
 
     pub async fn handle(&self, request: jsonrpc_core::Request) -> Option<jsonrpc_core::Response> {
-        self.io.handle_rpc_request(request, self.ctx.clone()).await
+        IO.handle_rpc_request(request, self.ctx.clone()).await
     }
 
-    fn add_handlers(&mut self) {
+    fn add_handlers(io: &mut MetaIoHandler<Ctx>) {
         macro_rules! self_handler {
             ($name:literal, $fn:path) => {
-                self.io
-                    .add_method_with_meta($name, |params: Params, ctx: Ctx| async move {
+                io.add_method_with_meta($name, |params: Params, ctx: Ctx| async move {
+                    let started = Instant::now();
+                    let inspecting = inspector::enabled().await;
+                    let recording = recorder::is_recording().await;
+
+                    // the call consumes its params & ctx, so copies are only kept if needed
+                    let kept = (inspecting || recording).then(|| (params.clone(), ctx.clone()));
+
+                    let ret: jsonrpc_core::Result<serde_json::Value> = async {
+                        ensure_allowed(&ctx, $name).await?;
                         info!(method = $name, params = serde_json::to_string(&params).unwrap());
                         let ret = $fn(params, ctx).await;
                         info!(result = ?ret);
                         ret
-                    });
+                    }
+                    .await;
+
+                    if let Some((params, ctx)) = kept {
+                        if inspecting {
+                            inspector::record(&ctx, $name, &params, &ret, started).await;
+                        }
+                        if recording {
+                            recorder::record(&ctx, $name, &params, &ret).await;
+                        }
+                    }
+                    ret
+                });
             };
         }
 
         macro_rules! provider_handler {
             ($name:literal) => {
-                self.io
-                    .add_method_with_meta($name, |params: Params, ctx: Ctx| async move {
-                        let provider = ctx.network().await.get_provider();
+                io.add_method_with_meta($name, |params: Params, ctx: Ctx| async move {
+                    let started = Instant::now();
+
+                    // the upstream call consumes the params, so a copy is only kept if needed
+                    let kept = inspector::enabled().await.then(|| params.clone());
+
+                    let ret: jsonrpc_core::Result<serde_json::Value> = async {
+                        ensure_allowed(&ctx, $name).await?;
+                        let network = secrets::unlock(&ctx, ctx.network().await).await?;
+                        let provider = network
+                            .get_provider()
+                            .map_err(error::alloy_to_jsonrpc_error)?;
 
+                        let upstream_started = Instant::now();
                         let res: jsonrpc_core::Result<serde_json::Value> = provider
                             .raw_request::<_, serde_json::Value>($name.into(), params)
                             .await
                             .map_err(error::alloy_to_jsonrpc_error);
+
+                        if let Some(ref params) = kept {
+                            inspector::record_upstream(
+                                &ctx,
+                                &network,
+                                $name,
+                                params,
+                                &res,
+                                upstream_started,
+                            )
+                            .await;
+                        }
                         res
-                    });
+                    }
+                    .await;
+
+                    if let Some(params) = kept {
+                        inspector::record(&ctx, $name, &params, &ret, started).await;
+                    }
+                    ret
+                });
             };
         }
 
@@ -107,7 +225,7 @@ impl Handler {
 
         // handle internally
         self_handler!("eth_accounts", Self::accounts);
-        self_handler!("eth_requestAccounts", Self::accounts);
+        self_handler!("eth_requestAccounts", Self::request_accounts);
         self_handler!("eth_chainId", Self::chain_id);
         self_handler!("eth_sendTransaction", Self::send_transaction);
         self_handler!("eth_sign", Self::eth_sign);
@@ -121,6 +239,12 @@ impl Handler {
         self_handler!("wallet_switchEthereumChain", Self::switch_chain);
         self_handler!("wallet_watchAsset", Self::add_token);
 
+        // CAIP-25 multichain sessions
+        self_handler!("wallet_createSession", Self::create_session);
+        self_handler!("wallet_getSession", Self::get_session);
+        self_handler!("wallet_revokeSession", Self::revoke_session);
+        self_handler!("wallet_invokeMethod", Self::invoke_method);
+
         // metamask
         self_handler!("metamask_getProviderState", Self::provider_state);
 
@@ -133,11 +257,21 @@ impl Handler {
         self_handler!("eth_signTransaction", Self::unimplemented);
     }
 
-    async fn accounts(_: Params, _: Ctx) -> jsonrpc_core::Result<serde_json::Value> {
-        let wallets = Wallets::read().await;
-        let address = wallets.get_current_wallet().get_current_address().await;
+    async fn accounts(_: Params, ctx: Ctx) -> jsonrpc_core::Result<serde_json::Value> {
+        Ok(json!(exposed_accounts(&ctx).await?))
+    }
+
+    /// Same as `eth_accounts`, except that a burner address not yet derived because its wallet
+    /// is locked is derived now, asking the user to unlock it
+    async fn request_accounts(_: Params, ctx: Ctx) -> jsonrpc_core::Result<serde_json::Value> {
+        // read-only connections are never exposed any account
+        if ctx.is_read_only().await {
+            return Ok(json!([]));
+        }
+
+        ctx.unlock_burner().await.map_err(Error::from)?;
 
-        Ok(json!([address]))
+        Ok(json!(exposed_accounts(&ctx).await?))
     }
 
     async fn chain_id(_: Params, ctx: Ctx) -> jsonrpc_core::Result<serde_json::Value> {
@@ -146,16 +280,14 @@ impl Handler {
     }
 
     async fn provider_state(_: Params, ctx: Ctx) -> jsonrpc_core::Result<serde_json::Value> {
-        let wallets = Wallets::read().await;
-
         let network = ctx.network().await;
-        let address = wallets.get_current_wallet().get_current_address().await;
+        let accounts = exposed_accounts(&ctx).await?;
 
         Ok(json!({
             "isUnlocked": true,
             "chainId": network.chain_id_hex(),
             "networkVersion": network.chain_id().to_string(),
-            "accounts": [address],
+            "accounts": accounts,
         }))
     }
 
@@ -180,7 +312,7 @@ impl Handler {
         let method = methods::ChainAdd::build()
             .set_params(params.into())?
             .build()
-            .await;
+            .await?;
 
         method.run().await?;
 
@@ -201,15 +333,17 @@ impl Handler {
 
     #[tracing::instrument()]
     async fn switch_chain(params: Params, mut ctx: Ctx) -> jsonrpc_core::Result<serde_json::Value> {
-        let params = params.parse::<Vec<HashMap<String, String>>>().unwrap();
-        let chain_id_str = params[0].get("chainId").unwrap().clone();
-        let new_chain_id = u32::from_str_radix(&chain_id_str[2..], 16).unwrap();
+        let dedup_chain_id = methods::ChainSwitch::build()
+            .set_domain(ctx.key().cloned())
+            .set_current(ctx.dedup_chain_id().await)
+            .set_params(params.into())?
+            .build()
+            .await?
+            .run()
+            .await?;
 
-        // TODO future work
-        // multiple netowrks with same chain id should display a dialog so user can select which
-        // network to switch to
         Ok(ctx
-            .switch_chain(new_chain_id)
+            .switch_chain(dedup_chain_id)
             .await
             .map(|_| serde_json::Value::Null)
             .map_err(|e| match e {
@@ -222,16 +356,132 @@ impl Handler {
 
     #[tracing::instrument()]
     async fn add_token(params: Params, ctx: Ctx) -> jsonrpc_core::Result<serde_json::Value> {
-        let method = methods::TokenAdd::build()
+        let method = methods::TokenAdd::build(&ctx)
             .set_params(params.into())?
             .build()
-            .await;
+            .await?;
 
         method.run().await?;
 
         Ok(true.into())
     }
 
+    #[tracing::instrument(skip(params))]
+    async fn create_session(params: Params, ctx: Ctx) -> jsonrpc_core::Result<serde_json::Value> {
+        let domain = ctx.key().cloned().ok_or(Error::NoSessionKey)?;
+
+        let session = methods::SessionCreate::build()
+            .set_domain(domain.clone())
+            .set_current(ctx.dedup_chain_id().await)
+            .set_params(params.into())?
+            .build()
+            .await?
+            .run()
+            .await?;
+
+        let session_scopes = session.session_scopes(&exposed_accounts(&ctx).await?);
+        ethui_broadcast::session_changed(domain, session_scopes.clone()).await;
+
+        Ok(json!({
+            "sessionScopes": session_scopes,
+            "sessionProperties": session.properties,
+        }))
+    }
+
+    async fn get_session(_: Params, ctx: Ctx) -> jsonrpc_core::Result<serde_json::Value> {
+        let session = match ctx.key() {
+            Some(domain) => Store::read().await.get_session(domain),
+            None => None,
+        };
+
+        let session_scopes = match session {
+            Some(session) => session.session_scopes(&exposed_accounts(&ctx).await?),
+            None => json!({}),
+        };
+
+        Ok(json!({ "sessionScopes": session_scopes }))
+    }
+
+    async fn revoke_session(_: Params, ctx: Ctx) -> jsonrpc_core::Result<serde_json::Value> {
+        let Some(domain) = ctx.key().cloned() else {
+            return Ok(true.into());
+        };
+
+        let removed = Store::write()
+            .await
+            .remove_session(&domain)
+            .map_err(Error::from)?;
+
+        if removed.is_some() {
+            ethui_broadcast::session_changed(domain, json!({})).await;
+        }
+
+        Ok(true.into())
+    }
+
+    /// Runs a call within one of the session's scopes, routing it to that scope's network
+    #[tracing::instrument(skip(params, ctx))]
+    async fn invoke_method(params: Params, ctx: Ctx) -> jsonrpc_core::Result<serde_json::Value> {
+        #[derive(Deserialize)]
+        struct InvokeParams {
+            scope: String,
+            request: InvokeRequest,
+        }
+
+        #[derive(Deserialize)]
+        struct InvokeRequest {
+            method: String,
+            #[serde(default)]
+            params: serde_json::Value,
+        }
+
+        let params: serde_json::Value = params.into();
+        let params = match params {
+            serde_json::Value::Array(mut params) if !params.is_empty() => params.remove(0),
+            params => params,
+        };
+        let InvokeParams { scope, request } =
+            serde_json::from_value(params).map_err(|_| jsonrpc_core::Error::invalid_params(""))?;
+
+        let session = match ctx.key() {
+            Some(domain) => Store::read().await.get_session(domain),
+            None => None,
+        };
+        let session_scope = session
+            .as_ref()
+            .and_then(|s| s.scope(&scope))
+            .ok_or_else(|| Error::ScopeNotAuthorized(scope.clone()))?;
+
+        if !session_scope.methods.contains(&request.method) {
+            return Err(Error::MethodNotAuthorized(request.method, scope).into());
+        }
+
+        let ctx = Ctx {
+            scope: Some(session_scope.chain),
+            ..ctx
+        };
+
+        let params = match request.params {
+            serde_json::Value::Null => Params::None,
+            params => serde_json::from_value(params).map_err(|_| {
+                jsonrpc_core::Error::invalid_params("params must be an array or object")
+            })?,
+        };
+
+        let call = Call::MethodCall(MethodCall {
+            jsonrpc: Some(Version::V2),
+            method: request.method,
+            params,
+            id: Id::Num(0),
+        });
+
+        match IO.handle_call(call, ctx).await {
+            Some(Output::Success(success)) => Ok(success.result),
+            Some(Output::Failure(failure)) => Err(failure.error),
+            None => Ok(serde_json::Value::Null),
+        }
+    }
+
     async fn send_transaction<T: Into<serde_json::Value>>(
         params: T,
         ctx: Ctx,
@@ -242,11 +492,11 @@ impl Handler {
             .await
             .unwrap()
             .build()
-            .await;
+            .await?;
 
         let result = sender.estimate_gas().await.finish().await?;
 
-        Ok(format!("0x{:x}", result.tx_hash()).into())
+        Ok(format!("0x{:x}", result).into())
     }
 
     async fn send_call(params: serde_json::Value, ctx: Ctx) -> jsonrpc_core::Result<Bytes> {
@@ -255,7 +505,7 @@ impl Handler {
             .await
             .unwrap()
             .build()
-            .await;
+            .await?;
 
         Ok(sender.finish().await?)
     }
@@ -266,14 +516,15 @@ impl Handler {
         // TODO where should this be used?
         // let address = Address::from_str(&params[1].as_ref().cloned().unwrap()).unwrap();
 
+        let burner = ctx.unlock_burner().await.map_err(Error::from)?;
         let wallets = Wallets::read().await;
 
         let network = ctx.network().await;
-        let wallet = wallets.get_current_wallet();
+        let (wallet, path) = signing_wallet(&wallets, burner).await?;
 
         let mut signer = methods::SignMessage::build()
             .set_wallet(wallet)
-            .set_wallet_path(wallet.get_current_path())
+            .set_wallet_path(path)
             .set_network(network)
             .set_string_data(msg)
             .build();
@@ -295,14 +546,15 @@ impl Handler {
         let data = params[1].as_ref().cloned().unwrap();
         let typed_data: TypedData = serde_json::from_str(&data).unwrap();
 
+        let burner = ctx.unlock_burner().await.map_err(Error::from)?;
         let wallets = Wallets::read().await;
 
-        let wallet = wallets.get_current_wallet();
+        let (wallet, path) = signing_wallet(&wallets, burner).await?;
         let network = ctx.network().await;
 
         let mut signer = methods::SignMessage::build()
             .set_wallet(wallet)
-            .set_wallet_path(wallet.get_current_path())
+            .set_wallet_path(path)
             .set_network(network)
             .set_typed_data(typed_data)
             .build();
@@ -318,3 +570,36 @@ impl Handler {
         Err(jsonrpc_core::Error::internal_error())
     }
 }
+
+async fn ensure_allowed(ctx: &Ctx, method: &str) -> Result<()> {
+    if !READ_ONLY_METHODS.contains(&method) && ctx.is_read_only().await {
+        return Err(Error::Unauthorized(method.into()));
+    }
+
+    Ok(())
+}
+
+/// Accounts exposed to a connection. Those whose burner can't be derived yet get none, until
+/// their next `eth_requestAccounts` or signing request
+async fn exposed_accounts(ctx: &Ctx) -> Result<Vec<Address>> {
+    Ok(ctx.exposed_accounts().await?)
+}
+
+/// The address exposed to a connection: its domain's burner, if any, or the globally selected one
+async fn current_address(ctx: &Ctx) -> Result<Address> {
+    Ok(ctx.current_address().await?)
+}
+
+/// The wallet & path to sign with, given a connection's burner
+async fn signing_wallet(wallets: &Wallets, burner: Option<Burner>) -> Result<(&Wallet, String)> {
+    match burner {
+        Some(burner) => wallets
+            .find(burner.address)
+            .await
+            .ok_or(Error::WalletNotFound(burner.address)),
+        None => {
+            let wallet = wallets.get_current_wallet();
+            Ok((wallet, wallet.get_current_path()))
+        }
+    }
+}
@@ -1,7 +1,11 @@
 use ethui_dialogs::{Dialog, DialogMsg};
-use ethui_networks::Networks;
-use ethui_types::{GlobalState, NewNetworkParams, U64};
+use ethui_networks::{
+    probe::{self, RpcProbe},
+    Networks,
+};
+use ethui_types::{GlobalState, Network, NewNetworkParams, U64};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tracing::info;
 use url::Url;
 
@@ -10,6 +14,20 @@ use crate::{Error, Result};
 #[derive(Debug)]
 pub struct ChainAdd {
     network: NewNetworkParams,
+
+    /// all requested RPC URLs. Only probed once the user agrees to add them
+    rpc_urls: Vec<Url>,
+}
+
+/// What the user picked in the `chain-add` dialog, when the chain id is already known
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Choice {
+    /// adds the proposed RPC to an existing network, by name
+    Endpoint { network: String },
+
+    /// adds a separate network with the same chain id, under a new `DedupChainId`
+    Network { name: Option<String> },
 }
 
 impl ChainAdd {
@@ -18,36 +36,63 @@ impl ChainAdd {
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn run(self) -> Result<()> {
-        // TODO how to handle dedup_id
-        // if the network already exists, we may want to add a new one anyway
-        if self.already_exists().await {
+    pub async fn run(mut self) -> Result<()> {
+        let existing: Vec<Network> = Networks::read()
+            .await
+            .get_networks_by_chain_id(self.network.dedup_chain_id.chain_id())
+            .collect();
+
+        if existing.iter().any(|n| self.served_by(n)) {
             info!("Network already exists");
             return Ok(());
         }
 
-        let dialog = Dialog::new("chain-add", serde_json::to_value(&self.network).unwrap());
+        let mut data = serde_json::to_value(&self.network).unwrap();
+        data["existing"] = json!(existing.iter().map(Network::redacted).collect::<Vec<_>>());
+
+        let dialog = Dialog::new("chain-add", data);
         dialog.open().await?;
 
         while let Some(msg) = dialog.recv().await {
-            match msg {
-                DialogMsg::Data(msg) => {
-                    if let Some("accept") = msg.as_str() {
-                        self.on_accept().await?;
-                        break;
-                    }
-                }
-
+            let choice = match msg {
+                DialogMsg::Data(msg) if msg.as_str() == Some("reject") => break,
+                DialogMsg::Data(msg) if existing.is_empty() => match msg.as_str() {
+                    Some("accept") => None,
+                    _ => continue,
+                },
+                DialogMsg::Data(msg) => match serde_json::from_value(msg) {
+                    Ok(choice) => Some(choice),
+                    Err(_) => continue,
+                },
                 DialogMsg::Close => break,
+            };
+
+            // only probed once the user agreed, so that dapps can't use this to reach arbitrary
+            // hosts. results are shown to the user alone
+            let probes = self.probe().await?;
+            let Some(probe) = probe::fastest(&probes, self.chain_id()) else {
+                dialog.send("failed", Some(json!(probes))).await?;
+                continue;
+            };
+
+            // anvil nodes, forks included, have state of their own, so they're never used as a
+            // fallback for another network
+            if probe.anvil && matches!(choice, Some(Choice::Endpoint { .. })) {
+                dialog.send("anvil", None).await?;
+                continue;
             }
-        }
 
-        Ok(())
-    }
+            self.network.http_url = probe.url.clone();
+
+            match choice {
+                None => self.on_accept().await?,
+                Some(choice) => self.on_choice(choice).await?,
+            }
 
-    pub async fn already_exists(&self) -> bool {
-        let networks = Networks::read().await;
-        networks.validate_chain_id(self.network.dedup_chain_id.chain_id())
+            return Ok(());
+        }
+
+        Err(Error::UserRejectedDialog)
     }
 
     pub async fn on_accept(&self) -> Result<()> {
@@ -57,6 +102,58 @@ impl ChainAdd {
 
         Ok(())
     }
+
+    async fn on_choice(&self, choice: Choice) -> Result<()> {
+        let mut networks = Networks::write().await;
+
+        match choice {
+            Choice::Endpoint { network } => {
+                networks
+                    .add_http_url(&network, self.network.http_url.clone())
+                    .await?
+            }
+            Choice::Network { name } => {
+                let mut network = self.network.clone();
+                if let Some(name) = name {
+                    network.name = name;
+                }
+
+                networks.add_duplicate_network(network).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Probes all requested RPC URLs
+    ///
+    /// Fails if any of them serves a different chain, since the request can't be trusted then
+    async fn probe(&self) -> Result<Vec<RpcProbe>> {
+        let chain_id = self.chain_id();
+        let probes = probe::probe_all(self.rpc_urls.clone()).await;
+
+        if let Some(probe) = probe::mismatched(&probes, chain_id) {
+            return Err(Error::ChainIdMismatch(
+                probe.url.clone(),
+                chain_id,
+                probe.chain_id.unwrap_or_default(),
+            ));
+        }
+
+        Ok(probes)
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.network.dedup_chain_id.chain_id() as u64
+    }
+
+    /// Whether an existing network already uses one of the proposed RPCs
+    fn served_by(&self, network: &Network) -> bool {
+        network
+            .endpoints
+            .iter()
+            .any(|e| self.rpc_urls.iter().any(|url| probe::same_rpc(&e.url, url)))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -84,37 +181,42 @@ pub struct ChainAddBuilder {
 
 impl ChainAddBuilder {
     pub fn set_params(mut self, params: serde_json::Value) -> Result<Self> {
-        let params: serde_json::Value = if params.is_array() {
-            params.as_array().unwrap()[0].clone()
-        } else {
-            params
+        let params = match params {
+            serde_json::Value::Array(mut params) if !params.is_empty() => params.remove(0),
+            serde_json::Value::Array(_) => return Err(Error::ParseError),
+            params => params,
         };
 
         self.params = Some(serde_json::from_value(params)?);
         Ok(self)
     }
 
-    pub async fn build(self) -> ChainAdd {
-        ChainAdd {
-            network: self.params.unwrap().try_into().unwrap(),
-        }
+    pub async fn build(self) -> Result<ChainAdd> {
+        let params = self.params.ok_or(Error::ParseError)?;
+        let rpc_urls = params.rpc_urls.clone();
+        let network: NewNetworkParams = params.try_into()?;
+
+        Ok(ChainAdd { network, rpc_urls })
     }
 }
 
 impl TryFrom<Params> for NewNetworkParams {
     type Error = Error;
     fn try_from(params: Params) -> Result<Self> {
+        let chain_id: u32 = params.chain_id.try_into().map_err(|_| Error::ParseError)?;
+
         Ok(Self {
             name: params.chain_name,
-            // Using 0 for dedup_id since at this time no duplicate chain_id is allowed
-            dedup_chain_id: (params.chain_id.try_into().unwrap(), 0).into(),
+            // the actual dedup_id is assigned once the network is added
+            dedup_chain_id: (chain_id, 0).into(),
             explorer_url: params.block_explorer_urls.first().map(|u| u.to_string()),
+            // the first one, until probing picks the fastest
             http_url: params
                 .rpc_urls
                 .iter()
                 .find(|s| s.scheme().starts_with("http"))
                 .cloned()
-                .expect("http url not found"),
+                .ok_or(Error::ParseError)?,
             ws_url: params
                 .rpc_urls
                 .iter()
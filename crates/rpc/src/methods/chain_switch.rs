@@ -0,0 +1,292 @@
+use ethui_connections::Store;
+use ethui_dialogs::{Dialog, DialogMsg};
+use ethui_networks::Networks;
+use ethui_types::{DedupChainId, GlobalState, Json, Network, U64};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::ChainAdd;
+use crate::{Error, Result};
+
+/// Resolves `wallet_switchEthereumChain` to a single network
+///
+/// When several networks share the requested chain id (e.g. multiple anvil nodes), the user
+/// picks one, and may have the choice remembered for the domain. When none do, the user is
+/// offered to add one, going through the same flow as `wallet_addEthereumChain`. If they
+/// decline, the dapp gets the usual `4902` error, so it can still fall back to that
+#[derive(Debug)]
+pub struct ChainSwitch {
+    domain: Option<String>,
+    chain_id: u32,
+    current: DedupChainId,
+    candidates: Vec<Network>,
+}
+
+/// What the user picked in the `chain-switch` dialog
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Choice {
+    /// one of the listed networks, by dedup id
+    #[serde(rename_all = "camelCase")]
+    Select {
+        dedup_id: u32,
+        #[serde(default)]
+        remember: bool,
+    },
+
+    /// a new network, for a chain id that isn't known yet, with the same params as
+    /// `wallet_addEthereumChain`
+    Add { network: Json },
+}
+
+/// Whether a message from the `chain-switch` dialog selects the given network
+pub(super) fn selects(msg: &Json, network: &Network) -> bool {
+    matches!(
+        serde_json::from_value(msg.clone()),
+        Ok(Choice::Select { dedup_id, .. }) if dedup_id == network.dedup_chain_id().dedup_id()
+    )
+}
+
+impl ChainSwitch {
+    pub fn build() -> ChainSwitchBuilder {
+        ChainSwitchBuilder::default()
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn run(self) -> Result<DedupChainId> {
+        if self.candidates.is_empty() {
+            return self.offer_add().await;
+        }
+
+        match self.unambiguous().await {
+            Some(choice) => Ok(choice),
+            None => self.select().await,
+        }
+    }
+
+    /// The network the domain would be switched to, without asking the user. When the choice
+    /// is ambiguous, this is the network with the lowest dedup id
+    pub(crate) async fn preferred(&self) -> Option<DedupChainId> {
+        match self.unambiguous().await {
+            Some(choice) => Some(choice),
+            None => self.candidates.first().map(|n| n.dedup_chain_id()),
+        }
+    }
+
+    /// The only candidate, the domain's previous choice, or the network it's already on
+    async fn unambiguous(&self) -> Option<DedupChainId> {
+        if let [network] = self.candidates.as_slice() {
+            return Some(network.dedup_chain_id());
+        }
+
+        if let Some(choice) = self.remembered().await {
+            return Some(choice);
+        }
+
+        // already on one of them, so there's no ambiguity to resolve
+        (self.current.chain_id() == self.chain_id).then_some(self.current)
+    }
+
+    /// The domain's previous choice, if that network still exists
+    async fn remembered(&self) -> Option<DedupChainId> {
+        let domain = self.domain.as_ref()?;
+        let choice = Store::read()
+            .await
+            .get_chain_choice(domain, self.chain_id)?;
+
+        self.candidates
+            .iter()
+            .any(|n| n.dedup_chain_id() == choice)
+            .then_some(choice)
+    }
+
+    async fn select(&self) -> Result<DedupChainId> {
+        let dialog = self.dialog();
+        dialog.open().await?;
+
+        loop {
+            match dialog.recv().await {
+                // plain accepts are kept for compatibility, and go to the lowest dedup id
+                Some(DialogMsg::Data(msg)) if msg.as_str() == Some("accept") => {
+                    return Ok(self.candidates[0].dedup_chain_id());
+                }
+                Some(DialogMsg::Data(msg)) if msg.as_str() == Some("reject") => {
+                    return Err(Error::UserRejectedDialog);
+                }
+                Some(DialogMsg::Data(msg)) => {
+                    let Ok(Choice::Select { dedup_id, remember }) = serde_json::from_value(msg)
+                    else {
+                        continue;
+                    };
+
+                    let Some(network) = self
+                        .candidates
+                        .iter()
+                        .find(|n| n.dedup_chain_id().dedup_id() == dedup_id)
+                    else {
+                        continue;
+                    };
+
+                    if let (true, Some(domain)) = (remember, &self.domain) {
+                        Store::write()
+                            .await
+                            .set_chain_choice(domain, network.dedup_chain_id())?;
+                    }
+
+                    return Ok(network.dedup_chain_id());
+                }
+                Some(DialogMsg::Close) | None => return Err(Error::UserRejectedDialog),
+            }
+        }
+    }
+
+    /// Offers to add a network for an unknown chain id
+    ///
+    /// The network the user describes goes through `ChainAdd`, which checks its RPCs before
+    /// adding it
+    async fn offer_add(&self) -> Result<DedupChainId> {
+        let dialog = self.dialog();
+        dialog.open().await?;
+
+        let mut params = loop {
+            match dialog.recv().await {
+                Some(DialogMsg::Data(msg)) if msg.as_str() == Some("reject") => {
+                    return Err(Error::UnrecognizedChainId(self.chain_id));
+                }
+                Some(DialogMsg::Data(msg)) => match serde_json::from_value(msg) {
+                    Ok(Choice::Add { network }) => break network,
+                    _ => continue,
+                },
+                Some(DialogMsg::Close) | None => {
+                    return Err(Error::UnrecognizedChainId(self.chain_id))
+                }
+            }
+        };
+        dialog.close().await?;
+
+        // the chain id isn't up to the user
+        params["chainId"] = json!(U64::from(self.chain_id));
+
+        ChainAdd::build()
+            .set_params(params)?
+            .build()
+            .await?
+            .run()
+            .await?;
+
+        Networks::read()
+            .await
+            .get_networks_by_chain_id(self.chain_id)
+            .map(|n| n.dedup_chain_id())
+            .min_by_key(|id| id.dedup_id())
+            .ok_or(Error::UnrecognizedChainId(self.chain_id))
+    }
+
+    fn dialog(&self) -> Dialog {
+        Dialog::new(
+            "chain-switch",
+            json!({
+                "domain": self.domain,
+                "oldId": self.current.chain_id(),
+                "newId": self.chain_id,
+                "networks": self.candidates.iter().map(Network::redacted).collect::<Vec<_>>(),
+            }),
+        )
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Params {
+    pub chain_id: U64,
+}
+
+#[derive(Debug, Default)]
+pub struct ChainSwitchBuilder {
+    domain: Option<String>,
+    current: Option<DedupChainId>,
+    params: Option<Params>,
+}
+
+impl ChainSwitchBuilder {
+    pub fn set_domain(mut self, domain: Option<String>) -> Self {
+        self.domain = domain;
+        self
+    }
+
+    /// The network the connection is currently on
+    pub fn set_current(mut self, current: DedupChainId) -> Self {
+        self.current = Some(current);
+        self
+    }
+
+    pub fn set_chain_id(mut self, chain_id: u32) -> Self {
+        self.params = Some(Params {
+            chain_id: U64::from(chain_id),
+        });
+        self
+    }
+
+    pub fn set_params(mut self, params: Json) -> Result<Self> {
+        let params = match params {
+            Json::Array(mut params) if !params.is_empty() => params.remove(0),
+            params => params,
+        };
+
+        self.params = Some(serde_json::from_value(params)?);
+        Ok(self)
+    }
+
+    pub async fn build(self) -> Result<ChainSwitch> {
+        let chain_id: u32 = self
+            .params
+            .ok_or(Error::ParseError)?
+            .chain_id
+            .try_into()
+            .map_err(|_| Error::ParseError)?;
+
+        let mut candidates: Vec<_> = Networks::read()
+            .await
+            .get_networks_by_chain_id(chain_id)
+            .collect();
+        candidates.sort_by_key(|n| n.dedup_chain_id().dedup_id());
+
+        Ok(ChainSwitch {
+            domain: self.domain,
+            chain_id,
+            current: self.current.unwrap_or_else(|| (chain_id, 0).into()),
+            candidates,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dialog_choices() {
+        let choice: Choice = serde_json::from_value(json!({
+            "type": "select",
+            "dedupId": 2,
+            "remember": true,
+        }))
+        .unwrap();
+
+        assert!(matches!(
+            choice,
+            Choice::Select {
+                dedup_id: 2,
+                remember: true
+            }
+        ));
+        assert!(serde_json::from_value::<Choice>(json!("accept")).is_err());
+
+        let choice: Choice = serde_json::from_value(json!({
+            "type": "add",
+            "network": { "chainName": "Local" },
+        }))
+        .unwrap();
+        assert!(matches!(choice, Choice::Add { .. }));
+    }
+}
@@ -4,7 +4,7 @@ use ethui_networks::Networks;
 use ethui_types::{GlobalState, Network, NewNetworkParams};
 use serde::Serialize;
 
-use super::chain_add::Params;
+use super::{chain_add::Params, chain_switch::selects};
 
 #[derive(Debug)]
 pub struct ChainUpdate {
@@ -51,15 +51,19 @@ impl ChainUpdate {
 
         while let Some(msg) = switch_dialog.recv().await {
             match msg {
-                DialogMsg::Data(msg) => {
-                    if let Some("accept") = msg.as_str() {
-                        let mut networks = Networks::write().await;
-                        networks
-                            .set_current_by_dedup_chain_id(self.network.dedup_chain_id())
-                            .await?;
-                        break;
-                    }
+                DialogMsg::Data(msg) if msg.as_str() == Some("reject") => {
+                    return Err(Error::UserRejectedDialog)
+                }
+                DialogMsg::Data(msg)
+                    if msg.as_str() == Some("accept") || selects(&msg, &self.network) =>
+                {
+                    let mut networks = Networks::write().await;
+                    networks
+                        .set_current_by_dedup_chain_id(self.network.dedup_chain_id())
+                        .await?;
+                    break;
                 }
+                DialogMsg::Data(_) => {}
                 DialogMsg::Close => return Err(Error::UserRejectedDialog),
             }
         }
@@ -73,6 +77,7 @@ impl ChainUpdate {
         NetworkSwitch {
             old_id: current_chain.chain_id(),
             new_id: self.network.chain_id(),
+            networks: vec![self.network.clone()],
         }
     }
 
@@ -92,6 +97,7 @@ impl ChainUpdate {
 pub struct NetworkSwitch {
     pub old_id: u32,
     pub new_id: u32,
+    pub networks: Vec<Network>,
 }
 
 #[derive(Default)]
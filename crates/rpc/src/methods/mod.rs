@@ -1,13 +1,19 @@
 mod chain_add;
+mod chain_switch;
 mod chain_update;
 mod send_call;
 mod send_transaction;
+mod session_create;
+mod sign_authorization;
 mod sign_message;
 mod token_add;
 
 pub use chain_add::ChainAdd;
+pub use chain_switch::ChainSwitch;
 pub use chain_update::ChainUpdate;
 pub use send_call::SendCall;
 pub use send_transaction::SendTransaction;
+pub use session_create::SessionCreate;
+pub use sign_authorization::SignAuthorization;
 pub use sign_message::SignMessage;
 pub use token_add::TokenAdd;
@@ -73,10 +73,10 @@ impl<'a> SendCallBuilder<'a> {
         Ok(self)
     }
 
-    pub async fn build(self) -> SendCall {
-        SendCall {
-            network: self.ctx.network().await,
+    pub async fn build(self) -> Result<SendCall> {
+        Ok(SendCall {
+            network: crate::secrets::unlock(self.ctx, self.ctx.network().await).await?,
             request: self.request,
-        }
+        })
     }
 }
@@ -2,17 +2,17 @@ use std::str::FromStr;
 
 use alloy::{
     network::{Ethereum, TransactionBuilder as _},
-    primitives::{Bytes, U256},
-    providers::{ext::AnvilApi, PendingTransactionBuilder, Provider, ProviderBuilder},
+    primitives::{Bytes, B256, U256},
+    providers::{ext::AnvilApi, Provider, ProviderBuilder},
     rpc::types::TransactionRequest,
 };
 use ethui_connections::Ctx;
 use ethui_dialogs::{Dialog, DialogMsg};
 use ethui_settings::Settings;
 use ethui_types::{Address, GlobalState, Network};
-use ethui_wallets::{WalletControl, WalletType, Wallets};
+use ethui_wallets::{Wallet, WalletControl, WalletType, Wallets};
 
-use crate::{Error, Result};
+use crate::{ens, Error, Result};
 
 /// Orchestrates the signing of a transaction
 /// Takes references to both the wallet and network where this
@@ -49,7 +49,13 @@ impl SendTransaction {
         self
     }
 
-    pub async fn finish(&mut self) -> Result<PendingTransactionBuilder<Ethereum>> {
+    /// Returns the transaction hash, or the UserOperation hash for smart accounts
+    pub async fn finish(&mut self) -> Result<B256> {
+        // refuse early, before the user is asked to review anything
+        if self.wallet_type == WalletType::WatchOnly {
+            return Err(Error::WatchOnlyWallet(self.wallet_name.clone()));
+        }
+
         // inner scope so as not to lock wallets for the entire duration of the tx review
         let skip = {
             let wallets = Wallets::read().await;
@@ -68,11 +74,20 @@ impl SendTransaction {
         }
     }
 
-    async fn dialog_and_send(&mut self) -> Result<PendingTransactionBuilder<Ethereum>> {
+    async fn dialog_and_send(&mut self) -> Result<B256> {
         let mut params = serde_json::to_value(&self.request).unwrap();
         params["chainId"] = self.network.chain_id().into();
         params["walletType"] = self.wallet_type.to_string().into();
 
+        // address book / ENS name of the recipient, if known
+        if let Some(to) = self.request.to.and_then(|kind| kind.to().copied()) {
+            let label = ens::resolve_address_label(self.network.chain_id(), to)
+                .await
+                .ok()
+                .flatten();
+            params["toLabel"] = serde_json::to_value(label)?;
+        }
+
         let dialog = Dialog::new("tx-review", params);
         dialog.open().await?;
 
@@ -133,11 +148,36 @@ impl SendTransaction {
         Ok(())
     }
 
-    async fn send(&mut self) -> Result<PendingTransactionBuilder<Ethereum>> {
+    async fn send(&mut self) -> Result<B256> {
+        if self.is_contract_wallet() {
+            return self.send_through_contract_wallet().await;
+        }
+
         self.build_provider().await?;
         let provider = self.provider.as_ref().unwrap();
         let pending = provider.send_transaction(self.request.clone()).await?;
-        Ok(pending)
+        Ok(*pending.tx_hash())
+    }
+
+    /// Contract wallets are never impersonated, even on dev networks, so that the full
+    /// signing & execution flow can be tested against anvil
+    ///
+    /// Smart accounts return as soon as the bundler accepts the UserOperation, with its hash
+    async fn send_through_contract_wallet(&self) -> Result<B256> {
+        // wallets must not be locked during execution, since owners are looked up there
+        let wallet = Wallets::read()
+            .await
+            .get(&self.wallet_name)
+            .cloned()
+            .ok_or_else(|| Error::WalletNameNotFound(self.wallet_name.clone()))?;
+
+        match wallet {
+            Wallet::Safe(safe) => Ok(*safe.execute(&self.network, &self.request).await?.tx_hash()),
+            Wallet::SmartAccount(account) => {
+                Ok(account.execute(&self.network, &self.request).await?)
+            }
+            _ => Err(Error::WalletNameNotFound(self.wallet_name.clone())),
+        }
     }
 
     async fn build_provider(&mut self) -> Result<()> {
@@ -151,19 +191,12 @@ impl SendTransaction {
             .ok_or(Error::WalletNameNotFound(self.wallet_name.clone()))?
             .clone();
 
-        let url = self
-            .network
-            .http_url
-            .to_string()
-            .parse()
-            .map_err(|_| Error::CannotParseUrl(self.network.http_url.to_string().clone()))?;
-
         self.provider = if self.network.is_dev().await {
             // TODO: maybe we can find a way to only do this once for every account,
             // or only call anvil_autoImpersonate once for the whole network,
             // instead of making this request for every single transaction.
             // this is just a minor optimization, though
-            let provider = ProviderBuilder::new().on_http(url);
+            let provider = ProviderBuilder::new().on_client(self.network.rpc_client()?);
             provider.anvil_auto_impersonate_account(true).await?;
             Some(Box::new(provider))
         } else {
@@ -172,7 +205,7 @@ impl SendTransaction {
                 .await?;
             let provider = ProviderBuilder::new()
                 .wallet(signer.to_wallet())
-                .on_http(url);
+                .on_client(self.network.rpc_client()?);
             Some(Box::new(provider))
         };
 
@@ -213,6 +246,13 @@ impl SendTransaction {
     fn is_ledger(&self) -> bool {
         self.wallet_type == WalletType::Ledger
     }
+
+    fn is_contract_wallet(&self) -> bool {
+        matches!(
+            self.wallet_type,
+            WalletType::Safe | WalletType::SmartAccount
+        )
+    }
 }
 
 pub struct SendTransactionBuilder<'a> {
@@ -245,9 +285,14 @@ impl<'a> SendTransactionBuilder<'a> {
             &params
         };
 
+        // without an explicit sender, domains with a burner address use it
+        let from = match params["from"].as_str() {
+            Some(from) => Some(Address::from_str(from).unwrap()),
+            None => self.ctx.unlock_burner().await?.map(|burner| burner.address),
+        };
+
         let wallets = Wallets::read().await;
-        if let Some(from) = params["from"].as_str() {
-            let address = Address::from_str(from).unwrap();
+        if let Some(address) = from {
             self.request.set_from(address);
 
             let (wallet, path) = wallets
@@ -279,17 +324,23 @@ impl<'a> SendTransactionBuilder<'a> {
             self.request.set_input(Bytes::from_str(data).unwrap());
         }
 
+        // EIP-7702 authorizations turn this into a type-4 transaction
+        if !params["authorizationList"].is_null() {
+            self.request.authorization_list =
+                Some(serde_json::from_value(params["authorizationList"].clone())?);
+        }
+
         Ok(self)
     }
 
-    pub async fn build(self) -> SendTransaction {
-        SendTransaction {
+    pub async fn build(self) -> Result<SendTransaction> {
+        Ok(SendTransaction {
             wallet_name: self.wallet_name.unwrap(),
             wallet_path: self.wallet_path.unwrap(),
             wallet_type: self.wallet_type.unwrap(),
-            network: self.ctx.network().await,
+            network: crate::secrets::unlock(self.ctx, self.ctx.network().await).await?,
             request: self.request,
             provider: None,
-        }
+        })
     }
 }
@@ -0,0 +1,253 @@
+use std::collections::{BTreeMap, HashMap};
+
+use ethui_connections::{
+    sessions::{parse_scope, Session, SessionScope},
+    Store,
+};
+use ethui_dialogs::{Dialog, DialogMsg};
+use ethui_networks::Networks;
+use ethui_types::{DedupChainId, GlobalState, Json};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::ChainSwitch;
+use crate::{Error, Result};
+
+/// Methods that can be authorized within a session scope. Anything that changes the
+/// connection's own state, such as its active chain or permissions, is left out
+pub(crate) const SESSION_METHODS: &[&str] = &[
+    "eth_accounts",
+    "eth_chainId",
+    "eth_blockNumber",
+    "eth_sendRawTransaction",
+    "eth_getBalance",
+    "eth_getStorageAt",
+    "eth_getTransactionCount",
+    "eth_getCode",
+    "eth_call",
+    "eth_estimateGas",
+    "eth_protocolVersion",
+    "eth_syncing",
+    "eth_mining",
+    "net_version",
+    "eth_getBlockTransactionCountByHash",
+    "eth_getBlockTransactionCountByNumber",
+    "eth_getUncleCountByBlockHash",
+    "eth_getUncleCountByBlockNumber",
+    "eth_getBlockByHash",
+    "eth_getBlockByNumber",
+    "eth_getTransactionByHash",
+    "eth_getTransactionByBlockHashAndIndex",
+    "eth_getTransactionByBlockNumberAndIndex",
+    "eth_getTransactionReceipt",
+    "eth_getUncleByBlockHashAndIndex",
+    "eth_getUncleByBlockNumberAndIndex",
+    "eth_newFilter",
+    "eth_newBlockFilter",
+    "eth_newPendingFilter",
+    "eth_uninstallFilter",
+    "eth_getFilterLogs",
+    "eth_getLogs",
+    "eth_sendTransaction",
+    "eth_sign",
+    "personal_sign",
+    "eth_signTypedData",
+    "eth_signTypedData_v4",
+    "wallet_watchAsset",
+];
+
+#[derive(Debug)]
+pub struct SessionCreate {
+    domain: String,
+    session: Session,
+}
+
+impl SessionCreate {
+    pub fn build() -> SessionCreateBuilder {
+        SessionCreateBuilder::default()
+    }
+
+    /// Asks the user to approve the session, replacing the domain's current one if approved
+    #[tracing::instrument(skip(self))]
+    pub async fn run(self) -> Result<Session> {
+        let dialog = Dialog::new(
+            "session-create",
+            json!({
+                "domain": self.domain,
+                "scopes": self.session.scopes,
+            }),
+        );
+        dialog.open().await?;
+
+        let approved = loop {
+            match dialog.recv().await {
+                Some(DialogMsg::Data(msg)) if msg.as_str() == Some("accept") => break true,
+                Some(DialogMsg::Data(msg)) if msg.as_str() == Some("reject") => break false,
+                Some(DialogMsg::Data(_)) => continue,
+                Some(DialogMsg::Close) | None => break false,
+            }
+        };
+
+        if !approved {
+            return Err(Error::UserRejectedDialog);
+        }
+
+        Store::write()
+            .await
+            .set_session(&self.domain, self.session.clone())?;
+
+        Ok(self.session)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Params {
+    pub required_scopes: HashMap<String, ScopeRequest>,
+    pub optional_scopes: HashMap<String, ScopeRequest>,
+    pub session_properties: serde_json::Map<String, Json>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ScopeRequest {
+    pub methods: Vec<String>,
+    pub notifications: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct SessionCreateBuilder {
+    domain: Option<String>,
+    current: Option<DedupChainId>,
+    params: Option<Params>,
+}
+
+impl SessionCreateBuilder {
+    pub fn set_domain(mut self, domain: String) -> Self {
+        self.domain = Some(domain);
+        self
+    }
+
+    /// The network the connection is currently on
+    pub fn set_current(mut self, current: DedupChainId) -> Self {
+        self.current = Some(current);
+        self
+    }
+
+    pub fn set_params(mut self, params: Json) -> Result<Self> {
+        let params = match params {
+            Json::Array(mut params) if !params.is_empty() => params.remove(0),
+            params => params,
+        };
+
+        self.params = Some(serde_json::from_value(params)?);
+        Ok(self)
+    }
+
+    /// Resolves each requested scope to a known network
+    ///
+    /// Unknown optional scopes are dropped, but a single unknown required scope fails the whole
+    /// request. When several networks share a scope's chain id, the one the domain would switch
+    /// to is used
+    pub async fn build(mut self) -> Result<SessionCreate> {
+        let params = self.params.take().unwrap_or_default();
+
+        let requested: Vec<_> = {
+            let networks = Networks::read().await;
+
+            params
+                .required_scopes
+                .into_iter()
+                .map(|(scope, request)| (scope, request, true))
+                .chain(
+                    params
+                        .optional_scopes
+                        .into_iter()
+                        .map(|(scope, request)| (scope, request, false)),
+                )
+                .map(|(scope, request, required)| {
+                    let chain_id = parse_scope(&scope).filter(|id| networks.validate_chain_id(*id));
+                    (scope, request, required, chain_id)
+                })
+                .collect()
+        };
+
+        let mut scopes: BTreeMap<String, SessionScope> = BTreeMap::new();
+
+        for (scope, request, required, chain_id) in requested {
+            let chain = match chain_id {
+                Some(chain_id) => self.network_for(chain_id).await?,
+                None => None,
+            };
+
+            let Some(chain) = chain else {
+                if required {
+                    return Err(Error::UnsupportedScope(scope));
+                }
+                continue;
+            };
+
+            let entry = scopes.entry(scope).or_insert_with(|| SessionScope {
+                chain,
+                methods: vec![],
+                notifications: vec![],
+            });
+
+            for method in allowed_methods(&request.methods) {
+                if !entry.methods.contains(&method) {
+                    entry.methods.push(method);
+                }
+            }
+        }
+
+        if scopes.is_empty() {
+            return Err(Error::NoSupportedScopes);
+        }
+
+        Ok(SessionCreate {
+            domain: self.domain.unwrap_or_default(),
+            session: Session {
+                scopes,
+                properties: params.session_properties,
+            },
+        })
+    }
+
+    /// The network a scope is routed to, as chosen for `wallet_switchEthereumChain`
+    async fn network_for(&self, chain_id: u32) -> Result<Option<DedupChainId>> {
+        let mut switch = ChainSwitch::build()
+            .set_domain(self.domain.clone())
+            .set_chain_id(chain_id);
+        if let Some(current) = self.current {
+            switch = switch.set_current(current);
+        }
+
+        Ok(switch.build().await?.preferred().await)
+    }
+}
+
+/// The requested methods that can be authorized, or all of them if none were requested
+fn allowed_methods(requested: &[String]) -> Vec<String> {
+    if requested.is_empty() {
+        return SESSION_METHODS.iter().map(|m| m.to_string()).collect();
+    }
+
+    requested
+        .iter()
+        .filter(|m| SESSION_METHODS.contains(&m.as_str()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_supported_methods_are_allowed() {
+        let requested = vec!["eth_call".into(), "wallet_switchEthereumChain".into()];
+
+        assert_eq!(allowed_methods(&requested), vec!["eth_call".to_string()]);
+        assert_eq!(allowed_methods(&[]).len(), SESSION_METHODS.len());
+    }
+}
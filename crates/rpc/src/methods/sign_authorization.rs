@@ -0,0 +1,213 @@
+use alloy::{
+    eips::eip7702::{Authorization, SignedAuthorization},
+    providers::Provider as _,
+};
+use ethui_connections::Ctx;
+use ethui_db::utils::{fetch_etherscan_abi, fetch_etherscan_contract_name};
+use ethui_dialogs::{Dialog, DialogMsg};
+use ethui_types::{Address, GlobalState, Json, Network, U256};
+use ethui_wallets::{WalletControl, WalletType, Wallets};
+use serde_json::json;
+use tracing::warn;
+
+use crate::{Error, Result};
+
+/// Orchestrates the signing of an EIP-7702 authorization
+/// The user reviews the delegate contract before anything is signed
+///
+/// Only reachable from the app itself, never from dapps: an authorization hands the account's
+/// code over to the delegate
+pub struct SignAuthorization {
+    pub wallet_name: String,
+    pub wallet_path: String,
+    pub network: Network,
+    pub authorization: Authorization,
+}
+
+impl SignAuthorization {
+    pub fn build(ctx: &Ctx) -> SignAuthorizationBuilder<'_> {
+        SignAuthorizationBuilder::new(ctx)
+    }
+
+    pub async fn finish(&self) -> Result<SignedAuthorization> {
+        self.spawn_dialog().await?;
+        self.sign().await
+    }
+
+    async fn spawn_dialog(&self) -> Result<()> {
+        let params = json!({
+            "authority": self.authority().await?,
+            "delegate": self.authorization.address,
+            "chainId": self.authorization.chain_id,
+            "nonce": self.authorization.nonce,
+            "contract": self.delegate_contract().await,
+        });
+
+        let dialog = Dialog::new("authorization-review", params);
+        dialog.open().await?;
+
+        match dialog.recv().await {
+            Some(DialogMsg::Data(msg)) if msg.as_str() == Some("accept") => Ok(()),
+            _ => Err(Error::SignatureRejected),
+        }
+    }
+
+    /// The delegate's name and ABI, either from a contract we already know of, or from its
+    /// verified source on etherscan
+    async fn delegate_contract(&self) -> Option<Json> {
+        let chain_id = self.network.chain_id();
+        let delegate = self.authorization.address;
+
+        if let Ok(Some(contract)) = ethui_db::get().get_contract(chain_id, delegate).await {
+            return Some(json!({
+                "name": contract.name,
+                "abi": contract.abi,
+                "source": "known",
+            }));
+        }
+
+        if self.network.is_dev().await {
+            return None;
+        }
+
+        let chain = u64::from(chain_id).into();
+        match fetch_etherscan_abi(chain, delegate).await {
+            Ok(Some(abi)) => Some(json!({
+                "name": fetch_etherscan_contract_name(chain, delegate).await.ok().flatten(),
+                "abi": abi,
+                "source": "etherscan",
+            })),
+            Ok(None) => None,
+            Err(e) => {
+                warn!(?delegate, "failed to fetch delegate source: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn sign(&self) -> Result<SignedAuthorization> {
+        // building the signer may prompt the user to unlock the wallet, which must not happen
+        // while holding the lock
+        let wallet = Wallets::read()
+            .await
+            .get(&self.wallet_name)
+            .cloned()
+            .ok_or_else(|| Error::WalletNameNotFound(self.wallet_name.clone()))?;
+
+        let signer = wallet
+            .build_signer(self.network.chain_id(), &self.wallet_path)
+            .await?;
+
+        Ok(signer
+            .sign_authorization(self.authorization.clone())
+            .await?)
+    }
+
+    async fn authority(&self) -> Result<Address> {
+        let wallets = Wallets::read().await;
+        let wallet = wallets
+            .get(&self.wallet_name)
+            .ok_or_else(|| Error::WalletNameNotFound(self.wallet_name.clone()))?;
+
+        Ok(wallet.get_address(&self.wallet_path).await?)
+    }
+}
+
+pub struct SignAuthorizationBuilder<'a> {
+    ctx: &'a Ctx,
+    from: Option<Address>,
+    delegate: Option<Address>,
+    self_sponsored: bool,
+}
+
+impl<'a> SignAuthorizationBuilder<'a> {
+    pub fn new(ctx: &'a Ctx) -> Self {
+        Self {
+            ctx,
+            from: None,
+            delegate: None,
+            self_sponsored: false,
+        }
+    }
+
+    pub fn set_from(mut self, from: Address) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    pub fn set_delegate(mut self, delegate: Address) -> Self {
+        self.delegate = Some(delegate);
+        self
+    }
+
+    /// The authorization will be included in a transaction sent by the authority itself,
+    /// which increments its nonce before the authorization is processed
+    pub fn self_sponsored(mut self) -> Self {
+        self.self_sponsored = true;
+        self
+    }
+
+    pub async fn build(self) -> Result<SignAuthorization> {
+        let network = crate::secrets::unlock(self.ctx, self.ctx.network().await).await?;
+
+        let from = match self.from {
+            Some(from) => Some(from),
+            None => self.ctx.unlock_burner().await?.map(|burner| burner.address),
+        };
+
+        let (wallet_name, wallet_path, authority) = {
+            let wallets = Wallets::read().await;
+
+            let (wallet, path, authority) = match from {
+                Some(from) => {
+                    let (wallet, path) = wallets
+                        .find(from)
+                        .await
+                        .ok_or(Error::WalletNotFound(from))?;
+                    (wallet, path, from)
+                }
+                None => {
+                    let wallet = wallets.get_current_wallet();
+                    (
+                        wallet,
+                        wallet.get_current_path(),
+                        wallet.get_current_address().await,
+                    )
+                }
+            };
+
+            let wallet_type = WalletType::from(wallet);
+            if wallet_type == WalletType::WatchOnly {
+                return Err(Error::WatchOnlyWallet(wallet.name()));
+            }
+
+            // checked before the user reviews anything, rather than failing once they accept
+            if !wallet_type.can_sign_hash() {
+                return Err(ethui_wallets::Error::CantSignHash(wallet.name()).into());
+            }
+
+            (wallet.name(), path, authority)
+        };
+
+        let nonce = network
+            .get_provider()?
+            .get_transaction_count(authority)
+            .await?
+            + self.self_sponsored as u64;
+
+        // always bound to the current network. A chain id of 0 would make the authorization
+        // valid on every chain
+        let chain_id = network.chain_id();
+
+        Ok(SignAuthorization {
+            wallet_name,
+            wallet_path,
+            network,
+            authorization: Authorization {
+                chain_id: U256::from(chain_id),
+                address: self.delegate.ok_or(Error::ParseError)?,
+                nonce,
+            },
+        })
+    }
+}
@@ -8,7 +8,7 @@ use alloy::{
 use ethui_dialogs::{Dialog, DialogMsg};
 use ethui_settings::Settings;
 use ethui_types::{GlobalState, Network};
-use ethui_wallets::{Signer, Wallet, WalletControl};
+use ethui_wallets::{Signer, Wallet, WalletControl, WalletType};
 use serde::Serialize;
 
 use crate::{Error, Result};
@@ -28,6 +28,10 @@ impl<'a> SignMessage<'a> {
     }
 
     pub async fn finish(&mut self) -> Result<PrimitiveSignature> {
+        if WalletType::from(self.wallet) == WalletType::WatchOnly {
+            return Err(Error::WatchOnlyWallet(self.wallet.name()));
+        }
+
         let skip = {
             self.network.is_dev().await
                 && self.wallet.is_dev()
@@ -64,7 +68,7 @@ impl<'a> SignMessage<'a> {
     }
 
     pub async fn sign(&mut self) -> Result<PrimitiveSignature> {
-        let signer = self.build_signer().await;
+        let signer = self.build_signer().await?;
 
         match self.data {
             Data::Raw(ref msg) => {
@@ -75,11 +79,11 @@ impl<'a> SignMessage<'a> {
         }
     }
 
-    async fn build_signer(&self) -> Signer {
-        self.wallet
+    async fn build_signer(&self) -> Result<Signer> {
+        Ok(self
+            .wallet
             .build_signer(self.network.chain_id(), &self.wallet_path)
-            .await
-            .unwrap()
+            .await?)
     }
 }
 
@@ -1,14 +1,23 @@
-use std::collections::HashSet;
-
+use alloy::{
+    network::Ethereum,
+    primitives::{fixed_bytes, FixedBytes},
+    providers::{Provider as _, RootProvider},
+};
+use ethui_abis::{IERC721WithMetadata, IERC1155, IERC165, IERC20, IERC721};
+use ethui_connections::Ctx;
 use ethui_dialogs::{Dialog, DialogMsg};
-use ethui_networks::Networks;
-use ethui_sync::{get_alchemy, Erc20Metadata, ErcMetadataResponse, ErcOwnersResponse};
-use ethui_types::{Address, GlobalState, TokenMetadata, U256};
-use ethui_wallets::{WalletControl, Wallets};
+use ethui_sync::{
+    get_alchemy, Erc20Metadata, ErcContract, ErcImageData, ErcMetadataResponse, ErcRawMetadata,
+};
+use ethui_types::{Address, Json, Network, TokenMetadata, U256};
 use serde::{Deserialize, Serialize};
 
 use crate::{Error, Result};
 
+/// ERC-165 interface ids
+const ERC721_INTERFACE_ID: FixedBytes<4> = fixed_bytes!("80ac58cd");
+const ERC1155_INTERFACE_ID: FixedBytes<4> = fixed_bytes!("d9b67a26");
+
 #[derive(Debug)]
 pub struct TokenAdd {
     erc20_token: Option<TokenMetadata>,
@@ -16,12 +25,18 @@ pub struct TokenAdd {
     erc1155_token: Option<ERC1155Data>,
     chain_id: Option<u32>,
     _type: String,
+
+    /// the requesting connection's network
+    network: Network,
+
+    /// the address exposed to the requesting connection, which must own the token
+    wallet_address: Address,
 }
 
 #[derive(Debug, Serialize, Clone)]
 pub struct Erc20FullData {
     metadata: TokenMetadata,
-    alchemy_metadata: Erc20Metadata,
+    alchemy_metadata: Option<Erc20Metadata>,
 }
 
 #[derive(Debug, Serialize)]
@@ -37,161 +52,200 @@ struct ERC1155Data {
 }
 
 impl TokenAdd {
-    pub fn build() -> TokenAddBuilder {
-        TokenAddBuilder::default()
+    pub fn build(ctx: &Ctx) -> TokenAddBuilder<'_> {
+        TokenAddBuilder::new(ctx)
     }
 
-    pub async fn get_current_chain_id(&self) -> u32 {
-        let networks = Networks::read().await;
-        networks.get_current().chain_id()
+    /// The token's address, whichever its type
+    fn address(&self) -> Result<Address> {
+        self.erc20_token
+            .as_ref()
+            .map(|t| t.address)
+            .or(self.erc721_token.as_ref().map(|t| t.address))
+            .or(self.erc1155_token.as_ref().map(|t| t.address))
+            .ok_or(Error::ParseError)
     }
 
-    pub async fn get_current_wallet_address(&self) -> Address {
-        let wallets = Wallets::read().await;
-        wallets.get_current_wallet().get_current_address().await
+    fn token_id(&self) -> Result<U256> {
+        self.erc721_token
+            .as_ref()
+            .map(|t| t.token_id)
+            .or(self.erc1155_token.as_ref().map(|t| t.token_id))
+            .ok_or(Error::ParseError)
     }
 
-    pub async fn get_erc20_metadata(&self, chain_id: u32) -> Result<Erc20Metadata> {
-        if let Some(erc20_token) = &self.erc20_token {
-            let alchemy = get_alchemy(chain_id).await.unwrap();
-            let metadata = alchemy
-                .fetch_erc20_metadata(erc20_token.address)
-                .await
-                .map_err(|_| Error::ParseError)?;
-            Ok(metadata)
-        } else {
-            Err(Error::ParseError)
+    /// Fails if there's no contract at the token's address
+    pub async fn check_contract(&self, provider: &RootProvider<Ethereum>) -> Result<()> {
+        let code = provider
+            .get_code_at(self.address()?)
+            .await
+            .map_err(|_| Error::TokenInvalid)?;
+
+        if code.is_empty() {
+            return Err(Error::TokenInvalid);
         }
+
+        Ok(())
     }
 
-    pub async fn get_erc_metadata(&self, chain_id: u32) -> Result<ErcMetadataResponse> {
-        let alchemy = get_alchemy(chain_id).await.unwrap();
-        match self._type.as_str() {
-            "ERC721" => {
-                if let Some(erc721_token) = &self.erc721_token {
-                    let metadata_response = alchemy
-                        .fetch_erc_metadata(
-                            erc721_token.address,
-                            erc721_token.token_id,
-                            self._type.clone(),
-                        )
-                        .await
-                        .map_err(|_| Error::ParseError)?;
-                    Ok(metadata_response)
-                } else {
-                    Err(Error::ParseError)
-                }
-            }
-            "ERC1155" => {
-                if let Some(erc1155_token) = &self.erc1155_token {
-                    let metadata_response = alchemy
-                        .fetch_erc_metadata(
-                            erc1155_token.address,
-                            erc1155_token.token_id,
-                            self._type.clone(),
-                        )
-                        .await
-                        .map_err(|_| Error::ParseError)?;
-                    Ok(metadata_response)
-                } else {
-                    Err(Error::ParseError)
-                }
-            }
-            _ => Err(Error::TypeInvalid(self._type.clone())),
+    /// Reads an ERC20's metadata from the token itself
+    pub async fn get_erc20_metadata(&self, provider: &RootProvider<Ethereum>) -> TokenMetadata {
+        let address = self.address().unwrap_or_default();
+        let contract = IERC20::new(address, provider);
+
+        TokenMetadata {
+            address,
+            name: contract.name().call().await.ok().map(|r| r.name),
+            symbol: contract.symbol().call().await.ok().map(|r| r.symbol),
+            decimals: contract.decimals().call().await.ok().map(|r| r.decimals),
         }
     }
 
-    pub async fn get_erc_owners(&self, chain_id: u32) -> Result<ErcOwnersResponse> {
-        let alchemy = get_alchemy(chain_id).await.unwrap();
-        match self._type.as_str() {
-            "ERC721" => {
-                if let Some(erc721_token) = &self.erc721_token {
-                    let owners_response = alchemy
-                        .fetch_erc_owners(erc721_token.address)
-                        .await
-                        .map_err(|_| Error::ParseError)?;
-                    Ok(owners_response)
-                } else {
-                    Err(Error::ParseError)
-                }
-            }
-            "ERC1155" => {
-                if let Some(erc1155_token) = &self.erc1155_token {
-                    let owners_response = alchemy
-                        .fetch_erc_owners(erc1155_token.address)
-                        .await
-                        .map_err(|_| Error::ParseError)?;
-                    Ok(owners_response)
-                } else {
-                    Err(Error::ParseError)
-                }
-            }
-            _ => Err(Error::TypeInvalid(self._type.clone())),
+    /// Alchemy's metadata for an ERC20, if an API key is set and the chain is supported
+    pub async fn get_erc20_alchemy_metadata(&self, chain_id: u32) -> Option<Erc20Metadata> {
+        let alchemy = get_alchemy(chain_id).await.ok()?;
+
+        alchemy
+            .fetch_erc20_metadata(self.address().ok()?)
+            .await
+            .ok()
+    }
+
+    /// Reads an ERC721 or ERC1155 token's metadata from the contract itself
+    ///
+    /// Only the token URI is known on-chain. The JSON metadata it points to is left for Alchemy
+    /// to fill in, when available
+    pub async fn get_erc_metadata(
+        &self,
+        provider: &RootProvider<Ethereum>,
+    ) -> Result<ErcMetadataResponse> {
+        let address = self.address()?;
+        let token_id = self.token_id()?;
+
+        // ERC1155 has no standard name or symbol, but many collections implement them anyway
+        let contract = IERC721::new(address, provider);
+        let name = contract.name().call().await.map(|r| r.name);
+        let symbol = contract.symbol().call().await.map(|r| r.symbol);
+
+        let token_uri = match self._type.as_str() {
+            "ERC721" => IERC721WithMetadata::new(address, provider)
+                .tokenURI(token_id)
+                .call()
+                .await
+                .map(|r| r.uri),
+            "ERC1155" => IERC1155::new(address, provider)
+                .uri(token_id)
+                .call()
+                .await
+                .map(|r| erc1155_uri(&r.uri, token_id)),
+            _ => return Err(Error::TypeInvalid(self._type.clone())),
+        };
+
+        Ok(ErcMetadataResponse {
+            contract: ErcContract {
+                address,
+                name: name.unwrap_or_default(),
+                symbol: symbol.unwrap_or_default(),
+                token_type: self.get_erc_type(provider).await,
+            },
+            token_id,
+            image: ErcImageData {
+                original_url: Default::default(),
+            },
+            raw: ErcRawMetadata {
+                token_uri: token_uri.unwrap_or_default(),
+                metadata: Json::Object(Default::default()),
+            },
+            collection: None,
+            balance: None,
+        })
+    }
+
+    /// Fills in what couldn't be read on-chain with Alchemy's metadata, if available
+    pub async fn enrich_erc_metadata(
+        &self,
+        chain_id: u32,
+        mut metadata: ErcMetadataResponse,
+    ) -> ErcMetadataResponse {
+        let Ok(alchemy) = get_alchemy(chain_id).await else {
+            return metadata;
+        };
+
+        let Ok(enriched) = alchemy
+            .fetch_erc_metadata(
+                metadata.contract.address,
+                metadata.token_id,
+                self._type.clone(),
+            )
+            .await
+        else {
+            return metadata;
+        };
+
+        if metadata.contract.name.is_empty() {
+            metadata.contract.name = enriched.contract.name;
+        }
+        if metadata.contract.symbol.is_empty() {
+            metadata.contract.symbol = enriched.contract.symbol;
+        }
+        if metadata.raw.token_uri.is_empty() {
+            metadata.raw.token_uri = enriched.raw.token_uri;
         }
+        metadata.raw.metadata = enriched.raw.metadata;
+        metadata.image = enriched.image;
+        metadata.collection = enriched.collection;
+
+        metadata
     }
 
-    pub async fn get_erc1155_balances(&self, chain_id: u32) -> Result<U256> {
-        if let Some(erc1155_token) = &self.erc1155_token {
-            let wallet_address = self.get_current_wallet_address().await;
-            let balances_response = self.get_erc_owners(chain_id).await.unwrap();
-
-            let owners: HashSet<String> = balances_response
-                .owners
-                .iter()
-                .map(|owner| owner.owner_address.clone())
-                .collect();
-
-            if !owners.contains(&wallet_address.to_string()) {
-                Err(Error::ErcWrongOwner)
-            } else {
-                for owner in balances_response.owners {
-                    if owner.owner_address == wallet_address.to_string() {
-                        for token_balance in owner.token_balances {
-                            if token_balance.token_id == erc1155_token.token_id.to_string() {
-                                let balance: U256 = token_balance
-                                    .balance
-                                    .parse()
-                                    .expect("Invalid number format");
-                                return Ok(balance);
-                            }
-                        }
-                    }
-                }
-                Err(Error::ErcInvalid)
-            }
-        } else {
-            Err(Error::ParseError)
+    /// The token type the contract reports via ERC-165
+    ///
+    /// Contracts that don't implement ERC-165 are assumed to be of the requested type
+    pub async fn get_erc_type(&self, provider: &RootProvider<Ethereum>) -> String {
+        let Ok(address) = self.address() else {
+            return self._type.clone();
+        };
+
+        let contract = IERC165::new(address, provider);
+        let erc721 = contract.supportsInterface(ERC721_INTERFACE_ID).call().await;
+        let erc1155 = contract
+            .supportsInterface(ERC1155_INTERFACE_ID)
+            .call()
+            .await;
+
+        match (erc721.map(|r| r.supported), erc1155.map(|r| r.supported)) {
+            (Ok(true), _) => "ERC721".into(),
+            (_, Ok(true)) => "ERC1155".into(),
+            (Ok(false), Ok(false)) => "unknown".into(),
+            _ => self._type.clone(),
         }
     }
 
+    /// The connection's balance of an ERC1155 token
+    pub async fn get_erc1155_balance(&self, provider: &RootProvider<Ethereum>) -> Result<U256> {
+        IERC1155::new(self.address()?, provider)
+            .balanceOf(self.wallet_address, self.token_id()?)
+            .call()
+            .await
+            .map(|r| r.balance)
+            .map_err(|_| Error::ErcInvalid)
+    }
+
     pub async fn set_erc20_metadata(
         &self,
         metadata: TokenMetadata,
-        alchemy_metadata: Erc20Metadata,
+        fetched: TokenMetadata,
     ) -> Result<TokenMetadata> {
-        // NOTE: metadata fetched from Alchemy is prioritized
+        // NOTE: metadata read from the token is prioritized over what the dapp claims
         let metadata = TokenMetadata {
             address: metadata.address,
-            name: if alchemy_metadata.name == Some("".to_string()) {
-                metadata.name
-            } else {
-                alchemy_metadata.name
-            },
-            symbol: if alchemy_metadata.symbol == Some("".to_string()) {
-                metadata.symbol
-            } else {
-                alchemy_metadata.symbol
-            },
-            decimals: if (alchemy_metadata.decimals.is_none()
-                || alchemy_metadata.decimals == Some(0))
-                && (metadata.decimals.is_none() || metadata.decimals == Some(0))
-            {
-                Some(18)
-            } else if alchemy_metadata.decimals.is_none() || alchemy_metadata.decimals == Some(0) {
-                metadata.decimals
-            } else {
-                alchemy_metadata.decimals
-            },
+            name: non_empty(fetched.name).or(non_empty(metadata.name)),
+            symbol: non_empty(fetched.symbol).or(non_empty(metadata.symbol)),
+            decimals: fetched
+                .decimals
+                .filter(|d| *d > 0)
+                .or(metadata.decimals.filter(|d| *d > 0))
+                .or(Some(18)),
         };
         Ok(metadata)
     }
@@ -205,7 +259,7 @@ impl TokenAdd {
     }
 
     pub async fn check_network(&self) -> Result<()> {
-        let current_chain_id = self.get_current_chain_id().await;
+        let current_chain_id = self.network.chain_id();
         let chain_id = self.chain_id.unwrap_or(current_chain_id);
         if current_chain_id != chain_id {
             return Err(Error::NetworkInvalid);
@@ -216,53 +270,63 @@ impl TokenAdd {
     pub async fn check_erc20_metadata(
         &self,
         metadata: TokenMetadata,
-        alchemy_metadata: Erc20Metadata,
+        fetched: TokenMetadata,
     ) -> Result<()> {
+        let fetched_symbol = non_empty(fetched.symbol).unwrap_or_default();
+        let symbol = non_empty(metadata.symbol).unwrap_or_default();
+
         // NOTE: symbol is required for the token to be added
-        if alchemy_metadata.symbol == Some("".to_string())
-            && (metadata.symbol.is_none() || metadata.symbol == Some("".to_string()))
-        {
+        if fetched_symbol.is_empty() && symbol.is_empty() {
             return Err(Error::SymbolMissing);
-        } else if alchemy_metadata.symbol.unwrap_or("".to_string()).len() > 11
-            || metadata.symbol.unwrap_or("".to_string()).len() > 11
-        {
+        } else if fetched_symbol.len() > 11 || symbol.len() > 11 {
             return Err(Error::SymbolInvalid);
-        } else if alchemy_metadata.decimals.unwrap_or(0) > 36 || metadata.decimals.unwrap_or(0) > 36
-        {
+        } else if fetched.decimals.unwrap_or(0) > 36 || metadata.decimals.unwrap_or(0) > 36 {
             return Err(Error::DecimalsInvalid);
         }
         Ok(())
     }
 
-    pub fn check_erc_type(&self, erc_data: Option<ErcMetadataResponse>) -> Result<()> {
-        let token_type = erc_data.unwrap().contract.token_type;
-        if token_type != self._type {
-            return Err(Error::ErcTypeInvalid(self._type.clone(), token_type));
+    pub fn check_erc_type(&self, erc_data: &ErcMetadataResponse) -> Result<()> {
+        let token_type = &erc_data.contract.token_type;
+        if *token_type != self._type {
+            return Err(Error::ErcTypeInvalid(
+                self._type.clone(),
+                token_type.clone(),
+            ));
         }
         Ok(())
     }
 
-    pub async fn check_erc_owner(&self, chain_id: u32) -> Result<()> {
-        let wallet_address = self.get_current_wallet_address().await;
-        let owners_response = self.get_erc_owners(chain_id).await.unwrap();
-
-        let owners: HashSet<String> = owners_response
-            .owners
-            .iter()
-            .map(|owner| owner.owner_address.clone())
-            .collect();
-        if !owners.contains(&wallet_address.to_string()) {
-            Err(Error::ErcWrongOwner)
-        } else {
-            Ok(())
+    /// Checks that the connection's address owns the token
+    pub async fn check_erc_owner(&self, provider: &RootProvider<Ethereum>) -> Result<()> {
+        let owns = match self._type.as_str() {
+            "ERC721" => {
+                IERC721::new(self.address()?, provider)
+                    .ownerOf(self.token_id()?)
+                    .call()
+                    .await
+                    .map_err(|_| Error::ErcInvalid)?
+                    .owner
+                    == self.wallet_address
+            }
+            "ERC1155" => self.get_erc1155_balance(provider).await? > U256::ZERO,
+            _ => return Err(Error::TypeInvalid(self._type.clone())),
+        };
+
+        if !owns {
+            return Err(Error::ErcWrongOwner);
         }
+
+        Ok(())
     }
 
     pub async fn run(self) -> Result<()> {
         self.check_type()?;
         self.check_network().await?;
 
-        let chain_id = self.get_current_chain_id().await;
+        let chain_id = self.network.chain_id();
+        let provider = self.network.get_provider()?;
+        self.check_contract(&provider).await?;
 
         let mut erc20_full_data: Option<Erc20FullData> = None;
         let mut erc721_full_data: Option<ErcMetadataResponse> = None;
@@ -270,18 +334,20 @@ impl TokenAdd {
 
         let dialog = match self._type.as_str() {
             "ERC20" => {
-                let alchemy_metadata = self
-                    .get_erc20_metadata(chain_id)
-                    .await
-                    .map_err(|_e| Error::TokenInvalid)?;
-                self.check_erc20_metadata(
-                    self.erc20_token.clone().unwrap(),
-                    alchemy_metadata.clone(),
-                )
-                .await?;
-                let final_metadata = self
-                    .set_erc20_metadata(self.erc20_token.clone().unwrap(), alchemy_metadata.clone())
+                let requested = self.erc20_token.clone().ok_or(Error::ParseError)?;
+                let alchemy_metadata = self.get_erc20_alchemy_metadata(chain_id).await;
+
+                // Alchemy only fills in what the token itself doesn't tell
+                let mut fetched = self.get_erc20_metadata(&provider).await;
+                if let Some(ref alchemy_metadata) = alchemy_metadata {
+                    fetched.name = non_empty(fetched.name).or(alchemy_metadata.name.clone());
+                    fetched.symbol = non_empty(fetched.symbol).or(alchemy_metadata.symbol.clone());
+                    fetched.decimals = fetched.decimals.or(alchemy_metadata.decimals);
+                }
+
+                self.check_erc20_metadata(requested.clone(), fetched.clone())
                     .await?;
+                let final_metadata = self.set_erc20_metadata(requested, fetched).await?;
                 erc20_full_data = Some(Erc20FullData {
                     metadata: final_metadata,
                     alchemy_metadata,
@@ -289,37 +355,24 @@ impl TokenAdd {
                 Dialog::new("erc20-add", serde_json::to_value(&erc20_full_data).unwrap())
             }
             "ERC721" => {
-                erc721_full_data = match self.get_erc_metadata(chain_id).await {
-                    Ok(metadata_response) => Some(metadata_response),
-                    Err(_e) => {
-                        return Err(Error::TokenInvalid);
-                    }
-                };
-                self.check_erc_type(erc721_full_data.clone())?;
-                self.check_erc_owner(chain_id).await?;
+                let metadata = self.get_erc_metadata(&provider).await?;
+                self.check_erc_type(&metadata)?;
+                self.check_erc_owner(&provider).await?;
+
+                erc721_full_data = Some(self.enrich_erc_metadata(chain_id, metadata).await);
                 Dialog::new(
                     "erc721-add",
                     serde_json::to_value(erc721_full_data.clone()).unwrap(),
                 )
             }
             "ERC1155" => {
-                let metadata_response = self
-                    .get_erc_metadata(chain_id)
-                    .await
-                    .map_err(|_e| Error::TokenInvalid)?;
-                erc1155_full_data = Some(metadata_response.clone());
-                self.check_erc_type(erc1155_full_data.clone())?;
-                self.check_erc_owner(chain_id).await?;
-                let balance = self.get_erc1155_balances(chain_id).await?;
-                erc1155_full_data = Some(ErcMetadataResponse {
-                    contract: metadata_response.contract,
-                    token_id: metadata_response.token_id,
-                    image: metadata_response.image,
-                    raw: metadata_response.raw,
-                    collection: metadata_response.collection,
-                    balance: Some(balance),
-                });
-                self.get_erc1155_balances(chain_id).await?;
+                let metadata = self.get_erc_metadata(&provider).await?;
+                self.check_erc_type(&metadata)?;
+                self.check_erc_owner(&provider).await?;
+
+                let mut metadata = self.enrich_erc_metadata(chain_id, metadata).await;
+                metadata.balance = Some(self.get_erc1155_balance(&provider).await?);
+                erc1155_full_data = Some(metadata);
                 Dialog::new(
                     "erc1155-add",
                     serde_json::to_value(erc1155_full_data.clone()).unwrap(),
@@ -328,6 +381,7 @@ impl TokenAdd {
 
             _ => return Err(Error::TypeInvalid(self._type.clone())),
         };
+
         dialog.open().await?;
 
         while let Some(msg) = dialog.recv().await {
@@ -363,7 +417,7 @@ impl TokenAdd {
         erc20_full_data: Option<Erc20FullData>,
     ) -> Result<()> {
         let db = ethui_db::get();
-        let wallet_address = self.get_current_wallet_address().await;
+        let wallet_address = self.wallet_address;
         let erc20_data = erc20_full_data.unwrap();
         let _save_metadata = db
             .save_erc20_metadata(chain_id, erc20_data.metadata.clone())
@@ -385,7 +439,7 @@ impl TokenAdd {
         full_data: ErcMetadataResponse,
     ) -> Result<()> {
         let db = ethui_db::get();
-        let wallet_address = self.get_current_wallet_address().await;
+        let wallet_address = self.wallet_address;
         let raw_metadata = full_data.raw;
         let token_uri = raw_metadata.token_uri;
         let metadata = raw_metadata.metadata.to_string();
@@ -421,7 +475,7 @@ impl TokenAdd {
         full_data: ErcMetadataResponse,
     ) -> Result<()> {
         let db = ethui_db::get();
-        let wallet_address = self.get_current_wallet_address().await;
+        let wallet_address = self.wallet_address;
         let raw_metadata = full_data.raw;
         let token_uri = raw_metadata.token_uri;
         let metadata = raw_metadata.metadata.to_string();
@@ -453,6 +507,16 @@ impl TokenAdd {
     }
 }
 
+/// Trims empty strings, which some tokens return instead of reverting
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.is_empty())
+}
+
+/// Expands the `{id}` placeholder of ERC1155 URIs, as specified in EIP-1155
+fn erc1155_uri(uri: &str, token_id: U256) -> String {
+    uri.replace("{id}", &format!("{:064x}", token_id))
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Params {
@@ -494,17 +558,21 @@ pub struct ERC1155Options {
     token_id: U256,
 }
 
-#[derive(Default)]
-pub struct TokenAddBuilder {
+pub struct TokenAddBuilder<'a> {
+    ctx: &'a Ctx,
     params: Option<Params>,
 }
 
-impl TokenAddBuilder {
+impl<'a> TokenAddBuilder<'a> {
+    pub fn new(ctx: &'a Ctx) -> Self {
+        Self { ctx, params: None }
+    }
+
     pub fn set_params(mut self, params: serde_json::Value) -> Result<Self> {
-        let params: serde_json::Value = if params.is_array() {
-            params.as_array().unwrap()[0].clone()
-        } else {
-            params
+        let params = match params {
+            serde_json::Value::Array(mut params) if !params.is_empty() => params.remove(0),
+            serde_json::Value::Array(_) => return Err(Error::ParseError),
+            params => params,
         };
 
         let type_value: serde_json::Value = params
@@ -560,10 +628,12 @@ impl TokenAddBuilder {
         Ok(self)
     }
 
-    pub async fn build(self) -> TokenAdd {
-        let params = self.params.unwrap();
+    pub async fn build(self) -> Result<TokenAdd> {
+        let params = self.params.ok_or(Error::ParseError)?;
+        let network = crate::secrets::unlock(self.ctx, self.ctx.network().await).await?;
+        let wallet_address = crate::current_address(self.ctx).await?;
 
-        match params.options {
+        Ok(match params.options {
             TokenOptions::ERC20(options) => {
                 let metadata = TokenMetadata {
                     address: options.address,
@@ -577,6 +647,8 @@ impl TokenAddBuilder {
                     erc1155_token: None,
                     chain_id: options.chain_id,
                     _type: params._type,
+                    network,
+                    wallet_address,
                 }
             }
             TokenOptions::ERC721(options) => {
@@ -590,6 +662,8 @@ impl TokenAddBuilder {
                     erc1155_token: None,
                     chain_id: options.chain_id,
                     _type: params._type,
+                    network,
+                    wallet_address,
                 }
             }
             TokenOptions::ERC1155(options) => {
@@ -603,8 +677,27 @@ impl TokenAddBuilder {
                     erc1155_token: Some(metadata),
                     chain_id: options.chain_id,
                     _type: params._type,
+                    network,
+                    wallet_address,
                 }
             }
-        }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_erc1155_uri() {
+        assert_eq!(
+            erc1155_uri("https://token-cdn-domain/{id}.json", U256::from(314592)),
+            "https://token-cdn-domain/000000000000000000000000000000000000000000000000000000000004cce0.json"
+        );
+        assert_eq!(
+            erc1155_uri("ipfs://metadata.json", U256::from(1)),
+            "ipfs://metadata.json"
+        );
     }
 }
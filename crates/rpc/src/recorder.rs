@@ -0,0 +1,328 @@
+//! Recording of dapp sessions
+//!
+//! While recording, the state-changing calls a dapp makes (transactions, signatures and chain
+//! switches) are captured in order. Once stopped, the recording is stored, and can be replayed
+//! against anvil with `replay`
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use alloy::{
+    network::{Ethereum, ReceiptResponse as _},
+    providers::{Provider as _, RootProvider},
+};
+use ethui_connections::Ctx;
+use ethui_networks::Networks;
+use ethui_types::{
+    Address, GlobalState, Json, Network, RecordedLog, RecordedStep, Recording, RecordingNetwork,
+    B256,
+};
+use ethui_wallets::{Wallet, Wallets};
+use jsonrpc_core::{ErrorCode, Params};
+use once_cell::sync::Lazy;
+use serde_json::json;
+use tokio::{sync::Mutex, task::JoinSet};
+
+use crate::{Error, Result};
+
+static RECORDER: Lazy<Mutex<Option<Recording>>> = Lazy::new(Default::default);
+
+/// Signing methods, recorded for reference only
+const SIGNATURE_METHODS: &[&str] = &[
+    "eth_sign",
+    "personal_sign",
+    "eth_signTypedData",
+    "eth_signTypedData_v4",
+];
+
+/// How many times to poll for a receipt, and how long to wait in between
+const RECEIPT_ATTEMPTS: usize = 20;
+const RECEIPT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Starts recording. If a domain is given, calls from other domains are ignored
+///
+/// If the current network is anvil, a snapshot is taken, so that replays can start from the
+/// same state
+pub async fn start(name: String, domain: Option<String>) -> Result<()> {
+    if is_recording().await {
+        return Err(Error::AlreadyRecording);
+    }
+
+    let ctx = Ctx {
+        domain: domain.clone(),
+        ..Default::default()
+    };
+    let network = recording_network(&ctx.network().await).await;
+
+    // checked again, since the recorder isn't locked while taking the snapshot
+    let mut recorder = RECORDER.lock().await;
+    if recorder.is_some() {
+        return Err(Error::AlreadyRecording);
+    }
+
+    *recorder = Some(Recording {
+        id: 0,
+        name,
+        domain,
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        network,
+        steps: vec![],
+    });
+
+    Ok(())
+}
+
+/// Stops recording, and stores the recording once all transaction receipts are known
+///
+/// Receipts are looked up concurrently
+pub async fn stop() -> Result<Recording> {
+    let mut recording = RECORDER.lock().await.take().ok_or(Error::NotRecording)?;
+
+    let mut receipts = JoinSet::new();
+    for (index, step) in recording.steps.iter().enumerate() {
+        if let RecordedStep::Transaction {
+            chain_id,
+            request,
+            hash,
+            user_operation_hash,
+            ..
+        } = step
+        {
+            let (chain_id, from, hash, user_operation_hash) =
+                (*chain_id, sender(request), *hash, *user_operation_hash);
+
+            receipts.spawn(async move {
+                let receipt = fetch_receipt(chain_id, from, hash, user_operation_hash).await;
+                (index, receipt)
+            });
+        }
+    }
+
+    while let Some(res) = receipts.join_next().await {
+        let Ok((index, Some((receipt_hash, success, receipt_logs)))) = res else {
+            continue;
+        };
+
+        if let RecordedStep::Transaction {
+            hash, status, logs, ..
+        } = &mut recording.steps[index]
+        {
+            *hash = Some(receipt_hash);
+            *status = Some(success);
+            *logs = receipt_logs;
+        }
+    }
+
+    recording.id = ethui_db::get().insert_recording(&recording).await?;
+
+    Ok(recording)
+}
+
+/// The recording in progress, if any
+pub async fn current() -> Option<Recording> {
+    RECORDER.lock().await.clone()
+}
+
+pub(crate) async fn is_recording() -> bool {
+    RECORDER.lock().await.is_some()
+}
+
+/// Captures a call, if a recording is in progress and the call is relevant to it
+///
+/// The recorder is only locked to check for, and append to, the recording, and not while the
+/// step is being built
+pub(crate) async fn record(
+    ctx: &Ctx,
+    method: &str,
+    params: &Params,
+    result: &jsonrpc_core::Result<Json>,
+) {
+    let Some(domain) = RECORDER.lock().await.as_ref().map(|r| r.domain.clone()) else {
+        return;
+    };
+
+    if domain.is_some() && domain != ctx.domain {
+        return;
+    }
+
+    // requests the user rejected were never meant to happen
+    if matches!(result, Err(e) if e.code == ErrorCode::ServerError(4001)) {
+        return;
+    }
+
+    let chain_id = ctx.chain_id().await;
+    let params = serde_json::to_value(params).unwrap_or_default();
+
+    let step = match method {
+        "eth_sendTransaction" => {
+            let request = with_sender(ctx, params[0].clone()).await;
+
+            let (hash, error) = match result {
+                Ok(hash) => (serde_json::from_value::<B256>(hash.clone()).ok(), None),
+                Err(e) => (None, Some(e.message.clone())),
+            };
+
+            // smart accounts return the UserOperation hash. the transaction that includes it is
+            // only known once the bundler has done so
+            let (hash, user_operation_hash) = match sender(&request) {
+                Some(from) if is_smart_account(from).await => (None, hash),
+                _ => (hash, None),
+            };
+
+            RecordedStep::Transaction {
+                chain_id,
+                request,
+                hash,
+                user_operation_hash,
+                error,
+                status: None,
+                logs: vec![],
+            }
+        }
+        "wallet_switchEthereumChain" if result.is_ok() => RecordedStep::ChainSwitch { chain_id },
+        method if SIGNATURE_METHODS.contains(&method) && result.is_ok() => {
+            RecordedStep::Signature {
+                chain_id,
+                method: method.into(),
+                params,
+            }
+        }
+        _ => return,
+    };
+
+    // the recording may have been stopped in the meantime
+    if let Some(recording) = RECORDER.lock().await.as_mut() {
+        recording.steps.push(step);
+    }
+}
+
+/// The account a recorded transaction was sent from
+fn sender(request: &Json) -> Option<Address> {
+    serde_json::from_value(request["from"].clone()).ok()
+}
+
+async fn is_smart_account(address: Address) -> bool {
+    matches!(
+        Wallets::read().await.find(address).await,
+        Some((Wallet::SmartAccount(_), _))
+    )
+}
+
+/// Looks up the receipt of a recorded transaction. Returns its hash, whether it succeeded, and
+/// its logs
+///
+/// Transactions sent by smart accounts are first looked up by their UserOperation hash, through
+/// the account's bundler
+async fn fetch_receipt(
+    chain_id: u32,
+    from: Option<Address>,
+    hash: Option<B256>,
+    user_operation_hash: Option<B256>,
+) -> Option<(B256, bool, Vec<RecordedLog>)> {
+    let hash = match (hash, user_operation_hash) {
+        (Some(hash), _) => hash,
+        (None, Some(user_operation_hash)) => included_in(from?, user_operation_hash).await?,
+        (None, None) => return None,
+    };
+
+    let network = Networks::read().await.get_network(chain_id)?;
+    let provider = network.get_provider().ok()?;
+    let (success, logs) = wait_for_receipt(&provider, hash).await?;
+
+    Some((hash, success, logs))
+}
+
+/// The transaction that included a smart account's UserOperation
+async fn included_in(account: Address, user_operation_hash: B256) -> Option<B256> {
+    let account = match Wallets::read().await.find(account).await {
+        Some((Wallet::SmartAccount(account), _)) => account.clone(),
+        _ => return None,
+    };
+
+    match account.transaction_hash(user_operation_hash).await {
+        Ok(hash) => Some(hash),
+        Err(e) => {
+            tracing::warn!(?user_operation_hash, "user operation not included: {}", e);
+            None
+        }
+    }
+}
+
+/// Fills in the sender of a transaction that didn't specify one, with the account it was sent
+/// from: the domain's burner, or the globally selected one
+async fn with_sender(ctx: &Ctx, mut request: Json) -> Json {
+    let Some(fields) = request.as_object_mut() else {
+        return request;
+    };
+
+    if !fields.get("from").is_some_and(|from| !from.is_null()) {
+        if let Ok(from) = crate::current_address(ctx).await {
+            fields.insert("from".into(), json!(from));
+        }
+    }
+
+    request
+}
+
+/// Snapshots anvil networks, and notes where forks come from
+async fn recording_network(network: &Network) -> RecordingNetwork {
+    let mut res = RecordingNetwork {
+        chain_id: network.chain_id(),
+        dedup_chain_id: Some(network.dedup_chain_id()),
+        snapshot: None,
+        fork_url: None,
+        fork_block_number: None,
+    };
+
+    if !network.is_dev().await {
+        return res;
+    }
+
+    let Ok(provider) = network.get_provider() else {
+        return res;
+    };
+
+    res.snapshot = provider
+        .raw_request::<_, String>("evm_snapshot".into(), ())
+        .await
+        .ok();
+
+    if let Ok(info) = provider
+        .raw_request::<_, Json>("anvil_nodeInfo".into(), ())
+        .await
+    {
+        res.fork_url = info["forkConfig"]["forkUrl"].as_str().map(String::from);
+        res.fork_block_number = info["forkConfig"]["forkBlockNumber"].as_u64();
+    }
+
+    res
+}
+
+/// Waits for a transaction to be mined. Returns whether it succeeded, and its logs
+pub(crate) async fn wait_for_receipt(
+    provider: &RootProvider<Ethereum>,
+    hash: B256,
+) -> Option<(bool, Vec<RecordedLog>)> {
+    for _ in 0..RECEIPT_ATTEMPTS {
+        if let Ok(Some(receipt)) = provider.get_transaction_receipt(hash).await {
+            let logs = receipt
+                .inner
+                .logs()
+                .iter()
+                .map(|log| RecordedLog {
+                    address: log.address(),
+                    topics: log.topics().to_vec(),
+                    data: log.data().data.clone(),
+                })
+                .collect();
+
+            return Some((receipt.status(), logs));
+        }
+
+        tokio::time::sleep(RECEIPT_INTERVAL).await;
+    }
+
+    None
+}
@@ -0,0 +1,420 @@
+//! Replays recorded sessions against anvil
+//!
+//! Each recorded transaction is sent again, from the same account, and its outcome compared to
+//! the recorded one. Signatures and chain switches have no effect on chain, so they're not
+//! replayed
+
+use std::{collections::BTreeSet, net::TcpListener, process::Stdio, time::Duration};
+
+use alloy::{
+    network::Ethereum,
+    providers::{ext::AnvilApi as _, Provider as _, ProviderBuilder, RootProvider},
+};
+use ethui_networks::Networks;
+use ethui_types::{
+    Address, GlobalState, Json, RecordedLog, RecordedStep, Recording, RecordingNetwork, B256, U256,
+};
+use serde::{Deserialize, Serialize};
+use tokio::process::{Child, Command};
+
+use crate::{recorder::wait_for_receipt, Error, Result};
+
+/// Fields that depend on the state of the chain at the time, and are left for anvil to fill in
+const STRIPPED_FIELDS: &[&str] = &[
+    "nonce",
+    "chainId",
+    "gasPrice",
+    "maxFeePerGas",
+    "maxPriorityFeePerGas",
+];
+
+/// How many times to check if a new anvil process is ready, and how long to wait in between
+const ANVIL_STARTUP_ATTEMPTS: usize = 50;
+const ANVIL_STARTUP_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Balance given to each sender on a fresh chain, in ether
+const SENDER_BALANCE: u64 = 10_000;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReplayTarget {
+    /// Reverts the recording's network to the snapshot taken when recording started
+    /// Any state changes made since then are lost
+    Snapshot,
+
+    /// Starts a new anvil process, forking from the same block if the recording's network was a
+    /// fork. Otherwise, the chain starts out empty, so the session must deploy whatever it uses,
+    /// and senders are funded before replaying
+    Fresh,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayReport {
+    pub recording_id: i64,
+    pub steps: Vec<ReplayedStep>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayedStep {
+    /// index of the transaction within the recording's steps
+    pub index: usize,
+
+    /// hash of the replayed transaction, if it was sent
+    pub hash: Option<B256>,
+
+    #[serde(flatten)]
+    pub outcome: ReplayOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "outcome", rename_all = "camelCase")]
+pub enum ReplayOutcome {
+    /// same status, and same logs, as when recorded
+    Unchanged,
+
+    /// reverts now, but didn't when recorded
+    Reverted {
+        error: Option<String>,
+    },
+
+    /// succeeds now, but reverted when recorded
+    NoLongerReverts,
+
+    /// succeeded both times, but emitted different logs
+    LogsChanged {
+        expected: Vec<RecordedLog>,
+        actual: Vec<RecordedLog>,
+    },
+
+    Skipped {
+        reason: String,
+    },
+}
+
+/// Outcome of a single run of a transaction
+#[derive(Debug, Clone, PartialEq)]
+enum Run {
+    Success(Vec<RecordedLog>),
+    Revert(Option<String>),
+    Unknown,
+}
+
+/// A node to replay against. Processes started for a replay are killed once it's done, and
+/// reaped in the background
+struct Node {
+    provider: RootProvider<Ethereum>,
+    /// only held so the process is killed when the node is dropped
+    _process: Option<Child>,
+}
+
+pub async fn replay(recording: &Recording, target: ReplayTarget) -> Result<ReplayReport> {
+    let node = match target {
+        ReplayTarget::Snapshot => revert(recording).await?,
+        ReplayTarget::Fresh => spawn(&recording.network).await?,
+    };
+
+    node.provider.anvil_auto_impersonate_account(true).await?;
+
+    if matches!(target, ReplayTarget::Fresh) && recording.network.fork_url.is_none() {
+        fund_senders(&node.provider, recording).await?;
+    }
+
+    let mut steps = vec![];
+
+    for (index, step) in recording.steps.iter().enumerate() {
+        let RecordedStep::Transaction {
+            chain_id,
+            request,
+            error,
+            status,
+            logs,
+            ..
+        } = step
+        else {
+            continue;
+        };
+
+        let recorded = match (error, status) {
+            (Some(error), _) => Run::Revert(Some(error.clone())),
+            (None, Some(false)) => Run::Revert(None),
+            (None, Some(true)) => Run::Success(logs.clone()),
+            (None, None) => Run::Unknown,
+        };
+
+        let (hash, outcome) = if *chain_id != recording.network.chain_id {
+            (None, skipped("sent on a different network"))
+        } else if recorded == Run::Unknown {
+            (None, skipped("the recorded transaction was never mined"))
+        } else {
+            let (hash, replayed) = send(&node.provider, request).await;
+            (hash, compare(recorded, replayed))
+        };
+
+        steps.push(ReplayedStep {
+            index,
+            hash,
+            outcome,
+        });
+    }
+
+    Ok(ReplayReport {
+        recording_id: recording.id,
+        steps,
+    })
+}
+
+/// Reverts the recording's network to its snapshot
+async fn revert(recording: &Recording) -> Result<Node> {
+    let chain_id = recording.network.chain_id;
+    let snapshot = recording
+        .network
+        .snapshot
+        .clone()
+        .ok_or(Error::NoSnapshot)?;
+
+    let network = {
+        let networks = Networks::read().await;
+
+        match recording.network.dedup_chain_id {
+            Some(dedup_chain_id) => networks.get_network_by_dedup_chain_id(dedup_chain_id),
+            None => networks.get_network(chain_id),
+        }
+        .ok_or(Error::UnrecognizedChainId(chain_id))?
+    };
+    let provider = network.get_provider()?;
+
+    let reverted: bool = provider
+        .raw_request("evm_revert".into(), [snapshot.clone()])
+        .await?;
+
+    if !reverted {
+        return Err(Error::SnapshotRevert(snapshot));
+    }
+
+    // anvil discards a snapshot once reverted to, so a new one is needed for the next replay
+    let snapshot: String = provider.raw_request("evm_snapshot".into(), ()).await?;
+    ethui_db::get()
+        .set_recording_snapshot(recording.id, &snapshot)
+        .await?;
+
+    Ok(Node {
+        provider,
+        _process: None,
+    })
+}
+
+/// Starts a new anvil process, on a free port
+async fn spawn(network: &RecordingNetwork) -> Result<Node> {
+    let port = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+
+    let mut cmd = Command::new("anvil");
+    cmd.args(["--port", &port.to_string()])
+        .args(["--chain-id", &network.chain_id.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    if let Some(ref fork_url) = network.fork_url {
+        cmd.args(["--fork-url", fork_url]);
+
+        if let Some(block) = network.fork_block_number {
+            cmd.args(["--fork-block-number", &block.to_string()]);
+        }
+    }
+
+    let process = cmd
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| Error::AnvilSpawn(e.to_string()))?;
+
+    let url = format!("http://127.0.0.1:{}", port).parse().unwrap();
+    let node = Node {
+        provider: ProviderBuilder::new()
+            .disable_recommended_fillers()
+            .on_http(url),
+        _process: Some(process),
+    };
+
+    for _ in 0..ANVIL_STARTUP_ATTEMPTS {
+        if node.provider.get_chain_id().await.is_ok() {
+            return Ok(node);
+        }
+
+        tokio::time::sleep(ANVIL_STARTUP_INTERVAL).await;
+    }
+
+    Err(Error::AnvilSpawn(
+        "timed out waiting for anvil to start".into(),
+    ))
+}
+
+/// Accounts on a non-forked chain start out with no balance, so each sender is given some
+async fn fund_senders(provider: &RootProvider<Ethereum>, recording: &Recording) -> Result<()> {
+    let balance = U256::from(SENDER_BALANCE) * U256::from(10).pow(U256::from(18));
+
+    let senders: BTreeSet<Address> = recording
+        .steps
+        .iter()
+        .filter_map(|step| match step {
+            RecordedStep::Transaction { request, .. } => {
+                serde_json::from_value(request["from"].clone()).ok()
+            }
+            _ => None,
+        })
+        .collect();
+
+    for sender in senders {
+        provider.anvil_set_balance(sender, balance).await?;
+    }
+
+    Ok(())
+}
+
+/// Sends a recorded transaction, and waits for it to be mined
+async fn send(provider: &RootProvider<Ethereum>, request: &Json) -> (Option<B256>, Run) {
+    let mut request = request.clone();
+    if let Some(fields) = request.as_object_mut() {
+        STRIPPED_FIELDS.iter().for_each(|f| {
+            fields.remove(*f);
+        });
+    }
+
+    let hash: B256 = match provider
+        .raw_request("eth_sendTransaction".into(), [request])
+        .await
+    {
+        Ok(hash) => hash,
+        Err(e) => return (None, Run::Revert(Some(e.to_string()))),
+    };
+
+    let run = match wait_for_receipt(provider, hash).await {
+        Some((true, logs)) => Run::Success(logs),
+        Some((false, _)) => Run::Revert(None),
+        None => Run::Unknown,
+    };
+
+    (Some(hash), run)
+}
+
+fn compare(recorded: Run, replayed: Run) -> ReplayOutcome {
+    match (recorded, replayed) {
+        (_, Run::Unknown) => skipped("the replayed transaction was not mined"),
+        (Run::Success(expected), Run::Success(actual)) if expected == actual => {
+            ReplayOutcome::Unchanged
+        }
+        (Run::Success(expected), Run::Success(actual)) => {
+            ReplayOutcome::LogsChanged { expected, actual }
+        }
+        (Run::Success(_), Run::Revert(error)) => ReplayOutcome::Reverted { error },
+        (Run::Revert(_), Run::Success(_)) => ReplayOutcome::NoLongerReverts,
+        (Run::Revert(_), Run::Revert(_)) => ReplayOutcome::Unchanged,
+        (Run::Unknown, _) => skipped("the recorded transaction was never mined"),
+    }
+}
+
+fn skipped(reason: &str) -> ReplayOutcome {
+    ReplayOutcome::Skipped {
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethui_types::Address;
+
+    use super::*;
+
+    fn log(data: &'static [u8]) -> RecordedLog {
+        RecordedLog {
+            address: Address::ZERO,
+            topics: vec![B256::ZERO],
+            data: data.into(),
+        }
+    }
+
+    #[test]
+    fn reports_changed_outcomes() {
+        let success = || Run::Success(vec![log(b"a")]);
+
+        assert_eq!(compare(success(), success()), ReplayOutcome::Unchanged);
+        assert_eq!(
+            compare(success(), Run::Revert(Some("execution reverted".into()))),
+            ReplayOutcome::Reverted {
+                error: Some("execution reverted".into())
+            }
+        );
+        assert_eq!(
+            compare(Run::Revert(None), success()),
+            ReplayOutcome::NoLongerReverts
+        );
+        assert_eq!(
+            compare(Run::Revert(None), Run::Revert(Some("other reason".into()))),
+            ReplayOutcome::Unchanged
+        );
+    }
+
+    fn transfer(from: Address, logs: Vec<RecordedLog>) -> RecordedStep {
+        RecordedStep::Transaction {
+            chain_id: 31337,
+            request: serde_json::json!({ "from": from, "to": Address::ZERO, "value": "0x1" }),
+            hash: Some(B256::ZERO),
+            user_operation_hash: None,
+            error: None,
+            status: Some(true),
+            logs,
+        }
+    }
+
+    #[tokio::test]
+    async fn replays_on_a_fresh_anvil() {
+        // not one of anvil's default accounts, so it must be funded before replaying
+        let sender = Address::repeat_byte(0x11);
+
+        let recording = Recording {
+            id: 1,
+            name: "session".into(),
+            domain: None,
+            created_at: 0,
+            network: RecordingNetwork {
+                chain_id: 31337,
+                dedup_chain_id: None,
+                snapshot: None,
+                fork_url: None,
+                fork_block_number: None,
+            },
+            steps: vec![
+                transfer(sender, vec![]),
+                RecordedStep::ChainSwitch { chain_id: 1 },
+                transfer(sender, vec![log(b"a")]),
+            ],
+        };
+
+        let report = replay(&recording, ReplayTarget::Fresh).await.unwrap();
+
+        assert_eq!(report.steps.len(), 2);
+        assert_eq!(report.steps[0].outcome, ReplayOutcome::Unchanged);
+        assert!(report.steps[0].hash.is_some());
+        assert_eq!(report.steps[1].index, 2);
+        assert_eq!(
+            report.steps[1].outcome,
+            ReplayOutcome::LogsChanged {
+                expected: vec![log(b"a")],
+                actual: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn reports_different_logs() {
+        let outcome = compare(Run::Success(vec![log(b"a")]), Run::Success(vec![log(b"b")]));
+
+        assert_eq!(
+            outcome,
+            ReplayOutcome::LogsChanged {
+                expected: vec![log(b"a")],
+                actual: vec![log(b"b")],
+            }
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Unlocking of network secrets, for requests that need them
+//!
+//! Secrets start locked every time the app starts, and whenever the user locks them again.
+//! Rather than sending requests without them, the user is asked for the password the first time
+//! they're needed
+
+use std::time::{Duration, Instant};
+
+use ethui_connections::Ctx;
+use ethui_dialogs::{Dialog, DialogMsg};
+use ethui_networks::Networks;
+use ethui_types::{GlobalState, Network};
+use once_cell::sync::Lazy;
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use crate::Result;
+
+/// How long requests fail without asking, after the user dismissed the unlock dialog
+const REJECTION_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Held while the unlock dialog is open, so that concurrent requests wait on a single one
+static PENDING: Lazy<Mutex<()>> = Lazy::new(Default::default);
+
+/// When the unlock dialog was last dismissed
+static LAST_REJECTION: Lazy<std::sync::Mutex<Option<Instant>>> = Lazy::new(Default::default);
+
+/// Returns `network` with the secrets of its current endpoint in place, asking the user for the
+/// password if they're still locked
+///
+/// Only connections the user trusts may ask: read-only ones, such as unpaired clients, fail
+/// right away. So does every request shortly after the user dismissed the dialog, and those
+/// that came in while it was open, rather than each opening one of its own
+///
+/// Fails with `SecretsLocked` if the user doesn't unlock them
+pub(crate) async fn unlock(ctx: &Ctx, network: Network) -> Result<Network> {
+    if !network.is_locked() {
+        return Ok(network);
+    }
+
+    if ctx.key().is_none() || ctx.is_read_only().await {
+        return Err(ethui_networks::Error::SecretsLocked.into());
+    }
+
+    let _pending = match PENDING.try_lock() {
+        Ok(pending) => pending,
+        Err(_) => {
+            // another request is already asking, and its answer is this one's too
+            drop(PENDING.lock().await);
+            return refreshed(&network)
+                .await
+                .ok_or(ethui_networks::Error::SecretsLocked.into());
+        }
+    };
+
+    // another request may have unlocked them just before this one got here
+    if let Some(network) = refreshed(&network).await {
+        return Ok(network);
+    }
+
+    if cooling_down(*LAST_REJECTION.lock().unwrap(), Instant::now()) {
+        return Err(ethui_networks::Error::SecretsLocked.into());
+    }
+
+    let dialog = Dialog::new("secrets-unlock", json!({ "network": network.name }));
+    dialog.open().await?;
+
+    // attempt to receive a password at most 3 times
+    for _ in 0..3 {
+        let Some(DialogMsg::Data(payload)) = dialog.recv().await else {
+            break;
+        };
+        let Some(password) = payload["password"].as_str() else {
+            break;
+        };
+
+        match Networks::unlock_secrets(password).await {
+            Ok(()) => {
+                return refreshed(&network)
+                    .await
+                    .ok_or(ethui_networks::Error::SecretsLocked.into())
+            }
+            Err(ethui_networks::Error::InvalidPassword) => dialog.send("failed", None).await?,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    *LAST_REJECTION.lock().unwrap() = Some(Instant::now());
+    Err(ethui_networks::Error::SecretsLocked.into())
+}
+
+/// The latest version of `network`, if its secrets are unlocked by now
+async fn refreshed(network: &Network) -> Option<Network> {
+    Networks::read()
+        .await
+        .get_network_by_dedup_chain_id(network.dedup_chain_id())
+        .filter(|n| !n.is_locked())
+}
+
+fn cooling_down(last_rejection: Option<Instant>, now: Instant) -> bool {
+    last_rejection.is_some_and(|at| now.duration_since(at) < REJECTION_COOLDOWN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cools_down_after_a_rejection() {
+        let now = Instant::now();
+
+        assert!(!cooling_down(None, now));
+        assert!(cooling_down(Some(now), now + Duration::from_secs(5)));
+        assert!(!cooling_down(Some(now), now + REJECTION_COOLDOWN));
+    }
+}
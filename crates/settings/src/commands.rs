@@ -1,4 +1,4 @@
-use ethui_types::{Address, GlobalState};
+use ethui_types::GlobalState;
 
 use super::{DarkMode, Result, SerializedSettings, Settings};
 use crate::onboarding::OnboardingStep;
@@ -28,18 +28,6 @@ pub async fn settings_finish_onboarding() -> Result<()> {
     Settings::write().await.finish_onboarding().await
 }
 
-/// Gets the alias for an address
-#[tauri::command]
-pub async fn settings_get_alias(address: Address) -> Option<String> {
-    Settings::read().await.get_alias(address)
-}
-
-/// Sets the alias for an address
-#[tauri::command]
-pub async fn settings_set_alias(address: Address, alias: Option<String>) -> Result<()> {
-    Settings::write().await.set_alias(address, alias).await
-}
-
 #[tauri::command]
 pub async fn settings_test_alchemy_api_key(key: String) -> bool {
     crate::utils::test_alchemy_api_key(key).await
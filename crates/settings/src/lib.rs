@@ -80,6 +80,10 @@ impl Settings {
             self.inner.fast_mode = serde_json::from_value(v.clone()).unwrap();
         }
 
+        if let Some(v) = params.get("rpcInspector") {
+            self.inner.rpc_inspector = serde_json::from_value(v.clone()).unwrap();
+        }
+
         if let Some(v) = params.get("rustLog") {
             self.inner.rust_log = serde_json::from_value(v.clone()).unwrap();
             ethui_tracing::parse(&self.inner.rust_log)?;
@@ -130,6 +134,11 @@ impl Settings {
         self.inner.start_minimized
     }
 
+    /// Whether JSON-RPC traffic should be recorded
+    pub fn rpc_inspector(&self) -> bool {
+        self.inner.rpc_inspector
+    }
+
     pub fn get_etherscan_api_key(&self) -> Result<String> {
         self.inner
             .etherscan_api_key
@@ -137,22 +146,19 @@ impl Settings {
             .ok_or(Error::EtherscanKeyNotSet)
     }
 
-    fn get_alias(&self, address: Address) -> Option<String> {
-        self.inner.aliases.get(&address).cloned()
+    /// Aliases set before the address book existed, yet to be moved there
+    pub fn aliases(&self) -> HashMap<Address, String> {
+        self.inner.aliases.clone()
     }
 
-    async fn set_alias(&mut self, address: Address, alias: Option<String>) -> Result<()> {
-        // trim whitespaces
-        // empty str becomes None
-        let alias = alias.map(|v| v.trim().to_owned()).filter(|v| !v.is_empty());
-
-        if let Some(alias) = alias {
-            self.inner.aliases.insert(address, alias);
-        } else {
-            self.inner.aliases.remove(&address);
+    /// Removes aliases, once they're safely stored in the address book
+    pub async fn clear_aliases(&mut self) -> Result<()> {
+        if self.inner.aliases.is_empty() {
+            return Ok(());
         }
-        self.save().await?;
-        Ok(())
+
+        self.inner.aliases.clear();
+        self.save().await
     }
 
     // Persists current state to disk
@@ -181,7 +187,8 @@ pub struct SerializedSettings {
     #[serde(default = "default_true")]
     pub hide_empty_tokens: bool,
 
-    #[serde(default = "default_aliases")]
+    /// Legacy aliases, only kept until they're moved to the address book
+    #[serde(default = "default_aliases", skip_serializing_if = "HashMap::is_empty")]
     aliases: HashMap<Address, String>,
 
     #[serde(default)]
@@ -196,6 +203,9 @@ pub struct SerializedSettings {
     #[serde(default)]
     rust_log: String,
 
+    #[serde(default)]
+    rpc_inspector: bool,
+
     #[serde(default)]
     pub onboarding: Onboarding,
 
@@ -215,6 +225,7 @@ impl Default for SerializedSettings {
             autostart: false,
             start_minimized: false,
             rust_log: "warn".into(),
+            rpc_inspector: false,
             version: ConstI64,
             onboarding: Onboarding::default(),
         }
@@ -151,6 +151,7 @@ fn run_migrations(settings: Versions) -> SerializedSettings {
                     autostart: v1.autostart,
                     start_minimized: v1.start_minimized,
                     rust_log: v1.rust_log,
+                    rpc_inspector: false,
                     onboarding: if v1.onboarded {
                         Onboarding::all_done()
                     } else {
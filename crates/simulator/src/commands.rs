@@ -12,7 +12,12 @@ use crate::{
 pub async fn simulator_run(chain_id: u32, request: Request) -> SimulationResult<Result> {
     let network = Networks::read().await.get_network(chain_id).unwrap();
 
-    let mut evm = Evm::new(network.http_url.to_string(), None, request.gas_limit).await;
+    let mut evm = Evm::new(
+        network.authenticated_http_url()?.to_string(),
+        None,
+        request.gas_limit,
+    )
+    .await;
 
     evm.call(request).await
 }
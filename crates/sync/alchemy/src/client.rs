@@ -90,6 +90,33 @@ impl Client {
         Ok((txs, erc20_metadatas))
     }
 
+    /// Checks whether there is at least one asset transfer in the given direction, over the
+    /// entire history of the chain
+    pub async fn has_asset_transfers(&self, addr: Direction) -> Result<bool> {
+        let mut params = json!({
+            "fromBlock": "0x0",
+            "toBlock": "latest",
+            "maxCount": "0x1",
+            "category": [ "external", "erc20", "erc721", "erc1155", "specialnft"],
+        });
+
+        let params_obj = params.as_object_mut().unwrap();
+
+        match addr {
+            Direction::From(addr) => params_obj.insert("fromAddress".to_string(), json!(addr)),
+            Direction::To(addr) => params_obj.insert("toAddress".to_string(), json!(addr)),
+        };
+
+        let res: serde_json::Value = self
+            .v2_provider
+            .raw_request("alchemy_getAssetTransfers".into(), json!([params]))
+            .await?;
+
+        Ok(res["transfers"]
+            .as_array()
+            .is_some_and(|transfers| !transfers.is_empty()))
+    }
+
     pub async fn get_native_balance(&self, address: Address) -> Result<U256> {
         Ok(self.v2_provider.get_balance(address).await?)
     }
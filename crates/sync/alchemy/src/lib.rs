@@ -8,7 +8,10 @@ use ethui_db::Db;
 use ethui_types::{Address, U256};
 pub use networks::supports_network;
 use tracing::instrument;
-pub use types::{Erc20Metadata, ErcMetadataResponse, ErcOwnersResponse};
+pub use types::{
+    Erc20Metadata, ErcContract, ErcImageData, ErcMetadataResponse, ErcOwnersResponse,
+    ErcRawMetadata,
+};
 pub use utils::{get_alchemy, get_current_api_key};
 
 pub use self::error::{Error, Result};
@@ -100,6 +103,19 @@ impl Alchemy {
         Ok(())
     }
 
+    /// Checks if an address has ever sent or received any asset on this chain
+    #[instrument(skip(self))]
+    pub async fn has_activity(&self, address: Address) -> Result<bool> {
+        Ok(self
+            .client
+            .has_asset_transfers(Direction::To(address))
+            .await?
+            || self
+                .client
+                .has_asset_transfers(Direction::From(address))
+                .await?)
+    }
+
     #[instrument(skip(self))]
     pub async fn fetch_erc20_metadata(&self, address: Address) -> Result<Erc20Metadata> {
         let metadata = self.client.get_erc20_metadata(address).await?;
@@ -18,7 +18,7 @@ pub async fn get_alchemy(chain_id: u32) -> Result<Alchemy> {
         Ok(Some(api_key)) => api_key,
         _ => return Err(Error::NoAPIKey),
     };
-    let alchemy = Alchemy::new(&api_key, ethui_db::get(), chain_id).unwrap();
+    let alchemy = Alchemy::new(&api_key, ethui_db::get(), chain_id)?;
 
     Ok(alchemy)
 }
@@ -4,7 +4,7 @@ use ethui_broadcast::InternalMsg;
 use ethui_types::DedupChainId;
 use once_cell::sync::Lazy;
 use tokio::sync::Mutex;
-use tracing::trace;
+use tracing::{trace, warn};
 use url::Url;
 
 use crate::tracker::Tracker;
@@ -30,12 +30,12 @@ async fn receiver() -> ! {
                     network.chain_id(),
                     network.dedup_chain_id.dedup_id()
                 );
-                reset_listener(
-                    network.dedup_chain_id(),
-                    network.clone().http_url,
-                    network.ws_url(),
-                )
-                .await
+                match (network.authenticated_http_url(), network.ws_url()) {
+                    (Ok(http), Ok(ws)) => reset_listener(network.dedup_chain_id(), http, ws).await,
+                    (Err(e), _) | (_, Err(e)) => {
+                        warn!("not listening to {}: {}", network.name, e)
+                    }
+                }
             }
             _ => (),
         }
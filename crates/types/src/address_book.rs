@@ -0,0 +1,35 @@
+use alloy::primitives::Address;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressBookEntry {
+    pub address: Address,
+
+    /// if unset, the entry applies to all networks
+    #[serde(default)]
+    pub chain_id: Option<u32>,
+
+    pub name: String,
+
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+/// A human-readable name for an address, and where it came from
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressLabel {
+    pub name: String,
+    pub source: AddressLabelSource,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum AddressLabelSource {
+    AddressBook,
+    Ens,
+}
@@ -1,3 +1,4 @@
+mod address_book;
 mod affinity;
 mod contracts;
 pub mod dedup_chain_id;
@@ -5,9 +6,13 @@ pub mod events;
 mod global_state;
 mod network;
 mod new_network_params;
+mod recordings;
+mod rpc_endpoint;
+mod rpc_traffic;
 mod tokens;
 pub mod transactions;
 pub mod ui_events;
+pub use address_book::{AddressBookEntry, AddressLabel, AddressLabelSource};
 pub use affinity::Affinity;
 pub use alloy::primitives::{address, Address, B256, U256, U64};
 pub use contracts::{Contract, ContractWithAbi};
@@ -16,6 +21,12 @@ pub use events::Event;
 pub use global_state::GlobalState;
 pub use network::Network;
 pub use new_network_params::NewNetworkParams;
+pub use recordings::{RecordedLog, RecordedStep, Recording, RecordingNetwork};
+pub use rpc_endpoint::{
+    is_key_param, looks_like_key, redact_url, redact_urls, redacted_url, select_endpoint,
+    EndpointHealth, EndpointSecret, RpcEndpoint, SecretValues,
+};
+pub use rpc_traffic::{RpcTrafficEntry, RpcTrafficFilter, RpcTrafficSource};
 pub use tokens::{
     Erc1155Token, Erc1155TokenData, Erc721Collection, Erc721Token, Erc721TokenData,
     Erc721TokenDetails, TokenBalance, TokenMetadata,
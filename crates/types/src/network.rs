@@ -1,20 +1,37 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
 use alloy::{
     network::Ethereum,
     providers::{ext::AnvilApi, ProviderBuilder, RootProvider},
-    rpc::client::ClientBuilder,
-    transports::{layers::RetryBackoffLayer, RpcError, TransportErrorKind},
+    rpc::client::{ClientBuilder, RpcClient},
+    transports::{
+        layers::RetryBackoffLayer, utils::guess_local_url, RpcError, TransportError,
+        TransportErrorKind,
+    },
 };
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::DedupChainId;
+use crate::{
+    rpc_endpoint::{redacted_url, select_endpoint},
+    DedupChainId, RpcEndpoint,
+};
+
+/// When each network last had a client built for it, so that idle ones aren't health checked
+static LAST_USED: LazyLock<Mutex<HashMap<DedupChainId, Instant>>> = LazyLock::new(Default::default);
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Network {
     pub dedup_chain_id: DedupChainId,
     pub name: String,
     pub explorer_url: Option<String>,
-    pub http_url: Url,
+    /// HTTP RPC endpoints serving this network. Only ever empty if `networks.json` was edited by
+    /// hand, in which case requests fail
+    pub endpoints: Vec<RpcEndpoint>,
     pub ws_url: Option<Url>,
     pub currency: String,
     pub decimals: u32,
@@ -26,7 +43,10 @@ impl Network {
             dedup_chain_id: (1, deduplication_id).into(),
             name: String::from("Mainnet"),
             explorer_url: Some(String::from("https://etherscan.io/search?q=")),
-            http_url: Url::parse("https://eth.llamarpc.com").unwrap(),
+            endpoints: vec![RpcEndpoint::new(
+                Url::parse("https://eth.llamarpc.com").unwrap(),
+                0,
+            )],
             ws_url: None,
             currency: String::from("ETH"),
             decimals: 18,
@@ -38,7 +58,10 @@ impl Network {
             dedup_chain_id: (11155111, deduplication_id).into(),
             name: String::from("Sepolia"),
             explorer_url: Some(String::from("https://sepolia.etherscan.io/search?q=")),
-            http_url: Url::parse("https://ethereum-sepolia-rpc.publicnode.com").unwrap(),
+            endpoints: vec![RpcEndpoint::new(
+                Url::parse("https://ethereum-sepolia-rpc.publicnode.com").unwrap(),
+                0,
+            )],
             ws_url: None,
             currency: String::from("ETH"),
             decimals: 18,
@@ -50,7 +73,10 @@ impl Network {
             dedup_chain_id: (31337, deduplication_id).into(),
             name: String::from("Anvil"),
             explorer_url: None,
-            http_url: Url::parse("http://localhost:8545").unwrap(),
+            endpoints: vec![RpcEndpoint::new(
+                Url::parse("http://localhost:8545").unwrap(),
+                0,
+            )],
             ws_url: Some(Url::parse("ws://localhost:8545").unwrap()),
             currency: String::from("ETH"),
             decimals: 18,
@@ -73,35 +99,140 @@ impl Network {
         format!("0x{:x}", self.chain_id())
     }
 
-    pub fn ws_url(&self) -> Url {
-        self.ws_url.clone().unwrap_or_else(|| {
-            Url::parse(&self.http_url.clone().as_str().replace("http", "ws")).unwrap()
+    /// The endpoint currently in use: the preferred one among those deemed healthy
+    ///
+    /// Fails if the network has no endpoints at all
+    pub fn endpoint(&self) -> Result<&RpcEndpoint, TransportError> {
+        select_endpoint(&self.endpoints).ok_or_else(|| {
+            TransportErrorKind::custom_str(&format!("network {} has no RPC endpoints", self.name))
         })
     }
 
+    /// URL of the endpoint currently in use, without any secrets. Safe to display
+    pub fn http_url(&self) -> Result<Url, TransportError> {
+        Ok(self.endpoint()?.url.clone())
+    }
+
+    /// A copy that's safe to show, with any credentials still embedded in endpoint URLs
+    /// redacted. Those only remain in networks that were set up before secrets were supported
+    pub fn redacted(&self) -> Self {
+        let mut network = self.clone();
+
+        for endpoint in network.endpoints.iter_mut() {
+            endpoint.url = redacted_url(&endpoint.url);
+        }
+        network.ws_url = network.ws_url.as_ref().map(redacted_url);
+
+        network
+    }
+
+    /// Whether the endpoint currently in use needs secrets that weren't unlocked yet
+    pub fn is_locked(&self) -> bool {
+        self.endpoint().is_ok_and(|e| e.is_locked())
+    }
+
+    /// The endpoint currently in use, as long as all its secrets are unlocked
+    ///
+    /// Sending requests without them would only fail upstream, or worse, reach a different
+    /// account than the user intended
+    fn unlocked_endpoint(&self) -> Result<&RpcEndpoint, TransportError> {
+        let endpoint = self.endpoint()?;
+        self.mark_used();
+
+        if endpoint.is_locked() {
+            return Err(TransportErrorKind::custom_str(&format!(
+                "secrets of network {} are locked",
+                self.name
+            )));
+        }
+
+        Ok(endpoint)
+    }
+
+    /// URL of the endpoint currently in use, with its secrets in place
+    ///
+    /// Only for consumers that can't take an alloy client, since header secrets are lost
+    pub fn authenticated_http_url(&self) -> Result<Url, TransportError> {
+        Ok(self.unlocked_endpoint()?.authenticated_url())
+    }
+
+    fn mark_used(&self) {
+        if let Ok(mut last_used) = LAST_USED.lock() {
+            last_used.insert(self.dedup_chain_id(), Instant::now());
+        }
+    }
+
+    /// Whether a client was built for this network within the given window
+    pub fn used_within(&self, window: Duration) -> bool {
+        LAST_USED.lock().is_ok_and(|last_used| {
+            last_used
+                .get(&self.dedup_chain_id())
+                .is_some_and(|at| at.elapsed() < window)
+        })
+    }
+
+    /// Adds an endpoint, with lower priority than all existing ones
+    pub fn add_endpoint(&mut self, url: Url) {
+        if self.endpoints.iter().any(|e| e.url == url) {
+            return;
+        }
+
+        let priority = self
+            .endpoints
+            .iter()
+            .map(|e| e.priority + 1)
+            .max()
+            .unwrap_or(0);
+        self.endpoints.push(RpcEndpoint::new(url, priority));
+    }
+
+    pub fn ws_url(&self) -> Result<Url, TransportError> {
+        match &self.ws_url {
+            Some(url) => Ok(url.clone()),
+            None => {
+                let http_url = self.authenticated_http_url()?;
+                Ok(Url::parse(&http_url.as_str().replace("http", "ws")).unwrap())
+            }
+        }
+    }
+
     pub async fn is_dev(&self) -> bool {
-        let provider = self.get_alloy_provider().await.unwrap();
+        if self.chain_id() == 31337 {
+            return true;
+        }
+
         // TODO cache node_info for entire chain
-        self.chain_id() == 31337 || provider.anvil_node_info().await.is_ok()
+        match self.get_alloy_provider().await {
+            Ok(provider) => provider.anvil_node_info().await.is_ok(),
+            Err(_) => false,
+        }
     }
 
     pub async fn get_alloy_provider(
         &self,
     ) -> Result<RootProvider<Ethereum>, RpcError<TransportErrorKind>> {
-        ProviderBuilder::new()
+        let endpoint = self.unlocked_endpoint()?;
+        let client = ClientBuilder::default()
+            .transport(endpoint.transport(), guess_local_url(&endpoint.url));
+
+        Ok(ProviderBuilder::new()
             .disable_recommended_fillers()
-            .connect(self.http_url.as_str())
-            .await
+            .on_client(client))
     }
 
-    pub fn get_provider(&self) -> RootProvider<Ethereum> {
-        let client = ClientBuilder::default()
-            .layer(RetryBackoffLayer::new(10, 500, 300))
-            .http(self.http_url.clone());
-
-        ProviderBuilder::new()
+    pub fn get_provider(&self) -> Result<RootProvider<Ethereum>, TransportError> {
+        Ok(ProviderBuilder::new()
             .disable_recommended_fillers()
-            .on_client(client)
+            .on_client(self.rpc_client()?))
+    }
+
+    /// A client for the endpoint currently in use, with its secrets injected
+    pub fn rpc_client(&self) -> Result<RpcClient, TransportError> {
+        let endpoint = self.unlocked_endpoint()?;
+
+        Ok(ClientBuilder::default()
+            .layer(RetryBackoffLayer::new(10, 500, 300))
+            .transport(endpoint.transport(), guess_local_url(&endpoint.url)))
     }
 }
 
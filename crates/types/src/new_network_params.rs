@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::{DedupChainId, Network};
+use crate::{DedupChainId, Network, RpcEndpoint};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct NewNetworkParams {
@@ -20,7 +20,7 @@ impl NewNetworkParams {
             dedup_chain_id: (self.dedup_chain_id.chain_id(), deduplication_id).into(),
             name: self.name,
             explorer_url: self.explorer_url,
-            http_url: self.http_url,
+            endpoints: vec![RpcEndpoint::new(self.http_url, 0)],
             ws_url: self.ws_url,
             currency: self.currency,
             decimals: self.decimals,
@@ -0,0 +1,92 @@
+use alloy::primitives::{Address, Bytes, B256};
+use serde::{Deserialize, Serialize};
+
+use crate::{DedupChainId, Json};
+
+/// The state-changing calls a dapp made during a session, in order
+/// Can later be replayed against anvil, to check for regressions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Recording {
+    #[serde(default)]
+    pub id: i64,
+
+    pub name: String,
+
+    /// if set, only calls from this domain were recorded
+    pub domain: Option<String>,
+
+    /// unix timestamp
+    pub created_at: u64,
+
+    pub network: RecordingNetwork,
+
+    pub steps: Vec<RecordedStep>,
+}
+
+/// The network the recording started on, and how to get back to its initial state
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingNetwork {
+    pub chain_id: u32,
+
+    /// tells apart networks sharing `chain_id`
+    /// missing from recordings made before it was stored
+    #[serde(default)]
+    pub dedup_chain_id: Option<DedupChainId>,
+
+    /// `evm_snapshot` id taken when recording started, if the network is anvil
+    pub snapshot: Option<String>,
+
+    /// set if the network is an anvil fork
+    pub fork_url: Option<String>,
+    pub fork_block_number: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RecordedStep {
+    #[serde(rename_all = "camelCase")]
+    Transaction {
+        chain_id: u32,
+
+        /// params of `eth_sendTransaction`, as sent by the dapp, except that `from` is always
+        /// set, so that replays know which account to impersonate
+        request: Json,
+
+        /// `None` if the transaction was never sent, or until a UserOperation is included
+        hash: Option<B256>,
+
+        /// set for transactions sent by smart accounts, for which `eth_sendTransaction` returns
+        /// the UserOperation hash. `hash` is then the transaction that included it
+        #[serde(default)]
+        user_operation_hash: Option<B256>,
+
+        /// error returned when sending, if any
+        error: Option<String>,
+
+        /// `None` until the receipt is known
+        status: Option<bool>,
+
+        #[serde(default)]
+        logs: Vec<RecordedLog>,
+    },
+
+    /// Kept for reference. Signatures have no effect on chain, so they're not replayed
+    #[serde(rename_all = "camelCase")]
+    Signature {
+        chain_id: u32,
+        method: String,
+        params: Json,
+    },
+
+    #[serde(rename_all = "camelCase")]
+    ChainSwitch { chain_id: u32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecordedLog {
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
+}
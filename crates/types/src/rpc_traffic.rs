@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Json;
+
+/// A single JSON-RPC call, as recorded by the traffic inspector
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTrafficEntry {
+    #[serde(default)]
+    pub id: i64,
+
+    /// unix timestamp, in milliseconds
+    pub timestamp: u64,
+
+    pub source: RpcTrafficSource,
+
+    /// the individual connection the call came from, e.g. a WebSocket peer's socket address
+    pub peer: Option<String>,
+
+    /// domain, or client name, of the connection
+    pub origin: Option<String>,
+
+    pub chain_id: Option<u32>,
+
+    /// node the call was sent to. only set for upstream calls
+    pub url: Option<String>,
+
+    pub method: String,
+    pub params: Json,
+    pub result: Option<Json>,
+    pub error: Option<Json>,
+
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcTrafficSource {
+    /// a call made by a dapp to ethui
+    Dapp,
+
+    /// a call made by ethui to a node, on behalf of a dapp
+    Upstream,
+}
+
+impl RpcTrafficSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dapp => "dapp",
+            Self::Upstream => "upstream",
+        }
+    }
+}
+
+impl std::str::FromStr for RpcTrafficSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dapp" => Ok(Self::Dapp),
+            "upstream" => Ok(Self::Upstream),
+            _ => Err(format!("invalid traffic source: {}", s)),
+        }
+    }
+}
+
+/// Criteria for listing recorded traffic. Unset fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RpcTrafficFilter {
+    pub source: Option<RpcTrafficSource>,
+    pub origin: Option<String>,
+    pub peer: Option<String>,
+    pub chain_id: Option<u32>,
+
+    /// matches any method containing this string
+    pub method: Option<String>,
+
+    /// only include failed calls
+    pub errors_only: bool,
+
+    /// unix timestamp, in milliseconds
+    pub since: Option<u64>,
+
+    /// unix timestamp, in milliseconds
+    pub until: Option<u64>,
+
+    pub limit: Option<u32>,
+}
@@ -34,6 +34,9 @@ pub enum UINotify {
     BalancesUpdated,
     ContractsUpdated,
     SettingsChanged,
+    KeystoresChanged,
+    DevAccountsDetected,
+    AddressBookUpdated,
 }
 
 impl UINotify {
@@ -47,6 +50,9 @@ impl UINotify {
             Self::BalancesUpdated => "balances-updated",
             Self::ContractsUpdated => "contracts-updated",
             Self::SettingsChanged => "settings-changed",
+            Self::KeystoresChanged => "keystores-changed",
+            Self::DevAccountsDetected => "dev-accounts-detected",
+            Self::AddressBookUpdated => "address-book-updated",
         }
     }
 }
@@ -1,6 +1,14 @@
+use std::path::PathBuf;
+
 use ethui_types::{Address, GlobalState, Json};
 
-use super::{utils, Result, Wallet, WalletControl, Wallets};
+use super::{
+    dev_accounts::{self, DevAccounts},
+    foundry_keystores::{self, FoundryKeystore},
+    utils,
+    wallets::GeneratedHDWalletParams,
+    DiscoveryParams, DiscoveryResult, Result, Wallet, WalletControl, Wallets,
+};
 
 /// Lists all wallets
 #[tauri::command]
@@ -26,12 +34,12 @@ pub async fn wallets_get_current_address() -> Result<Address> {
 
 #[tauri::command]
 pub async fn wallets_create(params: Json) -> Result<()> {
-    Wallets::write().await.create(params).await
+    Wallets::create(params).await
 }
 
 #[tauri::command]
 pub async fn wallets_update(name: String, params: Json) -> Result<()> {
-    Wallets::write().await.update(name, params).await
+    Wallets::update_without_lock(name, params).await
 }
 
 #[tauri::command]
@@ -63,8 +71,9 @@ pub async fn wallets_get_wallet_addresses(name: String) -> Result<Vec<(String, A
 pub async fn wallets_get_mnemonic_addresses(
     mnemonic: String,
     derivation_path: String,
+    passphrase: Option<String>,
 ) -> Vec<(String, Address)> {
-    utils::derive_addresses(&mnemonic, &derivation_path, 5)
+    utils::derive_addresses_with_passphrase(&mnemonic, passphrase.as_deref(), &derivation_path, 5)
 }
 
 //Checking the mnemonic when entering a new wallet
@@ -73,6 +82,62 @@ pub fn wallets_validate_mnemonic(mnemonic: String) -> bool {
     utils::validate_mnemonic(&mnemonic)
 }
 
+/// Generates a new mnemonic with the given number of words (12 or 24)
+/// Also returns the indices of the words the user will be asked to confirm before the wallet is
+/// created
+#[tauri::command]
+pub fn wallets_generate_mnemonic(word_count: usize) -> Result<(String, Vec<usize>)> {
+    let mnemonic = utils::generate_mnemonic(word_count)?;
+    let confirm = utils::issue_confirmation_indices(&mnemonic);
+
+    Ok((mnemonic, confirm))
+}
+
+/// Creates an HD wallet from a generated mnemonic, once the user confirmed the requested words
+#[tauri::command]
+pub async fn wallets_create_generated(params: GeneratedHDWalletParams) -> Result<()> {
+    Wallets::write().await.create_generated(params).await
+}
+
+/// Scans an HD wallet's derivation path for addresses with on-chain activity
+/// Optionally updates the wallet's address count to cover all of them
+#[tauri::command]
+pub async fn wallets_discover_addresses(
+    name: String,
+    params: DiscoveryParams,
+) -> Result<DiscoveryResult> {
+    Wallets::discover(name, params).await
+}
+
+/// Lists keystores found in `~/.foundry/keystores`
+#[tauri::command]
+pub async fn wallets_list_foundry_keystores() -> Vec<FoundryKeystore> {
+    foundry_keystores::list().await
+}
+
+/// Imports a foundry keystore as a JSON keystore wallet
+#[tauri::command]
+pub async fn wallets_import_foundry_keystore(file: PathBuf) -> Result<()> {
+    foundry_keystores::import(file).await
+}
+
+/// Lists dev networks whose node accounts don't match an existing wallet
+#[tauri::command]
+pub async fn wallets_get_dev_accounts() -> Vec<DevAccounts> {
+    dev_accounts::suggestions().await
+}
+
+/// Creates or refreshes the dev wallet matching a dev network's accounts
+#[tauri::command]
+pub async fn wallets_apply_dev_accounts(network: String) -> Result<()> {
+    dev_accounts::apply(&network).await
+}
+
+#[tauri::command]
+pub async fn wallets_dismiss_dev_accounts(network: String) {
+    dev_accounts::dismiss(&network).await
+}
+
 #[tauri::command]
 pub async fn wallets_ledger_derive(paths: Vec<String>) -> Result<Vec<(String, Address)>> {
     utils::ledger_derive_multiple(paths).await
@@ -0,0 +1,281 @@
+//! Detection of the accounts exposed by dev nodes (anvil), so that a matching dev wallet can be
+//! offered for each dev network
+//!
+//! anvil doesn't expose its mnemonic, so the wallet is only built from the default mnemonic if
+//! its addresses match the node's. Otherwise the node's accounts are impersonated
+
+use std::collections::HashMap;
+
+use alloy::providers::Provider as _;
+use ethui_broadcast::InternalMsg;
+use ethui_networks::Networks;
+use ethui_types::{Address, GlobalState, Json, Network, UINotify};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::{broadcast, Mutex};
+use tracing::{trace, warn};
+
+use crate::{
+    utils,
+    wallets::{Impersonator, PlaintextWallet},
+    Error, Result, Wallet, WalletControl, WalletType, Wallets,
+};
+
+const DEFAULT_MNEMONIC: &str = "test test test test test test test test test test test junk";
+const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0";
+
+/// Pending suggestions, indexed by network name
+static SUGGESTIONS: Lazy<Mutex<HashMap<String, DevAccounts>>> = Lazy::new(Default::default);
+
+/// Accounts found on a dev node
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevAccounts {
+    /// name of the network, which is also used as the wallet's name
+    pub network: String,
+    pub chain_id: u32,
+    pub accounts: Vec<Address>,
+
+    /// result of `anvil_nodeInfo`
+    pub node_info: Json,
+
+    /// whether the accounts are derived from anvil's default mnemonic
+    pub default_mnemonic: bool,
+
+    /// whether a wallet for this network already exists, and will be refreshed
+    pub wallet_exists: bool,
+}
+
+pub(crate) async fn init() {
+    // subscribe before networks are initialized, so that their initial broadcast isn't missed
+    let rx = ethui_broadcast::subscribe_internal().await;
+    tokio::spawn(async { receiver(rx).await });
+}
+
+async fn receiver(mut rx: broadcast::Receiver<InternalMsg>) -> ! {
+    loop {
+        match rx.recv().await {
+            Ok(InternalMsg::NetworkAdded(network)) | Ok(InternalMsg::NetworkUpdated(network)) => {
+                tokio::spawn(async move { on_network(network).await });
+            }
+            Ok(InternalMsg::NetworkRemoved(network)) => {
+                SUGGESTIONS.lock().await.remove(&network.name);
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Lists dev networks whose accounts don't match any existing wallet
+pub async fn suggestions() -> Vec<DevAccounts> {
+    SUGGESTIONS.lock().await.values().cloned().collect()
+}
+
+/// Ignores the current suggestion for a network
+pub async fn dismiss(network: &str) {
+    SUGGESTIONS.lock().await.remove(network);
+}
+
+/// Creates or refreshes the dev wallet for the given network, named after it
+pub async fn apply(network: &str) -> Result<()> {
+    let network = Networks::read()
+        .await
+        .get_network_by_name(network)
+        .ok_or_else(|| Error::InvalidNetworkName(network.into()))?;
+
+    let dev = detect(&network)
+        .await?
+        .ok_or_else(|| Error::NotDevNetwork(network.name.clone()))?;
+
+    let wallet = dev.to_wallet()?;
+    let mut wallets = Wallets::write().await;
+
+    match wallets.get(&dev.network).map(WalletType::from) {
+        None => wallets.add(wallet).await?,
+
+        // same type, so the wallet can be updated in place, keeping its position
+        Some(ty) if ty == WalletType::from(&wallet) => {
+            wallets
+                .update(dev.network.clone(), serde_json::to_value(&wallet)?)
+                .await?
+        }
+
+        Some(WalletType::Plaintext) | Some(WalletType::Impersonator) => {
+            wallets.remove(dev.network.clone()).await?;
+            wallets.add(wallet).await?;
+        }
+
+        // never replace a wallet that wasn't created from a dev node
+        Some(_) => return Err(Error::DuplicateWalletNames(dev.network)),
+    }
+
+    drop(wallets);
+    dismiss(&network.name).await;
+
+    Ok(())
+}
+
+async fn on_network(network: Network) {
+    if !network.is_dev().await {
+        return;
+    }
+
+    let dev = match detect(&network).await {
+        Ok(Some(dev)) => dev,
+        Ok(None) => return,
+        Err(e) => {
+            warn!(network = %network.name, "failed to detect dev accounts: {}", e);
+            return;
+        }
+    };
+
+    if dev.matches_wallet().await {
+        trace!(network = %network.name, "dev wallet is up to date");
+        dismiss(&network.name).await;
+        return;
+    }
+
+    SUGGESTIONS.lock().await.insert(network.name.clone(), dev);
+    ethui_broadcast::ui_notify(UINotify::DevAccountsDetected).await;
+}
+
+/// Reads the node's accounts and config. Returns `None` if the node is not anvil, or exposes no
+/// accounts
+async fn detect(network: &Network) -> Result<Option<DevAccounts>> {
+    let provider = network.get_provider()?;
+
+    let Ok(node_info) = provider
+        .raw_request::<_, Json>("anvil_nodeInfo".into(), ())
+        .await
+    else {
+        return Ok(None);
+    };
+
+    let accounts = provider.get_accounts().await?;
+    if accounts.is_empty() {
+        return Ok(None);
+    }
+
+    let wallet_exists = Wallets::read().await.get(&network.name).is_some();
+
+    Ok(Some(DevAccounts {
+        network: network.name.clone(),
+        chain_id: network.chain_id(),
+        default_mnemonic: is_default_mnemonic(&accounts),
+        accounts,
+        node_info,
+        wallet_exists,
+    }))
+}
+
+/// anvil lists its accounts in derivation order, so they must match the default mnemonic's
+/// first `n` addresses
+fn is_default_mnemonic(accounts: &[Address]) -> bool {
+    let derived = utils::derive_addresses(
+        DEFAULT_MNEMONIC,
+        DEFAULT_DERIVATION_PATH,
+        accounts.len() as u32,
+    );
+
+    derived.iter().map(|(_, a)| a).eq(accounts.iter())
+}
+
+impl DevAccounts {
+    fn to_wallet(&self) -> Result<Wallet> {
+        let wallet = if self.default_mnemonic {
+            Wallet::Plaintext(serde_json::from_value::<PlaintextWallet>(json!({
+                "name": self.network,
+                "mnemonic": DEFAULT_MNEMONIC,
+                "derivationPath": DEFAULT_DERIVATION_PATH,
+                "count": self.accounts.len(),
+            }))?)
+        } else {
+            Wallet::Impersonator(Impersonator {
+                name: self.network.clone(),
+                addresses: self.accounts.clone(),
+                current: 0,
+            })
+        };
+
+        Ok(wallet)
+    }
+
+    /// Checks if a wallet named after the network already holds exactly these accounts
+    async fn matches_wallet(&self) -> bool {
+        let wallets = Wallets::read().await;
+        let Some(wallet) = wallets.get(&self.network) else {
+            return false;
+        };
+
+        let addresses: Vec<_> = wallet
+            .get_all_addresses()
+            .await
+            .into_iter()
+            .map(|(_, a)| a)
+            .collect();
+
+        addresses == self.accounts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::address;
+
+    use super::*;
+
+    fn dev_accounts(accounts: Vec<Address>) -> DevAccounts {
+        DevAccounts {
+            network: "Anvil".into(),
+            chain_id: 31337,
+            default_mnemonic: is_default_mnemonic(&accounts),
+            accounts,
+            node_info: Json::Null,
+            wallet_exists: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn default_mnemonic_becomes_plaintext_wallet() {
+        let accounts: Vec<_> =
+            utils::derive_addresses(DEFAULT_MNEMONIC, DEFAULT_DERIVATION_PATH, 5)
+                .into_iter()
+                .map(|(_, a)| a)
+                .collect();
+
+        let wallet = dev_accounts(accounts.clone()).to_wallet().unwrap();
+
+        assert_eq!(WalletType::from(&wallet), WalletType::Plaintext);
+        assert_eq!(wallet.name(), "Anvil");
+        let addresses: Vec<_> = wallet
+            .get_all_addresses()
+            .await
+            .into_iter()
+            .map(|(_, a)| a)
+            .collect();
+        assert_eq!(addresses, accounts);
+    }
+
+    #[test]
+    fn custom_accounts_become_impersonator() {
+        let accounts = vec![address!("0x000000000000000000000000000000000000dEaD")];
+
+        let wallet = dev_accounts(accounts).to_wallet().unwrap();
+
+        assert_eq!(WalletType::from(&wallet), WalletType::Impersonator);
+    }
+
+    #[test]
+    fn detects_default_mnemonic_prefix_only() {
+        let mut accounts: Vec<_> =
+            utils::derive_addresses(DEFAULT_MNEMONIC, DEFAULT_DERIVATION_PATH, 3)
+                .into_iter()
+                .map(|(_, a)| a)
+                .collect();
+        assert!(is_default_mnemonic(&accounts));
+
+        accounts.swap(0, 1);
+        assert!(!is_default_mnemonic(&accounts));
+    }
+}
@@ -0,0 +1,257 @@
+use std::{future::Future, ops::Range};
+
+use alloy::providers::Provider as _;
+use ethui_networks::Networks;
+use ethui_types::{Address, GlobalState, Network};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+use tracing::warn;
+
+use crate::{wallets::HDWallet, Error, Result};
+
+/// Hard limit on how far discovery will walk a derivation path, regardless of the gap limit
+const MAX_INDEX: u32 = 1000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryParams {
+    /// chain IDs to look for activity on. If empty, all known networks are checked
+    #[serde(default)]
+    pub chain_ids: Vec<u32>,
+
+    /// how many consecutive unused addresses to find before stopping
+    #[serde(default = "default_gap_limit")]
+    pub gap_limit: u32,
+
+    /// if true, the wallet's `count` is updated to the suggested value
+    #[serde(default)]
+    pub apply: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryResult {
+    /// all paths found to have any on-chain activity
+    pub active: Vec<(String, Address)>,
+
+    /// the smallest `count` that includes every active path
+    pub suggested_count: u32,
+}
+
+/// Walks the derivation path of an HD wallet, looking for addresses that have been used on any of
+/// the given networks
+///
+/// An address is considered used if it has a non-zero nonce or balance, or if Alchemy (when
+/// configured and supported for that chain) reports any asset transfers for it.
+/// Stops after finding `gap_limit` consecutive unused addresses
+pub(crate) async fn discover(
+    wallet: &HDWallet,
+    params: &DiscoveryParams,
+) -> Result<DiscoveryResult> {
+    if params.gap_limit == 0 {
+        return Err(Error::InvalidGapLimit);
+    }
+
+    let networks = networks_for(&params.chain_ids).await?;
+    let networks = &networks;
+
+    let active = walk(
+        params.gap_limit,
+        move |range| wallet.derive_range(range),
+        move |address| is_used(address, networks),
+    )
+    .await?;
+
+    Ok(DiscoveryResult {
+        suggested_count: suggested_count(&active),
+        active,
+    })
+}
+
+/// Derives addresses in batches, until `gap_limit` consecutive ones are unused
+async fn walk<D, DF, U, UF>(
+    gap_limit: u32,
+    mut derive: D,
+    mut is_used: U,
+) -> Result<Vec<(String, Address)>>
+where
+    D: FnMut(Range<u32>) -> DF,
+    DF: Future<Output = Result<Vec<(String, Address)>>>,
+    U: FnMut(Address) -> UF,
+    UF: Future<Output = bool>,
+{
+    let mut active = vec![];
+    let mut gap = 0;
+    let mut next = 0;
+
+    while gap < gap_limit && next < MAX_INDEX {
+        let end = (next + gap_limit - gap).min(MAX_INDEX);
+        let batch = derive(next..end).await?;
+        next = end;
+
+        for (path, address) in batch {
+            if is_used(address).await {
+                active.push((path, address));
+                gap = 0;
+            } else {
+                gap += 1;
+            }
+
+            if gap >= gap_limit {
+                break;
+            }
+        }
+    }
+
+    Ok(active)
+}
+
+/// The smallest `count` that includes the last active path
+fn suggested_count(active: &[(String, Address)]) -> u32 {
+    active
+        .last()
+        .and_then(|(path, _)| path.rsplit('/').next())
+        .and_then(|idx| idx.parse::<u32>().ok())
+        .map(|idx| idx + 1)
+        .unwrap_or(1)
+}
+
+async fn networks_for(chain_ids: &[u32]) -> Result<Vec<Network>> {
+    let networks = Networks::read().await;
+
+    if chain_ids.is_empty() {
+        return Ok(networks.get_all().cloned().collect());
+    }
+
+    chain_ids
+        .iter()
+        .map(|chain_id| {
+            networks
+                .get_network(*chain_id)
+                .ok_or(Error::InvalidNetwork(*chain_id))
+        })
+        .collect()
+}
+
+/// Checks every network concurrently, returning as soon as any of them reports activity
+async fn is_used(address: Address, networks: &[Network]) -> bool {
+    let mut set = JoinSet::new();
+
+    for network in networks.iter().cloned() {
+        set.spawn(is_used_on(address, network));
+    }
+
+    while let Some(res) = set.join_next().await {
+        if let Ok(true) = res {
+            return true;
+        }
+    }
+
+    false
+}
+
+async fn is_used_on(address: Address, network: Network) -> bool {
+    let chain_id = network.chain_id();
+    let provider = match network.get_provider() {
+        Ok(provider) => provider,
+        Err(e) => {
+            warn!(chain_id, ?address, "failed to connect: {}", e);
+            return false;
+        }
+    };
+
+    match provider.get_transaction_count(address).await {
+        Ok(nonce) if nonce > 0 => return true,
+        Ok(_) => {}
+        Err(e) => warn!(chain_id, ?address, "failed to fetch nonce: {}", e),
+    }
+
+    match provider.get_balance(address).await {
+        Ok(balance) if !balance.is_zero() => return true,
+        Ok(_) => {}
+        Err(e) => warn!(chain_id, ?address, "failed to fetch balance: {}", e),
+    }
+
+    // receiving tokens doesn't change the nonce or ETH balance, so we ask Alchemy for any
+    // transfer history, if it's available for this chain
+    if ethui_sync_alchemy::supports_network(chain_id) {
+        if let Ok(alchemy) = ethui_sync_alchemy::get_alchemy(chain_id).await {
+            match alchemy.has_activity(address).await {
+                Ok(true) => return true,
+                Ok(false) => {}
+                Err(e) => warn!(
+                    chain_id,
+                    ?address,
+                    "failed to fetch alchemy activity: {}",
+                    e
+                ),
+            }
+        }
+    }
+
+    false
+}
+
+const fn default_gap_limit() -> u32 {
+    20
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashSet};
+
+    use super::*;
+
+    fn derive(range: Range<u32>) -> impl Future<Output = Result<Vec<(String, Address)>>> {
+        let batch = range
+            .map(|i| (format!("m/44'/60'/0'/0/{i}"), address_at(i)))
+            .collect();
+
+        async move { Ok(batch) }
+    }
+
+    fn address_at(index: u32) -> Address {
+        Address::left_padding_from(&index.to_be_bytes())
+    }
+
+    #[tokio::test]
+    async fn stops_after_gap_limit_unused_addresses() {
+        let used: HashSet<_> = [0, 3, 9].into_iter().map(address_at).collect();
+        let checked = RefCell::new(vec![]);
+
+        let active = walk(5, derive, |address| {
+            checked.borrow_mut().push(address);
+            let res = used.contains(&address);
+            async move { res }
+        })
+        .await
+        .unwrap();
+
+        // index 9 is past the 5 unused addresses that follow index 3
+        assert_eq!(
+            active,
+            vec![
+                ("m/44'/60'/0'/0/0".to_string(), address_at(0)),
+                ("m/44'/60'/0'/0/3".to_string(), address_at(3)),
+            ]
+        );
+        assert_eq!(
+            *checked.borrow(),
+            (0..=8).map(address_at).collect::<Vec<_>>()
+        );
+        assert_eq!(suggested_count(&active), 4);
+    }
+
+    #[tokio::test]
+    async fn stops_at_max_index() {
+        let active = walk(20, derive, |_| async { true }).await.unwrap();
+
+        assert_eq!(active.len(), MAX_INDEX as usize);
+        assert_eq!(suggested_count(&active), MAX_INDEX);
+    }
+
+    #[test]
+    fn suggests_a_single_address_when_none_are_active() {
+        assert_eq!(suggested_count(&[]), 1);
+    }
+}
@@ -1,4 +1,4 @@
-use alloy::signers::local::LocalSignerError;
+use alloy::{primitives::B256, signers::local::LocalSignerError};
 use serde::Serialize;
 use tokio::sync::oneshot;
 
@@ -40,6 +40,27 @@ pub enum Error {
     #[error("This wallet type cannot sign")]
     WalletCantSign,
 
+    #[error("wallet `{0}` is watch-only and cannot sign")]
+    WatchOnly(String),
+
+    #[error("wallet `{0}` cannot sign raw hashes, such as EIP-7702 authorizations")]
+    CantSignHash(String),
+
+    #[error("wallet must have at least one address")]
+    NoAddresses,
+
+    #[error("duplicate key `{0}`")]
+    DuplicateKey(String),
+
+    #[error("invalid password")]
+    InvalidPassword,
+
+    #[error("wallet `{0}` is locked")]
+    WalletLocked(String),
+
+    #[error("failed to encrypt wallet keys")]
+    Encryption,
+
     #[error(transparent)]
     ParseInto(#[from] std::num::ParseIntError),
 
@@ -49,8 +70,56 @@ pub enum Error {
     #[error("Ledger error: {0}")]
     Ledger(String),
 
+    #[error("remote signer error: {0}")]
+    RemoteSigner(String),
+
+    #[error(transparent)]
+    Signer(#[from] alloy::signers::Error),
+
+    #[error(transparent)]
+    Contract(#[from] alloy::contract::Error),
+
+    #[error(transparent)]
+    Transport(#[from] alloy::transports::TransportError),
+
+    #[error("contract wallet transactions must have a recipient")]
+    MissingRecipient,
+
+    #[error("not enough safe owners available: {signatures} of {threshold} signatures collected")]
+    SafeThresholdNotMet { signatures: usize, threshold: u64 },
+
+    #[error("bundler error: {0}")]
+    Bundler(String),
+
+    #[error("timed out waiting for user operation {0}")]
+    UserOperationTimeout(B256),
+
     #[error(transparent)]
     LocalSigner(#[from] LocalSignerError),
+
+    #[error("invalid mnemonic word count: {0}. must be 12 or 24")]
+    InvalidWordCount(usize),
+
+    #[error("mnemonic words do not match")]
+    MnemonicConfirmationFailed,
+
+    #[error("invalid network: {0}")]
+    InvalidNetwork(u32),
+
+    #[error("unknown network: {0}")]
+    InvalidNetworkName(String),
+
+    #[error("network {0} is not an anvil node, or exposes no accounts")]
+    NotDevNetwork(String),
+
+    #[error("gap limit must be greater than 0")]
+    InvalidGapLimit,
+
+    #[error("`{0}` can't be changed. Create a new wallet instead")]
+    ImmutableField(String),
+
+    #[error("could not read the address of keystore {0}")]
+    KeystoreAddress(std::path::PathBuf),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,377 @@
+//! Keeps track of keystores created by foundry (`cast wallet import`), which live in
+//! `~/.foundry/keystores`, so they can be imported as JSON keystore wallets
+//!
+//! Wallets previously imported from that directory are kept in sync: renaming a keystore file
+//! updates the wallet, and removing it removes the wallet
+//!
+//! If the directory doesn't exist yet, its closest existing ancestor is watched instead, until
+//! the directory is created
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use ethui_types::{GlobalState, UINotify};
+use kameo::{
+    actor::ActorRef, error::BoxError, mailbox::bounded::BoundedMailbox, message::Message, Actor,
+};
+use notify::{
+    event::{ModifyKind, RenameMode},
+    EventKind, RecommendedWatcher, RecursiveMode,
+};
+use notify_debouncer_full::{
+    new_debouncer, DebounceEventResult, DebouncedEvent, Debouncer, RecommendedCache,
+};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use serde_json::json;
+use tracing::{instrument, trace, warn};
+
+use crate::{wallets::JsonKeystoreWallet, Error, Result, Wallet, WalletControl, Wallets};
+
+static WORKER: OnceCell<ActorRef<Worker>> = OnceCell::new();
+
+/// A keystore file found in foundry's keystores directory
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FoundryKeystore {
+    /// the keystore's file name, which foundry uses as the account name
+    pub name: String,
+    pub file: PathBuf,
+
+    /// name of the wallet this keystore was imported as, if any
+    pub wallet: Option<String>,
+}
+
+pub(crate) async fn init() {
+    let Some(dir) = keystores_dir() else {
+        warn!("could not determine home directory. foundry keystores will not be watched");
+        return;
+    };
+
+    let handle = kameo::spawn(Worker::new(dir));
+    WORKER.set(handle).unwrap();
+}
+
+/// Lists all known foundry keystores, and the wallets they were imported as
+pub async fn list() -> Vec<FoundryKeystore> {
+    let Some(worker) = WORKER.get() else {
+        return vec![];
+    };
+
+    let files = worker.ask(ListFiles).await.unwrap_or_default();
+    let wallets = Wallets::read().await;
+
+    files
+        .into_iter()
+        .map(|file| FoundryKeystore {
+            name: file_name(&file),
+            wallet: imported_as(&wallets, &file),
+            file,
+        })
+        .collect()
+}
+
+/// Imports a foundry keystore as a JSON keystore wallet, named after the keystore
+///
+/// Foundry keystores don't store the address in plaintext, so this may prompt the user to
+/// unlock the keystore once
+pub async fn import(file: PathBuf) -> Result<()> {
+    let known = list().await.into_iter().find(|k| k.file == file);
+
+    let keystore = match known {
+        Some(keystore) => keystore,
+        None => return Err(Error::InvalidKey(file.to_string_lossy().to_string())),
+    };
+
+    if let Some(wallet) = keystore.wallet {
+        return Err(Error::DuplicateWalletNames(wallet));
+    }
+
+    // build the wallet before locking, since unlocking waits for user input
+    let wallet = JsonKeystoreWallet::from_file(keystore.name, keystore.file).await?;
+
+    Wallets::write()
+        .await
+        .add(Wallet::JsonKeystore(wallet))
+        .await
+}
+
+/// `~/.foundry/keystores`
+fn keystores_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".foundry").join("keystores"))
+}
+
+fn file_name(file: &Path) -> String {
+    file.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn imported_as(wallets: &Wallets, file: &Path) -> Option<String> {
+    wallets.wallets.iter().find_map(|w| match w {
+        Wallet::JsonKeystore(w) if w.file == file => Some(w.name()),
+        _ => None,
+    })
+}
+
+pub struct Worker {
+    dir: PathBuf,
+    files: BTreeSet<PathBuf>,
+    watcher: Option<Debouncer<RecommendedWatcher, RecommendedCache>>,
+
+    /// The directory currently being watched. Either `dir`, or its closest existing ancestor
+    watched: Option<PathBuf>,
+}
+
+struct ListFiles;
+
+impl Message<ListFiles> for Worker {
+    type Reply = Vec<PathBuf>;
+
+    async fn handle(
+        &mut self,
+        _msg: ListFiles,
+        _ctx: kameo::message::Context<'_, Self, Self::Reply>,
+    ) -> Self::Reply {
+        self.files.iter().cloned().collect()
+    }
+}
+
+impl Message<Vec<DebouncedEvent>> for Worker {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        events: Vec<DebouncedEvent>,
+        _ctx: kameo::message::Context<'_, Self, Self::Reply>,
+    ) -> Self::Reply {
+        trace!("process_debounced_events");
+
+        // the directory, or one of its ancestors, may have been created or removed
+        let mut changed = self.watch();
+
+        for debounced in events.into_iter() {
+            let paths = &debounced.event.paths;
+
+            match debounced.event.kind {
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if paths.len() == 2 => {
+                    let (from, to) = (&paths[0], &paths[1]);
+                    changed |= match (self.is_in_dir(from), self.is_in_dir(to)) {
+                        (true, true) => self.on_renamed(from, to).await,
+                        (true, false) => self.on_removed(from).await,
+                        (false, true) => self.on_created(to),
+                        (false, false) => false,
+                    };
+                }
+
+                EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                    for path in paths {
+                        changed |= self.on_removed(path).await;
+                    }
+                }
+
+                EventKind::Create(_) | EventKind::Modify(_) => {
+                    for path in paths.iter().filter(|p| self.is_in_dir(p)) {
+                        changed |= self.on_created(path);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        if changed {
+            ethui_broadcast::ui_notify(UINotify::KeystoresChanged).await;
+        }
+    }
+}
+
+impl Actor for Worker {
+    type Mailbox = BoundedMailbox<Self>;
+
+    async fn on_start(
+        &mut self,
+        actor_ref: kameo::actor::ActorRef<Self>,
+    ) -> std::result::Result<(), BoxError> {
+        let debounced_watcher = new_debouncer(
+            Duration::from_millis(500),
+            None,
+            move |result: DebounceEventResult| match result {
+                Ok(events) => {
+                    actor_ref.tell(events);
+                }
+                Err(e) => tracing::warn!("watch error: {:?}", e),
+            },
+        )?;
+
+        self.watcher = Some(debounced_watcher);
+        self.watch();
+
+        Ok(())
+    }
+}
+
+impl Worker {
+    fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            files: Default::default(),
+            watcher: None,
+            watched: None,
+        }
+    }
+
+    /// Watches the keystores directory, or its closest existing ancestor if it doesn't exist
+    ///
+    /// Called again on every change, so the watch moves down as directories are created, and
+    /// back up if they're removed. Whenever the watch moves, the keystores are rescanned.
+    /// Returns whether that changed them
+    fn watch(&mut self) -> bool {
+        let Some(watcher) = self.watcher.as_mut() else {
+            return false;
+        };
+
+        let target = self
+            .dir
+            .ancestors()
+            .find(|d| d.is_dir())
+            .map(Path::to_path_buf);
+        if target == self.watched {
+            return false;
+        }
+
+        if let Some(previous) = self.watched.take() {
+            let _ = watcher.unwatch(&previous);
+        }
+
+        if let Some(ref target) = target {
+            trace!(dir = ?target, "watching for foundry keystores");
+            match watcher.watch(target, RecursiveMode::NonRecursive) {
+                Ok(()) => self.watched = Some(target.clone()),
+                Err(e) => warn!(dir = ?target, "failed to watch: {}", e),
+            }
+        }
+
+        let before = std::mem::take(&mut self.files);
+        self.scan();
+        self.files != before
+    }
+
+    fn is_in_dir(&self, path: &Path) -> bool {
+        path.parent() == Some(self.dir.as_path())
+    }
+
+    /// Lists the keystores directory
+    #[instrument(skip_all)]
+    fn scan(&mut self) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            self.files.clear();
+            return;
+        };
+
+        self.files = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| is_keystore(p))
+            .collect();
+
+        trace!(files = ?self.files);
+    }
+
+    fn on_created(&mut self, path: &Path) -> bool {
+        is_keystore(path) && self.files.insert(path.to_path_buf())
+    }
+
+    /// Forgets a keystore, and removes the wallet it was imported as
+    async fn on_removed(&mut self, path: &Path) -> bool {
+        if !self.files.remove(path) {
+            return false;
+        }
+
+        let mut wallets = Wallets::write().await;
+        if let Some(name) = imported_as(&wallets, path) {
+            if let Err(e) = wallets.remove(name).await {
+                warn!(?path, "failed to remove wallet for deleted keystore: {}", e);
+            }
+        }
+
+        true
+    }
+
+    /// Points any imported wallet to the new file
+    /// Wallets still named after the old file are renamed as well
+    ///
+    /// The wallet checks whether the new file still holds the same key, so `Wallets` isn't
+    /// locked while updating it
+    async fn on_renamed(&mut self, from: &Path, to: &Path) -> bool {
+        self.files.remove(from);
+        if is_keystore(to) {
+            self.files.insert(to.to_path_buf());
+        }
+
+        let imported = imported_as(&*Wallets::read().await, from);
+        if let Some(name) = imported {
+            let mut params = json!({ "file": to });
+            if name == file_name(from) {
+                params["name"] = file_name(to).into();
+            }
+
+            if let Err(e) = Wallets::update_without_lock(name, params).await {
+                warn!(
+                    ?from,
+                    ?to,
+                    "failed to update wallet for renamed keystore: {}",
+                    e
+                );
+            }
+        }
+
+        true
+    }
+}
+
+/// foundry stores each keystore as a single extension-less JSON file
+fn is_keystore(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .file_name()
+            .is_some_and(|n| !n.to_string_lossy().starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn scans_keystores_ignoring_hidden_files() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("deployer"), "{}").unwrap();
+        fs::write(dir.path().join(".DS_Store"), "").unwrap();
+        fs::create_dir(dir.path().join("nested")).unwrap();
+
+        let mut worker = Worker::new(dir.path().to_path_buf());
+        worker.scan();
+
+        assert_eq!(
+            worker.files.into_iter().collect::<Vec<_>>(),
+            vec![dir.path().join("deployer")]
+        );
+    }
+
+    #[test]
+    fn missing_directory_is_empty() {
+        let mut worker = Worker::new(PathBuf::from("/nonexistent/.foundry/keystores"));
+        worker.scan();
+
+        assert!(worker.files.is_empty());
+    }
+}
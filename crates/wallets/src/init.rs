@@ -10,7 +10,7 @@ use once_cell::sync::OnceCell;
 use serde::Deserialize;
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-use super::{Wallet, Wallets};
+use super::{dev_accounts, foundry_keystores, Wallet, Wallets};
 
 static WALLETS: OnceCell<RwLock<Wallets>> = OnceCell::new();
 
@@ -47,6 +47,9 @@ pub async fn init(pathbuf: PathBuf) {
     res.init_broadcast().await;
 
     WALLETS.set(RwLock::new(res)).unwrap();
+
+    foundry_keystores::init().await;
+    dev_accounts::init().await;
 }
 
 #[async_trait]
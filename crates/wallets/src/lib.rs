@@ -1,7 +1,13 @@
 pub mod commands;
+pub mod dev_accounts;
+mod discovery;
 mod error;
+pub mod foundry_keystores;
 mod init;
+mod remote_signer;
 mod signer;
+#[cfg(test)]
+mod test_utils;
 mod utils;
 mod wallet;
 mod wallets;
@@ -12,9 +18,11 @@ use std::{
     path::{Path, PathBuf},
 };
 
+pub use discovery::{DiscoveryParams, DiscoveryResult};
 pub use error::{Error, Result};
-use ethui_types::{Address, Json, UINotify};
+use ethui_types::{Address, GlobalState, Json, UINotify};
 pub use init::init;
+pub use remote_signer::RemoteSigner;
 use serde::Serialize;
 pub use signer::Signer;
 
@@ -94,8 +102,33 @@ impl Wallets {
         res
     }
 
-    async fn create(&mut self, params: Json) -> Result<()> {
+    /// Creates a wallet and adds it to the list
+    ///
+    /// Some wallets wait for user input while being created (e.g.: unlocking a keystore to read
+    /// its address), so `Wallets` is only locked once the wallet is built
+    async fn create(params: Json) -> Result<()> {
         let wallet = Wallet::create(params).await?;
+        Self::write().await.add(wallet).await
+    }
+
+    /// Creates an HD wallet from a mnemonic generated by `utils::generate_mnemonic`
+    ///
+    /// Fails unless the user correctly confirmed the requested words
+    async fn create_generated(&mut self, params: wallets::GeneratedHDWalletParams) -> Result<()> {
+        if !utils::confirm_mnemonic_words(&params.params.mnemonic, &params.confirmations) {
+            return Err(Error::MnemonicConfirmationFailed);
+        }
+
+        let mnemonic = params.params.mnemonic.clone();
+        let wallet = wallets::HDWallet::from_params(params.params).await?;
+        self.add(Wallet::HDWallet(wallet)).await?;
+
+        utils::forget_confirmation(&mnemonic);
+        Ok(())
+    }
+
+    /// Adds an already built wallet to the list
+    async fn add(&mut self, wallet: Wallet) -> Result<()> {
         let addresses = wallet.get_all_addresses().await;
 
         self.ensure_no_duplicates_of(&wallet.name())?;
@@ -115,15 +148,46 @@ impl Wallets {
         Ok(())
     }
 
+    /// Updates a wallet while holding on to `Wallets`
+    ///
+    /// Only meant for updates that can't wait for user input. Otherwise, use
+    /// `update_without_lock`
     async fn update(&mut self, name: String, params: Json) -> Result<()> {
+        let wallet = self
+            .find_wallet(&name)
+            .cloned()
+            .ok_or_else(|| Error::InvalidWalletName(name.clone()))?;
+
+        let updated = wallet.update(params).await?;
+        self.replace(&name, updated).await
+    }
+
+    /// Updates a wallet without holding on to `Wallets` in the meantime
+    ///
+    /// Updates may load a different keystore file, or unlock the wallet, both of which can
+    /// prompt the user
+    pub(crate) async fn update_without_lock(name: String, params: Json) -> Result<()> {
+        let wallet = Self::read()
+            .await
+            .find_wallet(&name)
+            .cloned()
+            .ok_or_else(|| Error::InvalidWalletName(name.clone()))?;
+
+        let updated = wallet.update(params).await?;
+        Self::write().await.replace(&name, updated).await
+    }
+
+    /// Replaces the wallet with the given name by its updated version
+    async fn replace(&mut self, name: &str, wallet: Wallet) -> Result<()> {
+        // the wallet may have been removed in the meantime
         let i = self
             .wallets
             .iter()
             .position(|w| w.name() == name)
-            .ok_or(Error::InvalidWalletName(name))?;
+            .ok_or_else(|| Error::InvalidWalletName(name.into()))?;
 
         let before = self.wallets[i].get_all_addresses().await;
-        self.wallets[i] = self.wallets[i].clone().update(params).await?;
+        self.wallets[i] = wallet;
         let after = self.wallets[i].get_all_addresses().await;
 
         tokio::spawn(async move {
@@ -166,6 +230,91 @@ impl Wallets {
         Ok(())
     }
 
+    /// Derives a per-site burner address from an HD wallet, at the given path
+    ///
+    /// The address becomes part of the wallet, so it can sign and is synced like any other.
+    /// Deriving a new address may require unlocking the wallet, which is done without holding
+    /// on to `Wallets`, and only if `prompt` is set. Otherwise, a locked wallet fails with
+    /// `WalletLocked`
+    pub async fn add_burner(name: &str, path: &str, prompt: bool) -> Result<Address> {
+        let wallet = match Self::read().await.get(name) {
+            Some(Wallet::HDWallet(wallet)) => wallet.clone(),
+            Some(other) => {
+                return Err(Error::InvalidWalletType(
+                    WalletType::from(other).to_string(),
+                ))
+            }
+            None => return Err(Error::InvalidWalletName(name.into())),
+        };
+
+        if wallet.get_address(path).await.is_err() && !prompt && !wallet.is_unlocked().await {
+            return Err(Error::WalletLocked(name.into()));
+        }
+
+        let address = wallet.derive_burner(path).await?;
+
+        let mut wallets = Self::write().await;
+
+        // the wallet may have been removed while it was being unlocked
+        let is_new = match wallets.wallets.iter_mut().find(|w| w.name() == name) {
+            Some(Wallet::HDWallet(wallet)) => wallet.track_burner(path, address).await,
+            _ => return Err(Error::InvalidWalletName(name.into())),
+        };
+
+        if is_new {
+            wallets.save()?;
+            ethui_broadcast::address_added(address).await;
+            ethui_broadcast::ui_notify(UINotify::WalletsChanged).await;
+        }
+
+        Ok(address)
+    }
+
+    /// Stops tracking a burner address previously added with `add_burner`
+    pub async fn remove_burner(&mut self, name: &str, path: &str) -> Result<()> {
+        let removed = match self.wallets.iter_mut().find(|w| w.name() == name) {
+            Some(Wallet::HDWallet(wallet)) => wallet.remove_burner(path),
+            _ => None,
+        };
+
+        if let Some(address) = removed {
+            self.save()?;
+            ethui_broadcast::address_removed(address).await;
+            ethui_broadcast::ui_notify(UINotify::WalletsChanged).await;
+        }
+
+        Ok(())
+    }
+
+    /// Looks for used addresses in an HD wallet's derivation path, and suggests how many
+    /// addresses the wallet should track
+    ///
+    /// The wallets lock is not held while querying networks, so this takes a `Wallet` out of
+    /// the global state instead of `&self`
+    async fn discover(name: String, params: DiscoveryParams) -> Result<DiscoveryResult> {
+        let wallet = Self::read()
+            .await
+            .find_wallet(&name)
+            .cloned()
+            .ok_or_else(|| Error::InvalidWalletName(name.clone()))?;
+
+        let result = match wallet {
+            Wallet::HDWallet(ref wallet) => discovery::discover(wallet, &params).await?,
+            ref other => {
+                return Err(Error::InvalidWalletType(
+                    WalletType::from(other).to_string(),
+                ))
+            }
+        };
+
+        if params.apply {
+            let params = serde_json::json!({ "count": result.suggested_count });
+            Self::update_without_lock(name, params).await?;
+        }
+
+        Ok(result)
+    }
+
     /// Get all addresses currently enabled in a given wallet
     async fn get_wallet_addresses(&self, name: String) -> Vec<(String, Address)> {
         let wallet = self.find_wallet(&name).unwrap();
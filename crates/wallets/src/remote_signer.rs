@@ -0,0 +1,323 @@
+use alloy::{
+    consensus::{SignableTransaction, Transaction as _, TxEnvelope},
+    dyn_abi::TypedData,
+    eips::{eip2718::Decodable2718, Typed2718 as _},
+    network::TxSigner,
+    primitives::{Address, Bytes, ChainId, PrimitiveSignature, B256},
+    rpc::{
+        client::RpcClient,
+        types::{TransactionInput, TransactionRequest},
+    },
+    signers::{self, UnsupportedSignerOperation},
+    transports::http::{
+        reqwest::{
+            self,
+            header::{HeaderMap, HeaderValue, AUTHORIZATION},
+        },
+        Http,
+    },
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use url::Url;
+
+use crate::{Error, Result};
+
+/// A signer that delegates all signing to a remote service over JSON-RPC, such as web3signer or
+/// Clef
+///
+/// Remote signers don't expose raw hash signing, so every operation maps to its own RPC method
+/// (`eth_sign`, `eth_signTypedData`, `eth_signTransaction`)
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    client: RpcClient,
+    address: Address,
+    chain_id: Option<ChainId>,
+}
+
+/// `eth_signTransaction` returns the raw transaction in web3signer, but geth-like signers wrap
+/// it in an object together with the decoded transaction
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SignedTransaction {
+    Raw(Bytes),
+    Wrapped { raw: Bytes },
+}
+
+impl RemoteSigner {
+    pub fn new(url: Url, auth_token: Option<&str>, address: Address) -> Result<Self> {
+        Ok(Self {
+            client: client(url, auth_token)?,
+            address,
+            chain_id: None,
+        })
+    }
+
+    /// Lists all accounts the remote signer holds keys for
+    pub async fn accounts(url: Url, auth_token: Option<&str>) -> Result<Vec<Address>> {
+        client(url, auth_token)?
+            .request_noparams("eth_accounts")
+            .await
+            .map_err(|e| Error::RemoteSigner(e.to_string()))
+    }
+
+    async fn request<P, R>(&self, method: &'static str, params: P) -> signers::Result<R>
+    where
+        P: serde::Serialize + Clone + std::fmt::Debug + Send + Sync + Unpin + 'static,
+        R: serde::de::DeserializeOwned + std::fmt::Debug + Send + Sync + Unpin + 'static,
+    {
+        self.client
+            .request(method, params)
+            .await
+            .map_err(signers::Error::other)
+    }
+}
+
+fn client(url: Url, auth_token: Option<&str>) -> Result<RpcClient> {
+    let mut headers = HeaderMap::new();
+
+    if let Some(token) = auth_token {
+        let value = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|e| Error::RemoteSigner(e.to_string()))?;
+        headers.insert(AUTHORIZATION, value);
+    }
+
+    let http = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .map_err(|e| Error::RemoteSigner(e.to_string()))?;
+
+    Ok(RpcClient::new(Http::with_client(http, url), false))
+}
+
+fn signature_from_bytes(bytes: Bytes) -> signers::Result<PrimitiveSignature> {
+    Ok(PrimitiveSignature::from_raw(&bytes)?)
+}
+
+#[async_trait]
+impl signers::Signer<PrimitiveSignature> for RemoteSigner {
+    async fn sign_hash(&self, _hash: &B256) -> signers::Result<PrimitiveSignature> {
+        Err(signers::Error::UnsupportedOperation(
+            UnsupportedSignerOperation::SignHash,
+        ))
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> signers::Result<PrimitiveSignature> {
+        let bytes: Bytes = self
+            .request("eth_sign", (self.address, Bytes::copy_from_slice(message)))
+            .await?;
+
+        signature_from_bytes(bytes)
+    }
+
+    async fn sign_dynamic_typed_data(
+        &self,
+        payload: &TypedData,
+    ) -> signers::Result<PrimitiveSignature> {
+        let bytes: Bytes = self
+            .request("eth_signTypedData", (self.address, payload.clone()))
+            .await?;
+
+        signature_from_bytes(bytes)
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        self.chain_id
+    }
+
+    fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        self.chain_id = chain_id;
+    }
+}
+
+#[async_trait]
+impl TxSigner<PrimitiveSignature> for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<PrimitiveSignature>,
+    ) -> signers::Result<PrimitiveSignature> {
+        if let Some(chain_id) = self.chain_id {
+            if !tx.set_chain_id_checked(chain_id) {
+                return Err(signers::Error::TransactionChainIdMismatch {
+                    signer: chain_id,
+                    tx: tx.chain_id().unwrap(),
+                });
+            }
+        }
+
+        let request = TransactionRequest {
+            from: Some(self.address),
+            to: Some(tx.kind()),
+            gas_price: tx.gas_price(),
+            max_fee_per_gas: tx.is_dynamic_fee().then(|| tx.max_fee_per_gas()),
+            max_priority_fee_per_gas: tx.max_priority_fee_per_gas(),
+            gas: Some(tx.gas_limit()),
+            value: Some(tx.value()),
+            // web3signer reads `data`, while geth-like signers read `input`
+            input: TransactionInput::both(tx.input().clone()),
+            nonce: Some(tx.nonce()),
+            chain_id: tx.chain_id(),
+            access_list: tx.access_list().cloned(),
+            transaction_type: Some(tx.ty()),
+            authorization_list: tx.authorization_list().map(|list| list.to_vec()),
+            ..Default::default()
+        };
+
+        let signed = match self.request("eth_signTransaction", (request,)).await? {
+            SignedTransaction::Raw(raw) | SignedTransaction::Wrapped { raw } => raw,
+        };
+
+        let envelope =
+            TxEnvelope::decode_2718(&mut signed.as_ref()).map_err(signers::Error::other)?;
+
+        // make sure the remote signer didn't tamper with the transaction
+        if envelope.signature_hash() != tx.signature_hash() {
+            return Err(signers::Error::other(
+                "remote signer returned a different transaction than requested",
+            ));
+        }
+
+        Ok(*envelope.signature())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        consensus::TxEip1559,
+        eips::eip2718::Encodable2718,
+        network::{EthereumWallet, TransactionBuilder as _},
+        primitives::{address, TxKind, U256},
+        signers::{local::PrivateKeySigner, Signer as _},
+    };
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::test_utils::mock_rpc_server;
+
+    const TOKEN: &str = "secret-token";
+
+    /// Spawns a minimal JSON-RPC server that signs with a local key, and only accepts requests
+    /// carrying the expected bearer token
+    async fn mock_server(signer: PrivateKeySigner) -> Url {
+        mock_rpc_server(move |headers, req| {
+            let signer = signer.clone();
+            async move {
+                if !headers.contains(&format!("authorization: bearer {}", TOKEN.to_lowercase())) {
+                    return Err("401 Unauthorized");
+                }
+
+                Ok(handle_request(&signer, &req["method"], &req["params"]).await)
+            }
+        })
+        .await
+    }
+
+    async fn handle_request(signer: &PrivateKeySigner, method: &Value, params: &Value) -> Value {
+        match method.as_str().unwrap() {
+            "eth_accounts" => json!([signer.address()]),
+            "eth_sign" => {
+                let data: Bytes = serde_json::from_value(params[1].clone()).unwrap();
+                let sig = signer.sign_message(&data).await.unwrap();
+                json!(Bytes::from(sig.as_bytes()))
+            }
+            "eth_signTypedData" => {
+                let data: TypedData = serde_json::from_value(params[1].clone()).unwrap();
+                let sig = signer.sign_dynamic_typed_data(&data).await.unwrap();
+                json!(Bytes::from(sig.as_bytes()))
+            }
+            "eth_signTransaction" => {
+                let request: TransactionRequest =
+                    serde_json::from_value(params[0].clone()).unwrap();
+                let wallet = EthereumWallet::from(signer.clone());
+                let envelope = request.build(&wallet).await.unwrap();
+                json!({ "raw": Bytes::from(envelope.encoded_2718()) })
+            }
+            _ => Value::Null,
+        }
+    }
+
+    async fn setup() -> (PrivateKeySigner, RemoteSigner) {
+        let local = PrivateKeySigner::random();
+        let url = mock_server(local.clone()).await;
+        let remote = RemoteSigner::new(url, Some(TOKEN), local.address()).unwrap();
+
+        (local, remote)
+    }
+
+    #[tokio::test]
+    async fn lists_accounts() {
+        let local = PrivateKeySigner::random();
+        let url = mock_server(local.clone()).await;
+
+        let accounts = RemoteSigner::accounts(url, Some(TOKEN)).await.unwrap();
+
+        assert_eq!(accounts, vec![local.address()]);
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_auth() {
+        let local = PrivateKeySigner::random();
+        let url = mock_server(local.clone()).await;
+
+        assert!(RemoteSigner::accounts(url, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn signs_messages() {
+        let (local, remote) = setup().await;
+
+        let sig = remote.sign_message(b"hello").await.unwrap();
+
+        assert_eq!(sig, local.sign_message(b"hello").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn signs_typed_data() {
+        let (local, remote) = setup().await;
+        let data: TypedData = serde_json::from_value(json!({
+            "types": {
+                "EIP712Domain": [{ "name": "name", "type": "string" }],
+                "Mail": [{ "name": "contents", "type": "string" }]
+            },
+            "primaryType": "Mail",
+            "domain": { "name": "ethui" },
+            "message": { "contents": "hello" }
+        }))
+        .unwrap();
+
+        let sig = remote.sign_dynamic_typed_data(&data).await.unwrap();
+
+        assert_eq!(sig, local.sign_dynamic_typed_data(&data).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn signs_transactions() {
+        let (local, remote) = setup().await;
+        let mut tx = TxEip1559 {
+            chain_id: 1,
+            nonce: 3,
+            gas_limit: 21_000,
+            max_fee_per_gas: 1_000_000_000,
+            max_priority_fee_per_gas: 1,
+            to: TxKind::Call(address!("0x000000000000000000000000000000000000dEaD")),
+            value: U256::from(42),
+            ..Default::default()
+        };
+
+        let sig = remote.sign_transaction(&mut tx).await.unwrap();
+
+        let recovered = sig
+            .recover_address_from_prehash(&tx.signature_hash())
+            .unwrap();
+        assert_eq!(recovered, local.address());
+    }
+}
@@ -1,21 +1,26 @@
 use alloy::{
+    dyn_abi::TypedData,
+    eips::eip7702::{Authorization, SignedAuthorization},
     network::EthereumWallet,
     primitives::{Address, PrimitiveSignature, B256},
-    signers::{ledger::LedgerSigner, local::PrivateKeySigner},
+    signers::{ledger::LedgerSigner, local::PrivateKeySigner, Signer as _},
 };
 use async_trait::async_trait;
 
+use crate::remote_signer::RemoteSigner;
+
 #[derive(Debug)]
 pub enum Signer {
     Local(PrivateKeySigner),
     Ledger(LedgerSigner),
+    Remote(RemoteSigner),
 }
 
 impl Signer {
     pub fn is_ledger(&self) -> bool {
         match self {
-            Self::Local(_) => false,
             Self::Ledger(_) => true,
+            Self::Local(_) | Self::Remote(_) => false,
         }
     }
 }
@@ -26,6 +31,7 @@ impl alloy::signers::Signer<PrimitiveSignature> for Signer {
         match self {
             Self::Local(signer) => signer.address(),
             Self::Ledger(signer) => signer.address(),
+            Self::Remote(signer) => signer.address(),
         }
     }
 
@@ -33,6 +39,7 @@ impl alloy::signers::Signer<PrimitiveSignature> for Signer {
         match self {
             Self::Local(signer) => signer.chain_id(),
             Self::Ledger(signer) => signer.chain_id(),
+            Self::Remote(signer) => signer.chain_id(),
         }
     }
 
@@ -40,6 +47,7 @@ impl alloy::signers::Signer<PrimitiveSignature> for Signer {
         match self {
             Self::Local(signer) => signer.set_chain_id(chain_id),
             Self::Ledger(signer) => signer.set_chain_id(chain_id),
+            Self::Remote(signer) => signer.set_chain_id(chain_id),
         };
     }
 
@@ -47,15 +55,45 @@ impl alloy::signers::Signer<PrimitiveSignature> for Signer {
         match self {
             Self::Local(signer) => signer.sign_hash(hash).await,
             Self::Ledger(signer) => signer.sign_hash(hash).await,
+            Self::Remote(signer) => signer.sign_hash(hash).await,
+        }
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> alloy::signers::Result<PrimitiveSignature> {
+        match self {
+            Self::Local(signer) => signer.sign_message(message).await,
+            Self::Ledger(signer) => signer.sign_message(message).await,
+            Self::Remote(signer) => signer.sign_message(message).await,
+        }
+    }
+
+    async fn sign_dynamic_typed_data(
+        &self,
+        payload: &TypedData,
+    ) -> alloy::signers::Result<PrimitiveSignature> {
+        match self {
+            Self::Local(signer) => signer.sign_dynamic_typed_data(payload).await,
+            Self::Ledger(signer) => signer.sign_dynamic_typed_data(payload).await,
+            Self::Remote(signer) => signer.sign_dynamic_typed_data(payload).await,
         }
     }
 }
 
 impl Signer {
+    /// Signs an EIP-7702 authorization, delegating this signer's account to `auth.address`
+    pub async fn sign_authorization(
+        &self,
+        auth: Authorization,
+    ) -> alloy::signers::Result<SignedAuthorization> {
+        let signature = self.sign_hash(&auth.signature_hash()).await?;
+        Ok(auth.into_signed(signature))
+    }
+
     pub fn to_wallet(self) -> EthereumWallet {
         match self {
             Self::Local(signer) => EthereumWallet::from(signer),
             Self::Ledger(signer) => EthereumWallet::from(signer),
+            Self::Remote(signer) => EthereumWallet::from(signer),
         }
     }
 }
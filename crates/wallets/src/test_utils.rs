@@ -0,0 +1,148 @@
+//! Helpers shared by the tests of different wallet types
+
+use std::{
+    future::Future,
+    net::TcpListener,
+    process::{Child, Command, Stdio},
+    time::Duration,
+};
+
+use alloy::{
+    network::Ethereum,
+    providers::{Provider as _, ProviderBuilder, RootProvider},
+    rpc::client::RpcClient,
+};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncReadExt as _, AsyncWriteExt as _},
+    net::TcpStream,
+};
+use url::Url;
+
+/// How many times to check if anvil is ready, and how long to wait in between
+const ANVIL_STARTUP_ATTEMPTS: usize = 50;
+const ANVIL_STARTUP_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An anvil process listening on a free port, killed once dropped
+pub(crate) struct Anvil {
+    process: Child,
+    url: Url,
+}
+
+impl Anvil {
+    pub(crate) async fn spawn() -> Self {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let process = Command::new("anvil")
+            .args(["--port", &port.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("anvil must be installed to run this test");
+
+        let anvil = Self {
+            process,
+            url: format!("http://127.0.0.1:{port}").parse().unwrap(),
+        };
+
+        for _ in 0..ANVIL_STARTUP_ATTEMPTS {
+            if anvil.provider().get_chain_id().await.is_ok() {
+                return anvil;
+            }
+
+            tokio::time::sleep(ANVIL_STARTUP_INTERVAL).await;
+        }
+
+        panic!("timed out waiting for anvil to start");
+    }
+
+    pub(crate) fn provider(&self) -> RootProvider<Ethereum> {
+        ProviderBuilder::new()
+            .disable_recommended_fillers()
+            .on_http(self.url.clone())
+    }
+
+    pub(crate) fn client(&self) -> RpcClient {
+        RpcClient::new_http(self.url.clone())
+    }
+}
+
+impl Drop for Anvil {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+    }
+}
+
+/// Spawns a minimal JSON-RPC server over HTTP, on a free port
+///
+/// `handler` gets the request's headers, lowercased, and its body. It returns either the
+/// JSON-RPC result, or the HTTP status to fail with
+pub(crate) async fn mock_rpc_server<H, F>(handler: H) -> Url
+where
+    H: Fn(String, Value) -> F + Clone + Send + 'static,
+    F: Future<Output = Result<Value, &'static str>> + Send,
+{
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(handle_connection(stream, handler.clone()));
+        }
+    });
+
+    url.parse().unwrap()
+}
+
+async fn handle_connection<H, F>(mut stream: TcpStream, handler: H)
+where
+    H: Fn(String, Value) -> F,
+    F: Future<Output = Result<Value, &'static str>>,
+{
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await.unwrap();
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+
+    let headers = String::from_utf8_lossy(&buf[..header_end]).to_lowercase();
+    let content_length: usize = headers
+        .lines()
+        .find_map(|l| l.strip_prefix("content-length:"))
+        .map(|v| v.trim().parse().unwrap())
+        .unwrap_or(0);
+
+    while buf.len() < header_end + content_length {
+        let n = stream.read(&mut chunk).await.unwrap();
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let req: Value = serde_json::from_slice(&buf[header_end..]).unwrap();
+    let id = req["id"].clone();
+
+    let (status, body) = match handler(headers, req).await {
+        Ok(result) => (
+            "200 OK",
+            json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        ),
+        Err(status) => (status, json!({})),
+    };
+
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+}
@@ -1,8 +1,19 @@
-use alloy::signers::{
-    ledger::{HDPath, LedgerSigner},
-    local::{coins_bip39::English, MnemonicBuilder},
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
 };
-use ethui_types::Address;
+
+use alloy::{
+    primitives::keccak256,
+    signers::{
+        ledger::{HDPath, LedgerSigner},
+        local::{
+            coins_bip39::{English, Entropy, Mnemonic},
+            MnemonicBuilder,
+        },
+    },
+};
+use ethui_types::{Address, B256};
 use once_cell::sync::Lazy;
 use tokio::sync::Mutex;
 
@@ -11,12 +22,33 @@ use crate::Error;
 
 pub(crate) static HID_MUTEX: Lazy<Mutex<()>> = Lazy::new(Default::default);
 
+/// Word indices issued for each generated mnemonic, and when, keyed by the mnemonic's hash so the
+/// phrase itself isn't kept around
+static ISSUED_CONFIRMATIONS: Lazy<std::sync::Mutex<HashMap<B256, (Vec<usize>, Instant)>>> =
+    Lazy::new(Default::default);
+
+/// How long the user has to confirm a generated mnemonic. Abandoned ones are forgotten after that
+const CONFIRMATION_TTL: Duration = Duration::from_secs(30 * 60);
+
 pub fn derive_addresses(
     mnemonic: &str,
     derivation_path: &str,
     count: u32,
 ) -> Vec<(String, Address)> {
-    let builder = MnemonicBuilder::<English>::default().phrase(mnemonic);
+    derive_addresses_with_passphrase(mnemonic, None, derivation_path, count)
+}
+
+/// Same as `derive_addresses`, but takes into account an optional BIP-39 passphrase
+pub fn derive_addresses_with_passphrase(
+    mnemonic: &str,
+    passphrase: Option<&str>,
+    derivation_path: &str,
+    count: u32,
+) -> Vec<(String, Address)> {
+    let mut builder = MnemonicBuilder::<English>::default().phrase(mnemonic);
+    if let Some(passphrase) = passphrase {
+        builder = builder.password(passphrase);
+    }
 
     (0..count)
         .map(|idx| {
@@ -43,6 +75,82 @@ pub fn validate_mnemonic(mnemonic: &str) -> bool {
         .is_ok()
 }
 
+/// Generates a new random BIP-39 mnemonic with the given number of words
+///
+/// Only 12 and 24-word mnemonics are supported
+pub fn generate_mnemonic(word_count: usize) -> Result<String> {
+    let entropy = match word_count {
+        12 => Entropy::Sixteen(rand::random()),
+        24 => Entropy::ThirtyTwo(rand::random()),
+        _ => return Err(Error::InvalidWordCount(word_count)),
+    };
+
+    Ok(Mnemonic::<English>::new_from_entropy(entropy).to_phrase())
+}
+
+/// Picks the word indices the user will be asked to confirm for a generated mnemonic, and
+/// remembers them so that `confirm_mnemonic_words` only accepts those
+pub fn issue_confirmation_indices(mnemonic: &str) -> Vec<usize> {
+    let indices = pick_confirmation_indices(mnemonic.split_whitespace().count());
+
+    let mut issued = ISSUED_CONFIRMATIONS.lock().unwrap();
+    issued.retain(|_, (_, at)| at.elapsed() < CONFIRMATION_TTL);
+    issued.insert(keccak256(mnemonic), (indices.clone(), Instant::now()));
+
+    indices
+}
+
+/// Picks a few random, distinct word indices that the user will be asked to confirm after
+/// writing down a newly generated mnemonic
+fn pick_confirmation_indices(word_count: usize) -> Vec<usize> {
+    let mut indices: Vec<usize> = rand::seq::index::sample(
+        &mut rand::rng(),
+        word_count,
+        CONFIRMATION_WORDS.min(word_count),
+    )
+    .into_vec();
+    indices.sort();
+    indices
+}
+
+/// Checks that the user confirmed exactly the words issued by `issue_confirmation_indices` for
+/// this mnemonic, each matching the word at that position
+///
+/// The issued indices are kept until `forget_confirmation`, so that a failure to create the
+/// wallet afterwards doesn't force the user to start over
+pub fn confirm_mnemonic_words(mnemonic: &str, words: &[(usize, String)]) -> bool {
+    let issued = ISSUED_CONFIRMATIONS.lock().unwrap();
+    let Some((indices, _)) = issued
+        .get(&keccak256(mnemonic))
+        .filter(|(_, at)| at.elapsed() < CONFIRMATION_TTL)
+    else {
+        return false;
+    };
+
+    let mut submitted: Vec<usize> = words.iter().map(|(idx, _)| *idx).collect();
+    submitted.sort();
+    if submitted != *indices {
+        return false;
+    }
+
+    let expected: Vec<&str> = mnemonic.split_whitespace().collect();
+    words.iter().all(|(idx, word)| {
+        expected
+            .get(*idx)
+            .is_some_and(|w| *w == word.trim().to_lowercase())
+    })
+}
+
+/// Forgets the indices issued for a mnemonic, once its wallet was created
+pub fn forget_confirmation(mnemonic: &str) {
+    ISSUED_CONFIRMATIONS
+        .lock()
+        .unwrap()
+        .remove(&keccak256(mnemonic));
+}
+
+const CONFIRMATION_WORDS: usize = 3;
+
 pub(crate) async fn ledger_derive(path: &str) -> Result<Address> {
     let _guard = HID_MUTEX.lock().await;
 
@@ -66,15 +174,70 @@ pub(crate) async fn ledger_derive_multiple(paths: Vec<String>) -> Result<Vec<(St
     Ok(res)
 }
 
-// TODO: can I enable this test again in the future?
-//#[cfg(test)]
-//mod tests {
-//    use super::*;
-//
-//    #[tokio::test]
-//    async fn detect() {
-//        let addresses = ledger_derive("m/44'/60'/0'/0/0").await;
-//
-//        assert!(addresses.is_ok());
-//    }
-//}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_valid_mnemonics() {
+        for count in [12, 24] {
+            let mnemonic = generate_mnemonic(count).unwrap();
+
+            assert_eq!(mnemonic.split_whitespace().count(), count);
+            assert!(validate_mnemonic(&mnemonic));
+        }
+
+        assert!(generate_mnemonic(13).is_err());
+    }
+
+    #[test]
+    fn confirms_only_issued_mnemonic_words() {
+        let mnemonic = generate_mnemonic(12).unwrap();
+        let words: Vec<&str> = mnemonic.split_whitespace().collect();
+        let answer = |indices: &[usize]| -> Vec<(usize, String)> {
+            indices
+                .iter()
+                .map(|i| (*i, words[*i].to_uppercase()))
+                .collect()
+        };
+
+        // nothing was issued yet
+        assert!(!confirm_mnemonic_words(&mnemonic, &answer(&[0, 1, 2])));
+
+        let issued = issue_confirmation_indices(&mnemonic);
+        let other: Vec<usize> = (0..12).filter(|i| !issued.contains(i)).take(3).collect();
+
+        assert!(!confirm_mnemonic_words(&mnemonic, &answer(&other)));
+        assert!(!confirm_mnemonic_words(&mnemonic, &answer(&issued[..1])));
+        assert!(!confirm_mnemonic_words(
+            &mnemonic,
+            &[
+                (issued[0], "wrong".into()),
+                (issued[1], words[issued[1]].into()),
+                (issued[2], words[issued[2]].into())
+            ]
+        ));
+        assert!(confirm_mnemonic_words(&mnemonic, &answer(&issued)));
+
+        // can't be reused once the wallet was created
+        forget_confirmation(&mnemonic);
+        assert!(!confirm_mnemonic_words(&mnemonic, &answer(&issued)));
+    }
+
+    #[test]
+    fn picks_distinct_confirmation_indices() {
+        let indices = pick_confirmation_indices(12);
+
+        assert_eq!(indices.len(), CONFIRMATION_WORDS);
+        assert!(indices.windows(2).all(|w| w[0] < w[1]));
+        assert!(indices.iter().all(|i| *i < 12));
+    }
+
+    // TODO: can I enable this test again in the future?
+    //#[tokio::test]
+    //async fn detect() {
+    //    let addresses = ledger_derive("m/44'/60'/0'/0/0").await;
+    //
+    //    assert!(addresses.is_ok());
+    //}
+}
@@ -7,7 +7,9 @@ use super::{
     wallets::{HDWallet, Impersonator, JsonKeystoreWallet, LedgerWallet, PlaintextWallet},
     Error, Result,
 };
-use crate::wallets::PrivateKeyWallet;
+use crate::wallets::{
+    MultiKeyWallet, PrivateKeyWallet, RemoteWallet, SafeWallet, SmartAccountWallet, WatchOnlyWallet,
+};
 
 #[async_trait]
 #[enum_dispatch(Wallet)]
@@ -61,6 +63,16 @@ pub enum Wallet {
     Ledger(LedgerWallet),
 
     PrivateKey(PrivateKeyWallet),
+
+    MultiKey(MultiKeyWallet),
+
+    Remote(RemoteWallet),
+
+    Safe(SafeWallet),
+
+    SmartAccount(SmartAccountWallet),
+
+    WatchOnly(WatchOnlyWallet),
 }
 
 impl Wallet {
@@ -74,6 +86,11 @@ impl Wallet {
             "impersonator" => Impersonator::create(params).await?,
             "ledger" => LedgerWallet::create(params).await?,
             "privateKey" => PrivateKeyWallet::create(params).await?,
+            "multiKey" => MultiKeyWallet::create(params).await?,
+            "remote" => RemoteWallet::create(params).await?,
+            "safe" => SafeWallet::create(params).await?,
+            "smartAccount" => SmartAccountWallet::create(params).await?,
+            "watchOnly" => WatchOnlyWallet::create(params).await?,
             _ => return Err(Error::InvalidWalletType(wallet_type.into())),
         };
 
@@ -89,6 +106,24 @@ pub enum WalletType {
     Impersonator,
     Ledger,
     PrivateKey,
+    MultiKey,
+    Remote,
+    Safe,
+    SmartAccount,
+    WatchOnly,
+}
+
+impl WalletType {
+    /// Whether the wallet's signer can sign arbitrary hashes, as EIP-7702 authorizations require
+    ///
+    /// Remote signers only expose message, typed data and transaction signing, and contract
+    /// wallets have no key of their own
+    pub fn can_sign_hash(&self) -> bool {
+        !matches!(
+            self,
+            Self::Remote | Self::Safe | Self::SmartAccount | Self::WatchOnly
+        )
+    }
 }
 
 impl std::fmt::Display for WalletType {
@@ -103,6 +138,11 @@ impl std::fmt::Display for WalletType {
                 WalletType::Impersonator => "impersonator",
                 WalletType::Ledger => "ledger",
                 WalletType::PrivateKey => "privateKey",
+                WalletType::MultiKey => "multiKey",
+                WalletType::Remote => "remote",
+                WalletType::Safe => "safe",
+                WalletType::SmartAccount => "smartAccount",
+                WalletType::WatchOnly => "watchOnly",
             }
         )
     }
@@ -117,6 +157,11 @@ impl From<&Wallet> for WalletType {
             Wallet::Impersonator(_) => Self::Impersonator,
             Wallet::Ledger(_) => Self::Ledger,
             Wallet::PrivateKey(_) => Self::PrivateKey,
+            Wallet::MultiKey(_) => Self::MultiKey,
+            Wallet::Remote(_) => Self::Remote,
+            Wallet::Safe(_) => Self::Safe,
+            Wallet::SmartAccount(_) => Self::SmartAccount,
+            Wallet::WatchOnly(_) => Self::WatchOnly,
         }
     }
 }
@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::Duration};
+use std::{ops::Range, sync::Arc, time::Duration};
 
 use alloy::signers::{
     local::{coins_bip39::English, MnemonicBuilder},
@@ -26,6 +26,15 @@ pub struct HDWallet {
     addresses: Vec<(String, Address)>,
     ciphertext: EncryptedData<String>,
 
+    /// Addresses derived outside of the main derivation path, handed out as per-site burners
+    /// They can sign and are tracked like any other address, but never become the current one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    burners: Vec<(String, Address)>,
+
+    /// Optional BIP-39 passphrase, encrypted with the same password as the mnemonic
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    passphrase: Option<EncryptedData<String>>,
+
     /// The signer is cached inside a `RwLock` so we can have interior mutability
     /// Since JSON keystore signers are time-consuming to decrypt, we can't do it on-the-fly for
     /// every incoming signing request
@@ -38,6 +47,10 @@ pub struct HDWallet {
     #[serde(skip)]
     secret: Arc<RwLock<Option<Mutex<SecretVec<u8>>>>>,
 
+    /// Cached passphrase, if any. Expires alongside `secret`
+    #[serde(skip)]
+    passphrase_secret: Arc<RwLock<Option<Mutex<SecretVec<u8>>>>>,
+
     /// A join handle that will expire the signer after some time
     #[serde(skip)]
     expirer: Arc<RwLock<Option<JoinHandle<()>>>>,
@@ -94,28 +107,33 @@ impl WalletControl for HDWallet {
     async fn get_address(&self, path: &str) -> Result<Address> {
         self.addresses
             .iter()
+            .chain(self.burners.iter())
             .find(|(p, _)| p == path)
             .map(|(_, a)| *a)
             .ok_or(Error::InvalidKey(path.into()))
     }
 
     async fn get_all_addresses(&self) -> Vec<(String, Address)> {
-        self.addresses.clone()
+        self.addresses
+            .iter()
+            .chain(self.burners.iter())
+            .cloned()
+            .collect()
     }
 
     async fn build_signer(&self, chain_id: u32, path: &str) -> Result<Signer> {
-        if !self.addresses.iter().any(|(p, _)| p == path) {
+        if !self
+            .addresses
+            .iter()
+            .chain(self.burners.iter())
+            .any(|(p, _)| p == path)
+        {
             return Err(Error::InvalidKey(path.to_string()));
         }
 
-        self.unlock().await?;
-
-        let secret = self.secret.read().await;
-        let secret = secret.as_ref().unwrap().lock().await;
-
-        let mnemonic = mnemonic_from_secret(&secret);
-        let mut signer = MnemonicBuilder::<English>::default()
-            .phrase(mnemonic.as_str())
+        let mut signer = self
+            .mnemonic_builder()
+            .await?
             .derivation_path(path)?
             .build()?;
 
@@ -127,10 +145,17 @@ impl WalletControl for HDWallet {
 
 impl HDWallet {
     pub async fn from_params(params: HDWalletParams) -> Result<Self> {
-        let addresses =
-            utils::derive_addresses(&params.mnemonic, &params.derivation_path, params.count);
+        let passphrase = params.passphrase.filter(|p| !p.is_empty());
+
+        let addresses = utils::derive_addresses_with_passphrase(
+            &params.mnemonic,
+            passphrase.as_deref(),
+            &params.derivation_path,
+            params.count,
+        );
         let current = addresses.first().unwrap().clone();
         let ciphertext = ethui_crypto::encrypt(&params.mnemonic, &params.password).unwrap();
+        let passphrase = passphrase.map(|p| ethui_crypto::encrypt(&p, &params.password).unwrap());
 
         Ok(Self {
             name: params.name,
@@ -138,12 +163,60 @@ impl HDWallet {
             current,
             count: params.count,
             ciphertext,
+            passphrase,
             addresses,
+            burners: vec![],
             secret: Default::default(),
+            passphrase_secret: Default::default(),
             expirer: Default::default(),
         })
     }
 
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Derives the addresses for a range of indices within the derivation path, without changing
+    /// the list of addresses of the wallet itself
+    pub(crate) async fn derive_range(&self, indices: Range<u32>) -> Result<Vec<(String, Address)>> {
+        let builder = self.mnemonic_builder().await?;
+
+        indices
+            .map(|idx| {
+                let path = format!("{}/{}", self.derivation_path, idx);
+                let address = utils::derive_from_builder_and_path(builder.clone(), &path)?;
+                Ok((path, address))
+            })
+            .collect()
+    }
+
+    /// Derives a burner address at an arbitrary path, without tracking it yet
+    /// Returns the existing address if the path is already tracked
+    pub(crate) async fn derive_burner(&self, path: &str) -> Result<Address> {
+        if let Ok(address) = self.get_address(path).await {
+            return Ok(address);
+        }
+
+        let builder = self.mnemonic_builder().await?;
+        utils::derive_from_builder_and_path(builder, path)
+    }
+
+    /// Starts tracking a burner address. Returns whether it wasn't tracked yet
+    pub(crate) async fn track_burner(&mut self, path: &str, address: Address) -> bool {
+        if self.get_address(path).await.is_ok() {
+            return false;
+        }
+
+        self.burners.push((path.to_string(), address));
+        true
+    }
+
+    /// Stops tracking a burner address. Returns the removed address, if any
+    pub(crate) fn remove_burner(&mut self, path: &str) -> Option<Address> {
+        let idx = self.burners.iter().position(|(p, _)| p == path)?;
+        Some(self.burners.remove(idx).1)
+    }
+
     async fn update_derivation_path(&mut self, derivation_path: String) -> Result<()> {
         self.derivation_path = derivation_path;
 
@@ -166,8 +239,14 @@ impl HDWallet {
         let secret = self.secret.read().await;
         let secret = secret.as_ref().unwrap().lock().await;
         let mnemonic = mnemonic_from_secret(&secret);
-
-        let addresses = utils::derive_addresses(&mnemonic, &self.derivation_path, self.count);
+        let passphrase = self.cached_passphrase().await;
+
+        let addresses = utils::derive_addresses_with_passphrase(
+            &mnemonic,
+            passphrase.as_deref(),
+            &self.derivation_path,
+            self.count,
+        );
         // TODO check if current address is still part of the list, instead of hardcoding a new current
         let current = addresses.first().unwrap().clone();
 
@@ -177,7 +256,7 @@ impl HDWallet {
         Ok(())
     }
 
-    async fn is_unlocked(&self) -> bool {
+    pub(crate) async fn is_unlocked(&self) -> bool {
         let secret = self.secret.read().await;
         secret.is_some()
     }
@@ -206,7 +285,15 @@ impl HDWallet {
 
             // if password was given, and correctly decrypts the keystore
             if let Ok(mnemonic) = ethui_crypto::decrypt(&self.ciphertext, &password) {
-                self.store_secret(mnemonic).await;
+                let passphrase = match self.passphrase {
+                    Some(ref ciphertext) => Some(
+                        ethui_crypto::decrypt(ciphertext, &password)
+                            .map_err(|_| Error::UnlockDialogFailed)?,
+                    ),
+                    None => None,
+                };
+
+                self.store_secret(mnemonic, passphrase).await;
                 return Ok(());
             }
 
@@ -216,32 +303,74 @@ impl HDWallet {
         Err(Error::UnlockDialogFailed)
     }
 
-    async fn store_secret(&self, mnemonic: String) {
-        // acquire both write locks
+    async fn store_secret(&self, mnemonic: String, passphrase: Option<String>) {
+        // acquire all write locks
         let mut expirer_handle = self.expirer.write().await;
         let mut secret_handle = self.secret.write().await;
+        let mut passphrase_handle = self.passphrase_secret.write().await;
 
-        let secret = mnemonic_into_secret(mnemonic);
-
-        *secret_handle = Some(Mutex::new(secret));
+        *secret_handle = Some(Mutex::new(mnemonic_into_secret(mnemonic)));
+        *passphrase_handle = passphrase.map(|p| Mutex::new(mnemonic_into_secret(p)));
 
         // set up cache expiration for 1 minute
-        let clone = Arc::clone(&self.secret);
+        let secret = Arc::clone(&self.secret);
+        let passphrase = Arc::clone(&self.passphrase_secret);
         *expirer_handle = Some(tokio::spawn(async move {
             tokio::time::sleep(Duration::from_secs(60)).await;
-            clone.write().await.take();
+            secret.write().await.take();
+            passphrase.write().await.take();
         }));
     }
+
+    /// Reads the cached passphrase, if the wallet has one and is currently unlocked
+    async fn cached_passphrase(&self) -> Option<String> {
+        let passphrase = self.passphrase_secret.read().await;
+
+        match passphrase.as_ref() {
+            Some(p) => Some(mnemonic_from_secret(&*p.lock().await)),
+            None => None,
+        }
+    }
+
+    /// Unlocks the wallet, and returns a `MnemonicBuilder` ready to derive signers from
+    async fn mnemonic_builder(&self) -> Result<MnemonicBuilder<English>> {
+        self.unlock().await?;
+
+        let secret = self.secret.read().await;
+        let secret = secret.as_ref().unwrap().lock().await;
+
+        let mut builder =
+            MnemonicBuilder::<English>::default().phrase(mnemonic_from_secret(&secret));
+
+        if let Some(passphrase) = self.cached_passphrase().await {
+            builder = builder.password(passphrase);
+        }
+
+        Ok(builder)
+    }
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct HDWalletParams {
-    mnemonic: String,
-    derivation_path: String,
-    password: String,
-    name: String,
-    count: u32,
+    pub(crate) mnemonic: String,
+    pub(crate) derivation_path: String,
+    pub(crate) password: String,
+    pub(crate) name: String,
+    pub(crate) count: u32,
+    #[serde(default)]
+    pub(crate) passphrase: Option<String>,
+}
+
+/// Params for creating an HD wallet from a mnemonic freshly generated by ethui
+///
+/// Includes the words the user was asked to re-type, as proof that the mnemonic was written down
+#[derive(Debug, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedHDWalletParams {
+    #[serde(flatten)]
+    pub(crate) params: HDWalletParams,
+    pub(crate) confirmations: Vec<(usize, String)>,
 }
 
 /// Converts a signer into a SecretVec
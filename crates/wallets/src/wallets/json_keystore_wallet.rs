@@ -1,4 +1,11 @@
-use std::{fs::File, io::BufReader, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 use alloy::{
     primitives::B256,
@@ -17,10 +24,22 @@ use tokio::{
 use crate::{wallet::WalletCreate, Error, Result, Signer, Wallet, WalletControl};
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+#[serde(try_from = "PersistedKeystore")]
 pub struct JsonKeystoreWallet {
     name: String,
     pub file: PathBuf,
 
+    /// Cached address of the keystore
+    /// Keystores created by foundry don't include an `address` field, so the address is derived
+    /// once when importing them, and again whenever the wallet points to a different file
+    address: Address,
+
+    /// The encrypted key, as read from the keystore file when the wallet was loaded
+    /// Used to recognize the same key after the file is moved, when the address isn't in
+    /// plaintext
+    #[serde(skip)]
+    ciphertext: Option<String>,
+
     /// The signer is cached inside a `RwLock` so we can have interior mutability
     /// Since JSON keystore signers are time-consuming to decrypt, we can't do it on-the-fly for
     /// every incoming signing request
@@ -38,10 +57,53 @@ pub struct JsonKeystoreWallet {
     expirer: Arc<RwLock<Option<JoinHandle<()>>>>,
 }
 
+/// A keystore wallet, as persisted
+///
+/// Wallets saved before addresses were cached don't include one. It's read from the keystore
+/// file instead, which must include it in plaintext
+#[derive(serde::Deserialize)]
+struct PersistedKeystore {
+    name: String,
+    file: PathBuf,
+
+    #[serde(default)]
+    address: Option<Address>,
+}
+
+impl TryFrom<PersistedKeystore> for JsonKeystoreWallet {
+    type Error = String;
+
+    fn try_from(persisted: PersistedKeystore) -> std::result::Result<Self, Self::Error> {
+        let address = persisted
+            .address
+            .or_else(|| read_address(&persisted.file))
+            .ok_or_else(|| Error::KeystoreAddress(persisted.file.clone()).to_string())?;
+
+        Ok(Self {
+            name: persisted.name,
+            ciphertext: read_ciphertext(&persisted.file),
+            file: persisted.file,
+            address,
+            secret: Default::default(),
+            expirer: Default::default(),
+        })
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct CreateParams {
+    name: String,
+    file: PathBuf,
+}
+
 #[async_trait]
 impl WalletCreate for JsonKeystoreWallet {
+    /// May prompt the user to unlock the keystore, if the address isn't in plaintext
     async fn create(params: serde_json::Value) -> Result<Wallet> {
-        Ok(Wallet::JsonKeystore(serde_json::from_value(params)?))
+        let params: CreateParams = serde_json::from_value(params)?;
+        Ok(Wallet::JsonKeystore(
+            Self::from_file(params.name, params.file).await?,
+        ))
     }
 }
 
@@ -52,16 +114,29 @@ impl WalletControl for JsonKeystoreWallet {
     }
 
     async fn update(mut self, params: serde_json::Value) -> Result<Wallet> {
-        Ok(Wallet::JsonKeystore(serde_json::from_value(params)?))
+        if let Some(name) = params["name"].as_str() {
+            self.name = name.into();
+        }
+
+        if !params["file"].is_null() {
+            let file: PathBuf = serde_json::from_value(params["file"].clone())?;
+
+            // a moved file still holds the same key, and keeps the unlocked signer. A different
+            // key is a different wallet
+            if file != self.file && !self.holds_same_key(&file) {
+                return Ok(Wallet::JsonKeystore(
+                    Self::from_file(self.name, file).await?,
+                ));
+            }
+
+            self.file = file;
+        }
+
+        Ok(Wallet::JsonKeystore(self))
     }
 
     async fn get_current_address(&self) -> Address {
-        let file = File::open(self.file.clone()).unwrap();
-        let reader = BufReader::new(file);
-        let mut res: serde_json::Value = serde_json::from_reader(reader).unwrap();
-
-        // TODO: this should fail correctly
-        Address::from_str(res["address"].take().as_str().unwrap()).unwrap()
+        self.address
     }
 
     fn get_current_path(&self) -> String {
@@ -94,6 +169,48 @@ impl WalletControl for JsonKeystoreWallet {
 }
 
 impl JsonKeystoreWallet {
+    /// Creates a wallet from an existing keystore file
+    ///
+    /// If the file doesn't include the address in plaintext, the user is asked to unlock it so
+    /// the address can be derived. Either way, the address is cached
+    pub async fn from_file(name: String, file: PathBuf) -> Result<Self> {
+        if !file.is_file() {
+            return Err(Error::KeystoreAddress(file));
+        }
+
+        let address = read_address(&file);
+
+        let mut wallet = Self {
+            name,
+            address: address.unwrap_or_default(),
+            ciphertext: read_ciphertext(&file),
+            file,
+            secret: Default::default(),
+            expirer: Default::default(),
+        };
+
+        if address.is_none() {
+            wallet.unlock().await?;
+
+            let secret = wallet.secret.read().await;
+            let secret = secret.as_ref().unwrap().lock().await;
+            wallet.address = signer_from_secret(&secret).address();
+        }
+
+        Ok(wallet)
+    }
+
+    /// Checks if a keystore file holds the same key as this wallet, without decrypting it
+    ///
+    /// Compares addresses when the file has one in plaintext. Otherwise (e.g.: foundry
+    /// keystores), compares the encrypted key itself
+    fn holds_same_key(&self, file: &Path) -> bool {
+        match read_address(file) {
+            Some(address) => address == self.address,
+            None => self.ciphertext.is_some() && read_ciphertext(file) == self.ciphertext,
+        }
+    }
+
     async fn is_unlocked(&self) -> bool {
         let secret = self.secret.read().await;
         secret.is_some()
@@ -151,6 +268,24 @@ impl JsonKeystoreWallet {
     }
 }
 
+/// Reads the plaintext `address` field of a keystore file, if there is one
+fn read_address(file: &Path) -> Option<Address> {
+    let file = File::open(file).ok()?;
+    let res: serde_json::Value = serde_json::from_reader(BufReader::new(file)).ok()?;
+
+    Address::from_str(res["address"].as_str()?).ok()
+}
+
+/// Reads the encrypted key of a keystore file
+fn read_ciphertext(file: &Path) -> Option<String> {
+    let file = File::open(file).ok()?;
+    let res: serde_json::Value = serde_json::from_reader(BufReader::new(file)).ok()?;
+
+    // the v3 spec names it `crypto`, but some implementations use `Crypto`
+    let crypto = res.get("crypto").or_else(|| res.get("Crypto"))?;
+    crypto["ciphertext"].as_str().map(str::to_owned)
+}
+
 /// Converts a signer into a SecretVec
 fn signer_into_secret(keystore: &LocalSigner<ecdsa::SigningKey>) -> SecretVec<u8> {
     // TODO: test this encoding
@@ -185,4 +320,41 @@ mod tests {
         assert_eq!(signer.address(), recovered_signer.address());
         assert_eq!(signer.credential(), recovered_signer.credential());
     }
+
+    #[test]
+    fn recognizes_moved_keystores() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let keystore = |name: &str, ciphertext: &str| {
+            let file = dir.path().join(name);
+            let json = serde_json::json!({ "crypto": { "ciphertext": ciphertext } });
+            std::fs::write(&file, json.to_string()).unwrap();
+            file
+        };
+
+        let wallet = JsonKeystoreWallet::try_from(PersistedKeystore {
+            name: "deployer".into(),
+            file: keystore("deployer", "aa"),
+            address: Some(Address::with_last_byte(1)),
+        })
+        .unwrap();
+
+        assert!(wallet.holds_same_key(&keystore("renamed", "aa")));
+        assert!(!wallet.holds_same_key(&keystore("other", "bb")));
+        assert!(!wallet.holds_same_key(&dir.path().join("missing")));
+    }
+
+    #[test]
+    fn requires_an_address() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("deployer");
+        std::fs::write(&file, "{}").unwrap();
+
+        let res = JsonKeystoreWallet::try_from(PersistedKeystore {
+            name: "deployer".into(),
+            file,
+            address: None,
+        });
+
+        assert!(res.is_err());
+    }
 }
@@ -2,12 +2,24 @@ mod hd_wallet;
 mod impersonator;
 mod json_keystore_wallet;
 mod ledger;
+mod multi_key;
+mod owner;
 mod plaintext;
 mod private_key;
+mod remote;
+mod safe;
+mod smart_account;
+mod watch_only;
 
-pub use hd_wallet::HDWallet;
+pub use hd_wallet::{GeneratedHDWalletParams, HDWallet};
 pub use impersonator::Impersonator;
 pub use json_keystore_wallet::JsonKeystoreWallet;
 pub use ledger::LedgerWallet;
+pub use multi_key::MultiKeyWallet;
+pub use owner::OwnerKey;
 pub use plaintext::PlaintextWallet;
 pub use private_key::PrivateKeyWallet;
+pub use remote::RemoteWallet;
+pub use safe::SafeWallet;
+pub use smart_account::SmartAccountWallet;
+pub use watch_only::WatchOnlyWallet;
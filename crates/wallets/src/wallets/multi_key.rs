@@ -0,0 +1,415 @@
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use alloy::{
+    primitives::B256,
+    signers::{local::PrivateKeySigner, Signer as _},
+};
+use async_trait::async_trait;
+use ethui_crypto::{self, EncryptedData};
+use ethui_dialogs::{Dialog, DialogMsg};
+use ethui_types::{Address, Json};
+use secrets::SecretVec;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::{Mutex, RwLock},
+    task::JoinHandle,
+};
+
+use crate::{wallet::WalletCreate, Error, Result, Signer, Wallet, WalletControl};
+
+/// Several private keys, encrypted together under a single password
+///
+/// Each key is identified by its label, which is also used as its path.
+/// Adding or removing keys re-encrypts the whole set, so the password is required for both
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiKeyWallet {
+    name: String,
+
+    /// labels and addresses of each key, in the same order as in `ciphertext`
+    keys: Vec<LabeledKey>,
+    ciphertext: EncryptedData<Vec<B256>>,
+
+    #[serde(default)]
+    current: usize,
+
+    /// All decrypted keys, concatenated, cached for a short while after unlocking
+    ///
+    /// The additional Mutex within is there because `SecretVec` is not Send
+    #[serde(skip)]
+    secret: Arc<RwLock<Option<Mutex<SecretVec<u8>>>>>,
+
+    /// A join handle that will expire the cached keys after some time
+    #[serde(skip)]
+    expirer: Arc<RwLock<Option<JoinHandle<()>>>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LabeledKey {
+    pub label: String,
+    pub address: Address,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NewKey {
+    label: String,
+    private_key: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiKeyWalletParams {
+    name: String,
+    password: String,
+    keys: Vec<NewKey>,
+}
+
+#[async_trait]
+impl WalletCreate for MultiKeyWallet {
+    async fn create(params: Json) -> Result<Wallet> {
+        Ok(Wallet::MultiKey(Self::from_params(
+            serde_json::from_value(params)?,
+        )?))
+    }
+}
+
+#[async_trait]
+impl WalletControl for MultiKeyWallet {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Besides renaming, accepts `addKeys` and `removeKeys` (a list of labels), which require
+    /// the wallet's `password`
+    async fn update(mut self, params: Json) -> Result<Wallet> {
+        if let Some(name) = params["name"].as_str() {
+            self.name = name.into();
+        }
+
+        let add: Vec<NewKey> = match params.get("addKeys") {
+            Some(keys) if !keys.is_null() => serde_json::from_value(keys.clone())?,
+            _ => vec![],
+        };
+
+        let remove: Vec<String> = match params.get("removeKeys") {
+            Some(labels) if !labels.is_null() => serde_json::from_value(labels.clone())?,
+            _ => vec![],
+        };
+
+        if !add.is_empty() || !remove.is_empty() {
+            let password = params["password"].as_str().ok_or(Error::InvalidPassword)?;
+            self.change_keys(add, remove, password)?;
+        }
+
+        Ok(Wallet::MultiKey(self))
+    }
+
+    async fn get_current_address(&self) -> Address {
+        self.keys[self.current].address
+    }
+
+    fn get_current_path(&self) -> String {
+        self.keys[self.current].label.clone()
+    }
+
+    async fn set_current_path(&mut self, path: String) -> Result<()> {
+        self.current = self.position(&path)?;
+        Ok(())
+    }
+
+    async fn get_all_addresses(&self) -> Vec<(String, Address)> {
+        self.keys
+            .iter()
+            .map(|k| (k.label.clone(), k.address))
+            .collect()
+    }
+
+    async fn get_address(&self, path: &str) -> Result<Address> {
+        Ok(self.keys[self.position(path)?].address)
+    }
+
+    async fn build_signer(&self, chain_id: u32, path: &str) -> Result<Signer> {
+        let idx = self.position(path)?;
+        self.unlock().await?;
+
+        let secret = self.secret.read().await;
+        let secret = secret.as_ref().unwrap().lock().await;
+
+        let mut signer = signer_from_secret(&secret, idx);
+        // TODO: use u64 for chain id
+        signer.set_chain_id(Some(chain_id.into()));
+        Ok(Signer::Local(signer))
+    }
+}
+
+impl MultiKeyWallet {
+    pub fn from_params(params: MultiKeyWalletParams) -> Result<Self> {
+        let mut keys = vec![];
+        let mut secrets = vec![];
+
+        for new in params.keys {
+            let signer = parse_key(&new.private_key)?;
+            ensure_unique(&keys, &new.label, signer.address())?;
+
+            keys.push(LabeledKey {
+                label: new.label,
+                address: signer.address(),
+            });
+            secrets.push(signer.to_bytes());
+        }
+
+        if keys.is_empty() {
+            return Err(Error::NoAddresses);
+        }
+
+        Ok(Self {
+            name: params.name,
+            keys,
+            ciphertext: encrypt_keys(&secrets, &params.password)?,
+            current: 0,
+            secret: Default::default(),
+            expirer: Default::default(),
+        })
+    }
+
+    fn position(&self, label: &str) -> Result<usize> {
+        self.keys
+            .iter()
+            .position(|k| k.label == label)
+            .ok_or_else(|| Error::InvalidKey(label.into()))
+    }
+
+    /// Removes and adds keys, and re-encrypts the resulting set
+    /// The current key is kept, unless it was removed
+    fn change_keys(&mut self, add: Vec<NewKey>, remove: Vec<String>, password: &str) -> Result<()> {
+        let mut secrets = ethui_crypto::decrypt(&self.ciphertext, password)
+            .map_err(|_| Error::InvalidPassword)?;
+        let current = self.get_current_path();
+
+        for label in remove {
+            let idx = self.position(&label)?;
+            self.keys.remove(idx);
+            secrets.remove(idx);
+        }
+
+        for new in add {
+            let signer = parse_key(&new.private_key)?;
+            ensure_unique(&self.keys, &new.label, signer.address())?;
+
+            self.keys.push(LabeledKey {
+                label: new.label,
+                address: signer.address(),
+            });
+            secrets.push(signer.to_bytes());
+        }
+
+        if self.keys.is_empty() {
+            return Err(Error::NoAddresses);
+        }
+
+        self.ciphertext = encrypt_keys(&secrets, password)?;
+        self.current = self.position(&current).unwrap_or(0);
+
+        // clones of this wallet share the cache, which now holds a different set of keys
+        self.secret = Default::default();
+        self.expirer = Default::default();
+
+        Ok(())
+    }
+
+    async fn is_unlocked(&self) -> bool {
+        let secret = self.secret.read().await;
+        secret.is_some()
+    }
+
+    async fn unlock(&self) -> Result<()> {
+        // if we already have the keys, then we're good
+        if self.is_unlocked().await {
+            return Ok(());
+        }
+
+        // open the dialog
+        let dialog = Dialog::new("wallet-unlock", serde_json::to_value(self).unwrap());
+        dialog.open().await?;
+
+        // attempt to receive a password at most 3 times
+        for _ in 0..3 {
+            let password = if let Some(DialogMsg::Data(payload)) = dialog.recv().await {
+                let password = payload["password"].clone();
+                password
+                    .as_str()
+                    .ok_or(Error::UnlockDialogRejected)?
+                    .to_string()
+            } else {
+                return Err(Error::UnlockDialogRejected);
+            };
+
+            // if password was given, and correctly decrypts the keys
+            if let Ok(secrets) = ethui_crypto::decrypt(&self.ciphertext, &password) {
+                self.store_secret(&secrets).await;
+                return Ok(());
+            }
+
+            dialog.send("failed", None).await?;
+        }
+
+        Err(Error::UnlockDialogFailed)
+    }
+
+    async fn store_secret(&self, secrets: &[B256]) {
+        // acquire both write locks
+        let mut expirer_handle = self.expirer.write().await;
+        let mut secret_handle = self.secret.write().await;
+
+        *secret_handle = Some(Mutex::new(keys_into_secret(secrets)));
+
+        // set up cache expiration for 1 minute
+        let clone = Arc::clone(&self.secret);
+        *expirer_handle = Some(tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            clone.write().await.take();
+        }));
+    }
+}
+
+fn parse_key(private_key: &str) -> Result<PrivateKeySigner> {
+    let key = private_key.strip_prefix("0x").unwrap_or(private_key);
+    Ok(PrivateKeySigner::from_str(key)?)
+}
+
+fn ensure_unique(keys: &[LabeledKey], label: &str, address: Address) -> Result<()> {
+    if keys
+        .iter()
+        .any(|k| k.label == label || k.address == address)
+    {
+        return Err(Error::DuplicateKey(label.into()));
+    }
+
+    Ok(())
+}
+
+fn encrypt_keys(secrets: &[B256], password: &str) -> Result<EncryptedData<Vec<B256>>> {
+    ethui_crypto::encrypt(&secrets.to_vec(), password).map_err(|_| Error::Encryption)
+}
+
+/// Concatenates all keys into a single SecretVec
+fn keys_into_secret(secrets: &[B256]) -> SecretVec<u8> {
+    let bytes: Vec<u8> = secrets.iter().flat_map(|k| k.0).collect();
+
+    SecretVec::new(bytes.len(), |s| s.copy_from_slice(&bytes))
+}
+
+/// Builds a signer for the key at the given index of a SecretVec
+fn signer_from_secret(secret: &SecretVec<u8>, idx: usize) -> PrivateKeySigner {
+    let bytes = secret.borrow();
+    let key = B256::from_slice(&bytes[idx * 32..(idx + 1) * 32]);
+
+    PrivateKeySigner::from_bytes(&key).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const PASSWORD: &str = "password";
+
+    fn new_key(label: &str, signer: &PrivateKeySigner) -> Json {
+        json!({ "label": label, "privateKey": signer.to_bytes().to_string() })
+    }
+
+    async fn wallet(signers: &[PrivateKeySigner]) -> MultiKeyWallet {
+        let keys: Vec<_> = signers
+            .iter()
+            .enumerate()
+            .map(|(i, s)| new_key(&format!("key{}", i), s))
+            .collect();
+
+        let params = json!({ "name": "team", "password": PASSWORD, "keys": keys });
+
+        match MultiKeyWallet::create(params).await.unwrap() {
+            Wallet::MultiKey(wallet) => wallet,
+            _ => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn labels_are_paths() {
+        let signers = [PrivateKeySigner::random(), PrivateKeySigner::random()];
+        let mut wallet = wallet(&signers).await;
+
+        assert_eq!(
+            wallet.get_all_addresses().await,
+            vec![
+                ("key0".to_string(), signers[0].address()),
+                ("key1".to_string(), signers[1].address())
+            ]
+        );
+
+        wallet.set_current_path("key1".into()).await.unwrap();
+        assert_eq!(wallet.get_current_address().await, signers[1].address());
+        assert!(wallet.set_current_path("nope".into()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn adds_and_removes_keys() {
+        let signers = [PrivateKeySigner::random(), PrivateKeySigner::random()];
+        let mut wallet = wallet(&signers).await;
+        wallet.set_current_path("key1".into()).await.unwrap();
+
+        let extra = PrivateKeySigner::random();
+        let updated = wallet
+            .update(json!({
+                "password": PASSWORD,
+                "removeKeys": ["key0"],
+                "addKeys": [new_key("extra", &extra)],
+            }))
+            .await
+            .unwrap();
+
+        let Wallet::MultiKey(wallet) = updated else {
+            unreachable!()
+        };
+
+        // the current key is kept, even though its index changed
+        assert_eq!(wallet.get_current_path(), "key1");
+
+        let secrets = ethui_crypto::decrypt(&wallet.ciphertext, PASSWORD).unwrap();
+        assert_eq!(secrets, vec![signers[1].to_bytes(), extra.to_bytes()]);
+
+        let secret = keys_into_secret(&secrets);
+        assert_eq!(signer_from_secret(&secret, 1).address(), extra.address());
+    }
+
+    #[tokio::test]
+    async fn changing_keys_requires_password() {
+        let signers = [PrivateKeySigner::random()];
+        let wallet = wallet(&signers).await;
+
+        let res = wallet
+            .clone()
+            .update(json!({ "password": "wrong", "removeKeys": ["key0"] }))
+            .await;
+        assert!(matches!(res, Err(Error::InvalidPassword)));
+
+        let res = wallet.update(json!({ "removeKeys": ["key0"] })).await;
+        assert!(matches!(res, Err(Error::InvalidPassword)));
+    }
+
+    #[tokio::test]
+    async fn rejects_duplicate_keys() {
+        let signer = PrivateKeySigner::random();
+        let params = json!({
+            "name": "team",
+            "password": PASSWORD,
+            "keys": [new_key("a", &signer), new_key("b", &signer)],
+        });
+
+        assert!(matches!(
+            MultiKeyWallet::create(params).await,
+            Err(Error::DuplicateKey(_))
+        ));
+    }
+}
@@ -0,0 +1,27 @@
+use ethui_types::GlobalState;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result, Signer, WalletControl, Wallets};
+
+/// Reference to a key held in another ethui wallet, by wallet name and path within that wallet
+///
+/// Used by contract wallets (Safes, smart accounts) to find the EOAs that control them
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnerKey {
+    pub(crate) wallet: String,
+    pub(crate) path: String,
+}
+
+impl OwnerKey {
+    /// Must not be called while holding a lock on `Wallets`
+    pub(crate) async fn build_signer(&self, chain_id: u32) -> Result<Signer> {
+        let wallet = Wallets::read()
+            .await
+            .get(&self.wallet)
+            .cloned()
+            .ok_or_else(|| Error::InvalidWalletName(self.wallet.clone()))?;
+
+        wallet.build_signer(chain_id, &self.path).await
+    }
+}
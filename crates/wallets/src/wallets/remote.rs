@@ -0,0 +1,257 @@
+use std::{sync::Arc, time::Duration};
+
+use alloy::signers::Signer as _;
+use async_trait::async_trait;
+use ethui_crypto::EncryptedData;
+use ethui_dialogs::{Dialog, DialogMsg};
+use ethui_types::{Address, Json};
+use secrets::SecretVec;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::{Mutex, RwLock},
+    task::JoinHandle,
+};
+use url::Url;
+
+use super::private_key::private_key_into_secret;
+use crate::{
+    remote_signer::RemoteSigner, wallet::WalletCreate, Error, Result, Signer, Wallet, WalletControl,
+};
+
+/// A wallet whose keys live in a remote signing service (web3signer, Clef, ...)
+///
+/// Each account is identified by its address, which is also used as its path
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteWallet {
+    name: String,
+    url: Url,
+
+    /// optional bearer token sent with every request to the signer, encrypted with a password
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auth_token: Option<EncryptedData<String>>,
+
+    addresses: Vec<Address>,
+
+    #[serde(default)]
+    current: usize,
+
+    /// The decrypted token, cached for a short while after being unlocked
+    ///
+    /// Kept as a `SecretVec`, the same way private keys are
+    #[serde(skip)]
+    token: Arc<RwLock<Option<Mutex<SecretVec<u8>>>>>,
+
+    /// A join handle that will expire the token after some time
+    #[serde(skip)]
+    expirer: Arc<RwLock<Option<JoinHandle<()>>>>,
+}
+
+#[async_trait]
+impl WalletCreate for RemoteWallet {
+    async fn create(params: Json) -> Result<Wallet> {
+        let params = serde_json::from_value(params)?;
+        Ok(Wallet::Remote(Self::from_params(params).await?))
+    }
+}
+
+#[async_trait]
+impl WalletControl for RemoteWallet {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn update(mut self, params: Json) -> Result<Wallet> {
+        if let Some(name) = params["name"].as_str() {
+            self.name = name.into();
+        }
+
+        if !params["url"].is_null() {
+            self.url = serde_json::from_value(params["url"].clone())?;
+        }
+
+        if params.get("authToken").is_some() {
+            let token: Option<String> = serde_json::from_value(params["authToken"].clone())?;
+            self.auth_token = encrypt_token(token, params["password"].as_str())?;
+            self.token.write().await.take();
+        }
+
+        if !params["addresses"].is_null() {
+            self.addresses = serde_json::from_value(params["addresses"].clone())?;
+        } else if params["refresh"].as_bool().unwrap_or(false) {
+            let token = self.unlock().await?;
+            self.addresses = RemoteSigner::accounts(self.url.clone(), token.as_deref()).await?;
+        }
+
+        if self.addresses.is_empty() {
+            return Err(Error::RemoteSigner("signer has no accounts".into()));
+        }
+
+        if self.current >= self.addresses.len() {
+            self.current = 0;
+        }
+
+        Ok(Wallet::Remote(self))
+    }
+
+    async fn get_current_address(&self) -> Address {
+        self.addresses[self.current]
+    }
+
+    fn get_current_path(&self) -> String {
+        self.addresses[self.current].to_string()
+    }
+
+    async fn set_current_path(&mut self, path: String) -> Result<()> {
+        self.current = self
+            .addresses
+            .iter()
+            .position(|a| a.to_string() == path)
+            .ok_or(Error::InvalidKey(path))?;
+
+        Ok(())
+    }
+
+    async fn get_all_addresses(&self) -> Vec<(String, Address)> {
+        self.addresses.iter().map(|a| (a.to_string(), *a)).collect()
+    }
+
+    async fn get_address(&self, path: &str) -> Result<Address> {
+        self.addresses
+            .iter()
+            .find(|a| a.to_string() == path)
+            .copied()
+            .ok_or(Error::InvalidKey(path.into()))
+    }
+
+    async fn build_signer(&self, chain_id: u32, path: &str) -> Result<Signer> {
+        let address = self.get_address(path).await?;
+        let token = self.unlock().await?;
+        let signer = RemoteSigner::new(self.url.clone(), token.as_deref(), address)?
+            .with_chain_id(Some(chain_id.into()));
+
+        Ok(Signer::Remote(signer))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteWalletParams {
+    name: String,
+    url: Url,
+
+    #[serde(default)]
+    auth_token: Option<String>,
+
+    /// encrypts `auth_token`. Required if one is given
+    #[serde(default)]
+    password: Option<String>,
+
+    /// if not given, all accounts exposed by the signer are used
+    #[serde(default)]
+    addresses: Option<Vec<Address>>,
+}
+
+/// Never prints the auth token or its password
+impl std::fmt::Debug for RemoteWalletParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteWalletParams")
+            .field("name", &self.name)
+            .field("url", &self.url)
+            .field(
+                "auth_token",
+                &self.auth_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("addresses", &self.addresses)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RemoteWallet {
+    pub async fn from_params(params: RemoteWalletParams) -> Result<Self> {
+        let addresses = match params.addresses {
+            Some(addresses) => addresses,
+            None => {
+                RemoteSigner::accounts(params.url.clone(), params.auth_token.as_deref()).await?
+            }
+        };
+
+        if addresses.is_empty() {
+            return Err(Error::RemoteSigner("signer has no accounts".into()));
+        }
+
+        Ok(Self {
+            name: params.name,
+            url: params.url,
+            auth_token: encrypt_token(params.auth_token, params.password.as_deref())?,
+            addresses,
+            current: 0,
+            token: Default::default(),
+            expirer: Default::default(),
+        })
+    }
+
+    /// Decrypts the auth token, if there is one, asking the user for its password
+    async fn unlock(&self) -> Result<Option<String>> {
+        let Some(auth_token) = &self.auth_token else {
+            return Ok(None);
+        };
+
+        if let Some(secret) = self.token.read().await.as_ref() {
+            let token = String::from_utf8(secret.lock().await.borrow().to_vec())
+                .map_err(|_| Error::UnlockDialogFailed)?;
+            return Ok(Some(token));
+        }
+
+        let dialog = Dialog::new("wallet-unlock", serde_json::to_value(self).unwrap());
+        dialog.open().await?;
+
+        // attempt to receive a password at most 3 times
+        for _ in 0..3 {
+            let password = if let Some(DialogMsg::Data(payload)) = dialog.recv().await {
+                payload["password"]
+                    .as_str()
+                    .ok_or(Error::UnlockDialogRejected)?
+                    .to_string()
+            } else {
+                return Err(Error::UnlockDialogRejected);
+            };
+
+            if let Ok(token) = ethui_crypto::decrypt(auth_token, &password) {
+                self.store_token(token.clone()).await;
+                return Ok(Some(token));
+            }
+
+            dialog.send("failed", None).await?;
+        }
+
+        Err(Error::UnlockDialogFailed)
+    }
+
+    async fn store_token(&self, token: String) {
+        let mut expirer_handle = self.expirer.write().await;
+        *self.token.write().await = Some(Mutex::new(private_key_into_secret(token)));
+
+        // set up cache expiration for 1 minute
+        let clone = Arc::clone(&self.token);
+        *expirer_handle = Some(tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            clone.write().await.take();
+        }));
+    }
+}
+
+fn encrypt_token(
+    token: Option<String>,
+    password: Option<&str>,
+) -> Result<Option<EncryptedData<String>>> {
+    match (token, password) {
+        (None, _) => Ok(None),
+        (Some(token), Some(password)) => Ok(Some(
+            ethui_crypto::encrypt(&token, password).map_err(|_| Error::InvalidPassword)?,
+        )),
+        (Some(_), None) => Err(Error::RemoteSigner(
+            "a password is required to store the auth token".into(),
+        )),
+    }
+}
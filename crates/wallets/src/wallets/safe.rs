@@ -0,0 +1,588 @@
+use std::future::Future;
+
+use alloy::{
+    dyn_abi::TypedData,
+    network::Ethereum,
+    primitives::{Bytes, PrimitiveSignature, U256},
+    providers::{PendingTransactionBuilder, Provider, ProviderBuilder, RootProvider},
+    rpc::{client::RpcClient, types::TransactionRequest},
+    signers::Signer as _,
+    sol_types::SolCall as _,
+};
+use async_trait::async_trait;
+use ethui_abis::ISafe;
+use ethui_types::{Address, Json, Network};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::warn;
+
+use super::OwnerKey;
+use crate::{wallet::WalletCreate, Error, Result, Signer, Wallet, WalletControl};
+
+/// A Safe multisig, controlled by owner keys held in other ethui wallets
+///
+/// The Safe itself can't sign anything. Transactions sent from it are wrapped in a `SafeTx`,
+/// signed by each available local owner, and submitted through `execTransaction`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeWallet {
+    name: String,
+    address: Address,
+    owners: Vec<OwnerKey>,
+}
+
+#[async_trait]
+impl WalletCreate for SafeWallet {
+    async fn create(params: Json) -> Result<Wallet> {
+        Ok(Wallet::Safe(serde_json::from_value(params)?))
+    }
+}
+
+#[async_trait]
+impl WalletControl for SafeWallet {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn update(mut self, params: Json) -> Result<Wallet> {
+        if let Some(name) = params["name"].as_str() {
+            self.name = name.into();
+        }
+
+        if !params["address"].is_null() {
+            self.address = serde_json::from_value(params["address"].clone())?;
+        }
+
+        if !params["owners"].is_null() {
+            self.owners = serde_json::from_value(params["owners"].clone())?;
+        }
+
+        Ok(Wallet::Safe(self))
+    }
+
+    async fn get_current_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_current_path(&self) -> String {
+        self.address.to_string()
+    }
+
+    async fn set_current_path(&mut self, _path: String) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_all_addresses(&self) -> Vec<(String, Address)> {
+        vec![(self.address.to_string(), self.address)]
+    }
+
+    async fn get_address(&self, _path: &str) -> Result<Address> {
+        Ok(self.address)
+    }
+
+    async fn build_signer(&self, _chain_id: u32, _path: &str) -> Result<Signer> {
+        Err(Error::WalletCantSign)
+    }
+}
+
+impl SafeWallet {
+    /// Executes a transaction through the Safe
+    ///
+    /// Collects EIP-712 signatures from local owners until the on-chain threshold is reached,
+    /// and submits `execTransaction` using the first of those owners as the sender.
+    /// Must not be called while holding a lock on `Wallets`, since owner wallets are looked up
+    /// from there
+    pub async fn execute(
+        &self,
+        network: &Network,
+        request: &TransactionRequest,
+    ) -> Result<PendingTransactionBuilder<Ethereum>> {
+        let chain_id = network.chain_id();
+        let owners = self.owners.iter().map(|owner| async move {
+            owner.build_signer(chain_id).await.inspect_err(
+                |e| warn!(wallet = %owner.wallet, path = %owner.path, "skipping safe owner: {}", e),
+            )
+        });
+
+        execute_with(
+            self.address,
+            chain_id,
+            network.rpc_client()?,
+            request,
+            owners,
+        )
+        .await
+    }
+}
+
+/// Signs a `SafeTx` with the given owners and submits it
+///
+/// Owner signers are only built as needed, so that no more wallets are unlocked than the
+/// threshold requires
+async fn execute_with<F>(
+    safe: Address,
+    chain_id: u32,
+    client: RpcClient,
+    request: &TransactionRequest,
+    owners: impl IntoIterator<Item = F>,
+) -> Result<PendingTransactionBuilder<Ethereum>>
+where
+    F: Future<Output = Result<Signer>>,
+{
+    let state = SafeState::read(safe, RootProvider::<Ethereum>::new(client.clone())).await?;
+
+    let to = request
+        .to
+        .and_then(|kind| kind.to().copied())
+        .ok_or(Error::MissingRecipient)?;
+    let value = request.value.unwrap_or_default();
+    let data = request.input.input().cloned().unwrap_or_default();
+
+    let typed_data = safe_tx_typed_data(chain_id, safe, to, value, &data, state.nonce)?;
+    let (signatures, executor) = collect_signatures(safe, &state, &typed_data, owners).await?;
+
+    let provider = ProviderBuilder::new()
+        .wallet(executor.to_wallet())
+        .on_client(client);
+
+    let call = exec_transaction_call(to, value, data, signatures);
+    let tx = TransactionRequest::default()
+        .to(safe)
+        .input(Bytes::from(call.abi_encode()).into());
+
+    Ok(provider.send_transaction(tx).await?)
+}
+
+/// Signs the `SafeTx` with owners until the threshold is reached
+///
+/// Returns the signatures, and the first signer, which will submit the transaction
+async fn collect_signatures<F>(
+    safe: Address,
+    state: &SafeState,
+    typed_data: &TypedData,
+    owners: impl IntoIterator<Item = F>,
+) -> Result<(Vec<(Address, PrimitiveSignature)>, Signer)>
+where
+    F: Future<Output = Result<Signer>>,
+{
+    let mut signatures = vec![];
+    let mut executor = None;
+
+    for owner in owners {
+        if U256::from(signatures.len()) >= state.threshold {
+            break;
+        }
+
+        let Ok(signer) = owner.await else {
+            continue;
+        };
+
+        let address = signer.address();
+        if !state.owners.contains(&address) {
+            warn!(?address, ?safe, "wallet is not an owner of this safe");
+            continue;
+        }
+
+        signatures.push((address, signer.sign_dynamic_typed_data(typed_data).await?));
+        executor.get_or_insert(signer);
+    }
+
+    match executor {
+        Some(executor) if U256::from(signatures.len()) >= state.threshold => {
+            Ok((signatures, executor))
+        }
+        _ => Err(Error::SafeThresholdNotMet {
+            signatures: signatures.len(),
+            threshold: state.threshold.saturating_to(),
+        }),
+    }
+}
+
+/// On-chain state of a Safe, needed to execute a transaction through it
+#[derive(Debug, PartialEq)]
+struct SafeState {
+    /// the nonce the next `SafeTx` must use
+    nonce: U256,
+    threshold: U256,
+    owners: Vec<Address>,
+}
+
+impl SafeState {
+    async fn read<P: Provider>(safe: Address, provider: P) -> Result<Self> {
+        let safe = ISafe::new(safe, provider);
+
+        Ok(Self {
+            nonce: safe.nonce().call().await?.nonce,
+            threshold: safe.getThreshold().call().await?.threshold,
+            owners: safe.getOwners().call().await?.owners,
+        })
+    }
+}
+
+/// The `execTransaction` call for a plain call with no gas refunds, matching
+/// `safe_tx_typed_data`
+fn exec_transaction_call(
+    to: Address,
+    value: U256,
+    data: Bytes,
+    signatures: Vec<(Address, PrimitiveSignature)>,
+) -> ISafe::execTransactionCall {
+    ISafe::execTransactionCall {
+        to,
+        value,
+        data,
+        operation: 0,
+        safeTxGas: U256::ZERO,
+        baseGas: U256::ZERO,
+        gasPrice: U256::ZERO,
+        gasToken: Address::ZERO,
+        refundReceiver: Address::ZERO,
+        signatures: pack_signatures(signatures),
+    }
+}
+
+/// Builds the EIP-712 payload for a `SafeTx`, as defined by Safe v1.3+
+///
+/// Only plain calls are supported, with no gas refunds
+fn safe_tx_typed_data(
+    chain_id: u32,
+    safe: Address,
+    to: Address,
+    value: U256,
+    data: &Bytes,
+    nonce: U256,
+) -> Result<TypedData> {
+    Ok(serde_json::from_value(json!({
+        "types": {
+            "EIP712Domain": [
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" },
+            ],
+            "SafeTx": [
+                { "name": "to", "type": "address" },
+                { "name": "value", "type": "uint256" },
+                { "name": "data", "type": "bytes" },
+                { "name": "operation", "type": "uint8" },
+                { "name": "safeTxGas", "type": "uint256" },
+                { "name": "baseGas", "type": "uint256" },
+                { "name": "gasPrice", "type": "uint256" },
+                { "name": "gasToken", "type": "address" },
+                { "name": "refundReceiver", "type": "address" },
+                { "name": "nonce", "type": "uint256" },
+            ],
+        },
+        "primaryType": "SafeTx",
+        "domain": {
+            "chainId": chain_id,
+            "verifyingContract": safe,
+        },
+        "message": {
+            "to": to,
+            "value": value,
+            "data": data,
+            "operation": 0,
+            "safeTxGas": 0,
+            "baseGas": 0,
+            "gasPrice": 0,
+            "gasToken": Address::ZERO,
+            "refundReceiver": Address::ZERO,
+            "nonce": nonce,
+        },
+    }))?)
+}
+
+/// Safe expects signatures concatenated in ascending order of owner address
+fn pack_signatures(mut signatures: Vec<(Address, PrimitiveSignature)>) -> Bytes {
+    signatures.sort_by_key(|(address, _)| *address);
+
+    signatures
+        .into_iter()
+        .flat_map(|(_, signature)| signature.as_bytes())
+        .collect::<Vec<_>>()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::address,
+        signers::{local::PrivateKeySigner, Signer as _},
+        sol,
+        sol_types::{eip712_domain, SolStruct as _},
+    };
+
+    use super::*;
+
+    sol! {
+        struct SafeTx {
+            address to;
+            uint256 value;
+            bytes data;
+            uint8 operation;
+            uint256 safeTxGas;
+            uint256 baseGas;
+            uint256 gasPrice;
+            address gasToken;
+            address refundReceiver;
+            uint256 nonce;
+        }
+    }
+
+    #[test]
+    fn typed_data_matches_safe_tx_hash() {
+        let safe = address!("0x5FbDB2315678afecb367f032d93F642f64180aa3");
+        let to = address!("0x000000000000000000000000000000000000dEaD");
+        let data = Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+
+        let typed_data =
+            safe_tx_typed_data(31337, safe, to, U256::from(42), &data, U256::from(7)).unwrap();
+
+        let expected = SafeTx {
+            to,
+            value: U256::from(42),
+            data,
+            operation: 0,
+            safeTxGas: U256::ZERO,
+            baseGas: U256::ZERO,
+            gasPrice: U256::ZERO,
+            gasToken: Address::ZERO,
+            refundReceiver: Address::ZERO,
+            nonce: U256::from(7),
+        }
+        .eip712_signing_hash(&eip712_domain! {
+            chain_id: 31337,
+            verifying_contract: safe,
+        });
+
+        assert_eq!(typed_data.eip712_signing_hash().unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn packs_signatures_sorted_by_owner() {
+        let hash = alloy::primitives::B256::repeat_byte(1);
+        let mut owners: Vec<_> = (0..3).map(|_| PrivateKeySigner::random()).collect();
+
+        let mut signatures = vec![];
+        for owner in owners.iter() {
+            signatures.push((owner.address(), owner.sign_hash(&hash).await.unwrap()));
+        }
+
+        let packed = pack_signatures(signatures);
+        owners.sort_by_key(|o| o.address());
+
+        assert_eq!(packed.len(), 65 * 3);
+        for (i, owner) in owners.iter().enumerate() {
+            let signature = PrimitiveSignature::from_raw(&packed[i * 65..(i + 1) * 65]).unwrap();
+            assert_eq!(
+                signature.recover_address_from_prehash(&hash).unwrap(),
+                owner.address()
+            );
+        }
+    }
+
+    #[test]
+    fn encodes_exec_transaction_call() {
+        let to = address!("0x000000000000000000000000000000000000dEaD");
+        let owner = address!("0x0000000000000000000000000000000000000001");
+        let mut raw = [0x11; 65];
+        raw[64] = 0x1b;
+        let signature = PrimitiveSignature::from_raw(&raw).unwrap();
+
+        let call = exec_transaction_call(
+            to,
+            U256::from(42),
+            Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]),
+            vec![(owner, signature)],
+        );
+
+        // hand-encoded, following the ABI of Safe's `execTransaction`
+        let word = |hex: &str| format!("{hex:0>64}");
+        let expected = [
+            "6a761202".to_string(),
+            word("dead"),
+            word("2a"),
+            word("140"), // offset of `data`
+            word("0"),   // operation: call
+            word("0"),
+            word("0"),
+            word("0"),
+            word("0"),
+            word("0"),
+            word("180"), // offset of `signatures`
+            word("4"),
+            format!("{:0<64}", "deadbeef"),
+            word("41"),
+            format!(
+                "{:0<192}",
+                signature.as_bytes().map(|b| format!("{b:02x}")).concat()
+            ),
+        ]
+        .concat();
+
+        assert_eq!(
+            alloy::hex::encode(call.abi_encode()),
+            expected,
+            "execTransaction calldata doesn't match the Safe ABI"
+        );
+    }
+
+    #[tokio::test]
+    async fn signs_with_the_onchain_nonce() {
+        use alloy::{sol_types::SolValue as _, transports::mock::Asserter};
+
+        let safe = address!("0x5FbDB2315678afecb367f032d93F642f64180aa3");
+        let owners = vec![
+            address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8"),
+            address!("0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC"),
+        ];
+
+        // answers to `nonce()`, `getThreshold()` and `getOwners()`, in that order
+        let asserter = Asserter::new();
+        asserter.push_success(&Bytes::from(U256::from(5).abi_encode()));
+        asserter.push_success(&Bytes::from(U256::from(2).abi_encode()));
+        asserter.push_success(&Bytes::from(owners.abi_encode()));
+
+        let provider = ProviderBuilder::new().on_mocked_client(asserter);
+        let state = SafeState::read(safe, provider).await.unwrap();
+
+        assert_eq!(
+            state,
+            SafeState {
+                nonce: U256::from(5),
+                threshold: U256::from(2),
+                owners,
+            }
+        );
+
+        let to = address!("0x000000000000000000000000000000000000dEaD");
+        let typed_data =
+            safe_tx_typed_data(31337, safe, to, U256::ZERO, &Bytes::new(), state.nonce).unwrap();
+        assert_eq!(typed_data.message["nonce"], json!("0x5"));
+    }
+
+    #[tokio::test]
+    async fn signs_with_owners_until_the_threshold() {
+        let safe = address!("0x5FbDB2315678afecb367f032d93F642f64180aa3");
+        let owners: Vec<_> = (0..3).map(|_| PrivateKeySigner::random()).collect();
+        let outsider = PrivateKeySigner::random();
+        let state = SafeState {
+            nonce: U256::from(5),
+            threshold: U256::from(2),
+            owners: owners.iter().map(|o| o.address()).collect(),
+        };
+
+        let to = address!("0x000000000000000000000000000000000000dEaD");
+        let typed_data =
+            safe_tx_typed_data(31337, safe, to, U256::ZERO, &Bytes::new(), state.nonce).unwrap();
+
+        // the outsider is skipped, and the third owner isn't needed
+        let signers = [&outsider, &owners[0], &owners[1], &owners[2]]
+            .map(|signer| async move { Ok(Signer::Local(signer.clone())) });
+        let (signatures, executor) = collect_signatures(safe, &state, &typed_data, signers)
+            .await
+            .unwrap();
+
+        assert_eq!(executor.address(), owners[0].address());
+        assert_eq!(signatures.len(), 2);
+
+        let hash = typed_data.eip712_signing_hash().unwrap();
+        for ((address, signature), owner) in signatures.iter().zip(&owners) {
+            assert_eq!(*address, owner.address());
+            assert_eq!(
+                signature.recover_address_from_prehash(&hash).unwrap(),
+                owner.address()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn fails_below_the_threshold() {
+        let safe = address!("0x5FbDB2315678afecb367f032d93F642f64180aa3");
+        let owner = PrivateKeySigner::random();
+        let state = SafeState {
+            nonce: U256::ZERO,
+            threshold: U256::from(2),
+            owners: vec![owner.address()],
+        };
+
+        let typed_data =
+            safe_tx_typed_data(31337, safe, safe, U256::ZERO, &Bytes::new(), state.nonce).unwrap();
+        let signers = [async { Ok(Signer::Local(owner)) }];
+
+        assert!(matches!(
+            collect_signatures(safe, &state, &typed_data, signers).await,
+            Err(Error::SafeThresholdNotMet {
+                signatures: 1,
+                threshold: 2
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn executes_through_a_safe_on_anvil() {
+        use alloy::{
+            consensus::Transaction as _, hex, providers::ext::AnvilApi as _, sol_types::SolCall,
+        };
+
+        use crate::test_utils::Anvil;
+
+        let anvil = Anvil::spawn().await;
+        let provider = anvil.provider();
+
+        // anvil's first default account
+        let owner: PrivateKeySigner =
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+                .parse()
+                .unwrap();
+
+        // a stand-in for a Safe: `getOwners()` returns `[owner]`, and any other call returns 1,
+        // i.e. a nonce and threshold of 1, and a successful `execTransaction`
+        let safe = address!("0x5FbDB2315678afecb367f032d93F642f64180aa3");
+        let code = [
+            hex!("60003560e01c63a0e67e2b14601957600160005260206000f3").as_slice(),
+            hex!("5b6020600052600160205273").as_slice(),
+            owner.address().as_slice(),
+            hex!("60405260606000f3").as_slice(),
+        ]
+        .concat();
+        provider.anvil_set_code(safe, code.into()).await.unwrap();
+
+        let to = address!("0x000000000000000000000000000000000000dEaD");
+        let data = Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+        let request = TransactionRequest::default()
+            .to(to)
+            .value(U256::from(42))
+            .input(data.clone().into());
+
+        let signers = [async { Ok(Signer::Local(owner.clone())) }];
+        let receipt = execute_with(safe, 31337, anvil.client(), &request, signers)
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+        assert!(receipt.status());
+        assert_eq!(receipt.from, owner.address());
+
+        let tx = provider
+            .get_transaction_by_hash(receipt.transaction_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tx.to(), Some(safe));
+
+        let call = ISafe::execTransactionCall::abi_decode(tx.input(), true).unwrap();
+        assert_eq!((call.to, call.value, call.data), (to, U256::from(42), data));
+
+        // signed over the nonce read from the safe
+        let hash = safe_tx_typed_data(31337, safe, to, U256::from(42), &call.data, U256::from(1))
+            .unwrap()
+            .eip712_signing_hash()
+            .unwrap();
+        let signature = PrimitiveSignature::from_raw(&call.signatures).unwrap();
+        assert_eq!(
+            signature.recover_address_from_prehash(&hash).unwrap(),
+            owner.address()
+        );
+    }
+}
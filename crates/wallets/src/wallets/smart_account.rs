@@ -0,0 +1,546 @@
+use std::time::Duration;
+
+use alloy::{
+    primitives::{address, bytes, keccak256, Bytes, PrimitiveSignature, B256, U256},
+    providers::Provider as _,
+    rpc::{
+        client::RpcClient,
+        types::{eth::erc4337::PackedUserOperation, TransactionRequest},
+    },
+    signers::Signer as _,
+    sol_types::{SolCall as _, SolValue as _},
+};
+use async_trait::async_trait;
+use ethui_abis::{IEntryPoint, ISimpleAccount};
+use ethui_types::{Address, Json, Network};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use url::Url;
+
+use super::OwnerKey;
+use crate::{wallet::WalletCreate, Error, Result, Signer, Wallet, WalletControl};
+
+/// EntryPoint v0.7, deployed at the same address on every chain
+const DEFAULT_ENTRY_POINT: Address = address!("0x0000000071727De22E5E9d8BAf0edAc6f37da032");
+
+/// Signature with the right shape for ECDSA recovery, used only for gas estimation
+const DUMMY_SIGNATURE: Bytes = bytes!("fffffffffffffffffffffffffffffff0000000000000000000000000000000007aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa1c");
+
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// An ERC-4337 smart account (SimpleAccount-compatible), controlled by an EOA held in another
+/// ethui wallet
+///
+/// Transactions sent from it become UserOperations, signed by the owner and submitted to a
+/// bundler
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartAccountWallet {
+    name: String,
+    address: Address,
+    owner: OwnerKey,
+    bundler_url: Url,
+
+    #[serde(default = "default_entry_point")]
+    entry_point: Address,
+
+    /// used to deploy the account with its first UserOperation, if it doesn't exist yet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    factory: Option<Address>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    factory_data: Option<Bytes>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GasEstimation {
+    pre_verification_gas: U256,
+    verification_gas_limit: U256,
+    call_gas_limit: U256,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserOperationReceipt {
+    success: bool,
+    receipt: TransactionReceipt,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionReceipt {
+    transaction_hash: B256,
+}
+
+#[async_trait]
+impl WalletCreate for SmartAccountWallet {
+    async fn create(params: Json) -> Result<Wallet> {
+        Ok(Wallet::SmartAccount(serde_json::from_value(params)?))
+    }
+}
+
+#[async_trait]
+impl WalletControl for SmartAccountWallet {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn update(mut self, params: Json) -> Result<Wallet> {
+        if let Some(name) = params["name"].as_str() {
+            self.name = name.into();
+        }
+
+        if !params["owner"].is_null() {
+            self.owner = serde_json::from_value(params["owner"].clone())?;
+        }
+
+        if !params["bundlerUrl"].is_null() {
+            self.bundler_url = serde_json::from_value(params["bundlerUrl"].clone())?;
+        }
+
+        if !params["entryPoint"].is_null() {
+            self.entry_point = serde_json::from_value(params["entryPoint"].clone())?;
+        }
+
+        // these define the account itself. A different account needs a new wallet
+        let address: Option<Address> = serde_json::from_value(params["address"].clone())?;
+        if address.is_some_and(|address| address != self.address) {
+            return Err(Error::ImmutableField("address".into()));
+        }
+
+        let factory: Option<Address> = serde_json::from_value(params["factory"].clone())?;
+        if factory.is_some_and(|factory| Some(factory) != self.factory) {
+            return Err(Error::ImmutableField("factory".into()));
+        }
+
+        let factory_data: Option<Bytes> = serde_json::from_value(params["factoryData"].clone())?;
+        if factory_data.is_some_and(|data| Some(data) != self.factory_data) {
+            return Err(Error::ImmutableField("factoryData".into()));
+        }
+
+        Ok(Wallet::SmartAccount(self))
+    }
+
+    async fn get_current_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_current_path(&self) -> String {
+        self.address.to_string()
+    }
+
+    async fn set_current_path(&mut self, _path: String) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_all_addresses(&self) -> Vec<(String, Address)> {
+        vec![(self.address.to_string(), self.address)]
+    }
+
+    async fn get_address(&self, _path: &str) -> Result<Address> {
+        Ok(self.address)
+    }
+
+    async fn build_signer(&self, _chain_id: u32, _path: &str) -> Result<Signer> {
+        Err(Error::WalletCantSign)
+    }
+}
+
+impl SmartAccountWallet {
+    /// Sends a transaction as a UserOperation
+    ///
+    /// Returns the UserOperation hash as soon as the bundler accepts it. Inclusion is tracked in
+    /// the background, since bundlers may take a while to include it.
+    /// Must not be called while holding a lock on `Wallets`, since the owner is looked up from
+    /// there
+    pub async fn execute(&self, network: &Network, request: &TransactionRequest) -> Result<B256> {
+        let chain_id = network.chain_id();
+        let bundler = RpcClient::new_http(self.bundler_url.clone());
+
+        let op = self.build_user_operation(network, request).await?;
+        let signer = self.owner.build_signer(chain_id).await?;
+
+        let hash = submit(&bundler, op, self.entry_point, chain_id, &signer).await?;
+        tokio::spawn(track_inclusion(bundler, hash));
+
+        Ok(hash)
+    }
+
+    /// Waits for the bundler to include a UserOperation, and returns the hash of the transaction
+    /// that included it
+    pub async fn transaction_hash(&self, user_operation_hash: B256) -> Result<B256> {
+        let bundler = RpcClient::new_http(self.bundler_url.clone());
+        let receipt = wait_for_receipt(&bundler, user_operation_hash).await?;
+
+        Ok(receipt.receipt.transaction_hash)
+    }
+
+    async fn build_user_operation(
+        &self,
+        network: &Network,
+        request: &TransactionRequest,
+    ) -> Result<PackedUserOperation> {
+        let provider = network.get_provider()?;
+
+        let nonce = IEntryPoint::new(self.entry_point, provider.clone())
+            .getNonce(self.address, Default::default())
+            .call()
+            .await?
+            .nonce;
+
+        let dest = request
+            .to
+            .and_then(|kind| kind.to().copied())
+            .ok_or(Error::MissingRecipient)?;
+        let call_data = ISimpleAccount::executeCall {
+            dest,
+            value: request.value.unwrap_or_default(),
+            func: request.input.input().cloned().unwrap_or_default(),
+        }
+        .abi_encode();
+
+        // only include the factory if the account isn't deployed yet
+        let deployed = !provider.get_code_at(self.address).await?.is_empty();
+        let (factory, factory_data) = if deployed {
+            (None, None)
+        } else {
+            (self.factory, self.factory_data.clone())
+        };
+
+        let gas_price = provider.get_gas_price().await?;
+        let max_priority_fee_per_gas = provider.get_max_priority_fee_per_gas().await?;
+
+        Ok(PackedUserOperation {
+            sender: self.address,
+            nonce,
+            factory,
+            factory_data,
+            call_data: call_data.into(),
+            call_gas_limit: U256::ZERO,
+            verification_gas_limit: U256::ZERO,
+            pre_verification_gas: U256::ZERO,
+            max_fee_per_gas: U256::from(gas_price * 2 + max_priority_fee_per_gas),
+            max_priority_fee_per_gas: U256::from(max_priority_fee_per_gas),
+            paymaster: None,
+            paymaster_verification_gas_limit: None,
+            paymaster_post_op_gas_limit: None,
+            paymaster_data: None,
+            signature: DUMMY_SIGNATURE,
+        })
+    }
+}
+
+/// Estimates gas for a UserOperation, signs it and submits it to the bundler
+///
+/// Returns the UserOperation hash
+async fn submit<S>(
+    bundler: &RpcClient,
+    mut op: PackedUserOperation,
+    entry_point: Address,
+    chain_id: u32,
+    signer: &S,
+) -> Result<B256>
+where
+    S: alloy::signers::Signer<PrimitiveSignature> + Sync,
+{
+    let gas: GasEstimation = bundler
+        .request("eth_estimateUserOperationGas", (op.clone(), entry_point))
+        .await
+        .map_err(|e| Error::Bundler(e.to_string()))?;
+    op.pre_verification_gas = gas.pre_verification_gas;
+    op.verification_gas_limit = gas.verification_gas_limit;
+    op.call_gas_limit = gas.call_gas_limit;
+
+    let hash = user_operation_hash(&op, entry_point, chain_id);
+    op.signature = signer
+        .sign_message(hash.as_slice())
+        .await?
+        .as_bytes()
+        .into();
+
+    bundler
+        .request("eth_sendUserOperation", (op, entry_point))
+        .await
+        .map_err(|e| Error::Bundler(e.to_string()))
+}
+
+async fn track_inclusion(bundler: RpcClient, hash: B256) {
+    match wait_for_receipt(&bundler, hash).await {
+        Ok(UserOperationReceipt {
+            success: true,
+            receipt,
+        }) => info!(?hash, tx_hash = ?receipt.transaction_hash, "user operation included"),
+        Ok(_) => warn!(?hash, "user operation reverted"),
+        Err(e) => warn!(?hash, "failed to track user operation: {}", e),
+    }
+}
+
+async fn wait_for_receipt(bundler: &RpcClient, hash: B256) -> Result<UserOperationReceipt> {
+    let start = std::time::Instant::now();
+
+    while start.elapsed() < RECEIPT_TIMEOUT {
+        let receipt: Option<UserOperationReceipt> = bundler
+            .request("eth_getUserOperationReceipt", (hash,))
+            .await
+            .map_err(|e| Error::Bundler(e.to_string()))?;
+
+        if let Some(receipt) = receipt {
+            return Ok(receipt);
+        }
+
+        tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+    }
+
+    Err(Error::UserOperationTimeout(hash))
+}
+
+/// Computes the hash signed by the account owner, as done by `EntryPoint.getUserOpHash` in v0.7
+fn user_operation_hash(op: &PackedUserOperation, entry_point: Address, chain_id: u32) -> B256 {
+    let init_code = match (op.factory, &op.factory_data) {
+        (Some(factory), data) => [
+            factory.as_slice(),
+            data.as_ref().map(|d| &d[..]).unwrap_or_default(),
+        ]
+        .concat(),
+        (None, _) => vec![],
+    };
+
+    let packed = (
+        op.sender,
+        op.nonce,
+        keccak256(init_code),
+        keccak256(&op.call_data),
+        pack_u128s(op.verification_gas_limit, op.call_gas_limit),
+        op.pre_verification_gas,
+        pack_u128s(op.max_priority_fee_per_gas, op.max_fee_per_gas),
+        // paymasters are not supported
+        keccak256(b""),
+    )
+        .abi_encode_params();
+
+    keccak256((keccak256(packed), entry_point, U256::from(chain_id)).abi_encode_params())
+}
+
+/// Packs two values into a single word, as done for `accountGasLimits` and `gasFees`
+fn pack_u128s(high: U256, low: U256) -> B256 {
+    B256::from(((high << 128) | (low & U256::from(u128::MAX))).to_be_bytes::<32>())
+}
+
+const fn default_entry_point() -> Address {
+    DEFAULT_ENTRY_POINT
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use alloy::signers::local::PrivateKeySigner;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::test_utils::mock_rpc_server;
+
+    const USER_OP_HASH: B256 = B256::repeat_byte(0xaa);
+    const BUNDLE_TX_HASH: B256 = B256::repeat_byte(0xbb);
+
+    fn user_operation() -> PackedUserOperation {
+        PackedUserOperation {
+            sender: address!("0x1306b01bC3e4AD202612D3843387e94737673F53"),
+            nonce: U256::from(7),
+            factory: Some(address!("0x91E60e0613810449d098b0b5Ec8b51A0FE8c8985")),
+            factory_data: Some(bytes!(
+                "5fbfb9cf000000000000000000000000000000000000000000000000000000000000002a"
+            )),
+            call_data: bytes!("b61d27f6"),
+            call_gas_limit: U256::from(100_000),
+            verification_gas_limit: U256::from(200_000),
+            pre_verification_gas: U256::from(50_000),
+            max_fee_per_gas: U256::from(3_000_000_000u64),
+            max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+            paymaster: None,
+            paymaster_verification_gas_limit: None,
+            paymaster_post_op_gas_limit: None,
+            paymaster_data: None,
+            signature: DUMMY_SIGNATURE,
+        }
+    }
+
+    /// Spawns a minimal bundler, recording every request it gets
+    async fn mock_bundler() -> (RpcClient, Arc<Mutex<Vec<Value>>>) {
+        let requests: Arc<Mutex<Vec<Value>>> = Default::default();
+
+        let recorded = requests.clone();
+        let url = mock_rpc_server(move |_, req| {
+            let recorded = recorded.clone();
+            async move {
+                let result = match req["method"].as_str().unwrap() {
+                    "eth_estimateUserOperationGas" => json!({
+                        "preVerificationGas": "0xc350",
+                        "verificationGasLimit": "0x30d40",
+                        "callGasLimit": "0x186a0",
+                    }),
+                    "eth_sendUserOperation" => json!(USER_OP_HASH),
+                    "eth_getUserOperationReceipt" => json!({
+                        "success": true,
+                        "receipt": { "transactionHash": BUNDLE_TX_HASH },
+                    }),
+                    method => panic!("unexpected method {method}"),
+                };
+                recorded.lock().unwrap().push(req);
+
+                Ok(result)
+            }
+        })
+        .await;
+
+        (RpcClient::new_http(url), requests)
+    }
+
+    #[test]
+    fn matches_entry_point_v07_user_op_hash() {
+        // computed independently, following `UserOperationLib.encode` and
+        // `EntryPoint.getUserOpHash` from the v0.7 reference implementation
+        assert_eq!(
+            user_operation_hash(&user_operation(), DEFAULT_ENTRY_POINT, 11155111),
+            alloy::primitives::b256!(
+                "0x20b16d5f82ef12baebe95e82870dbb70bcd282470e69421c369358d57e714652"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn submits_signed_user_operations_to_the_bundler() {
+        let (bundler, requests) = mock_bundler().await;
+        let owner = PrivateKeySigner::random();
+
+        let op = PackedUserOperation {
+            call_gas_limit: U256::ZERO,
+            verification_gas_limit: U256::ZERO,
+            pre_verification_gas: U256::ZERO,
+            ..user_operation()
+        };
+
+        let hash = submit(&bundler, op, DEFAULT_ENTRY_POINT, 11155111, &owner)
+            .await
+            .unwrap();
+        assert_eq!(hash, USER_OP_HASH);
+
+        // doesn't wait for inclusion
+        let requests = requests.lock().unwrap();
+        let methods: Vec<_> = requests.iter().map(|r| r["method"].clone()).collect();
+        assert_eq!(
+            methods,
+            vec![
+                json!("eth_estimateUserOperationGas"),
+                json!("eth_sendUserOperation"),
+            ]
+        );
+
+        // the submitted operation carries the estimated gas, and is signed by the owner over
+        // its v0.7 hash
+        let sent: PackedUserOperation =
+            serde_json::from_value(requests[1]["params"][0].clone()).unwrap();
+        assert_eq!(sent.call_gas_limit, U256::from(100_000));
+        assert_eq!(sent.verification_gas_limit, U256::from(200_000));
+        assert_eq!(sent.pre_verification_gas, U256::from(50_000));
+        assert_eq!(requests[1]["params"][1], json!(DEFAULT_ENTRY_POINT));
+
+        let signature = PrimitiveSignature::try_from(&sent.signature[..]).unwrap();
+        let hash = user_operation_hash(&sent, DEFAULT_ENTRY_POINT, 11155111);
+        assert_eq!(
+            signature.recover_address_from_msg(hash.as_slice()).unwrap(),
+            owner.address()
+        );
+        assert_eq!(
+            hash,
+            user_operation_hash(&user_operation(), DEFAULT_ENTRY_POINT, 11155111)
+        );
+    }
+
+    #[tokio::test]
+    async fn waits_for_the_bundle_transaction() {
+        let (bundler, requests) = mock_bundler().await;
+
+        let receipt = wait_for_receipt(&bundler, USER_OP_HASH).await.unwrap();
+
+        assert!(receipt.success);
+        assert_eq!(receipt.receipt.transaction_hash, BUNDLE_TX_HASH);
+        assert_eq!(requests.lock().unwrap()[0]["params"], json!([USER_OP_HASH]));
+    }
+
+    #[tokio::test]
+    async fn rejects_changes_to_the_account() {
+        let wallet: SmartAccountWallet = serde_json::from_value(json!({
+            "name": "account",
+            "address": "0x1306b01bC3e4AD202612D3843387e94737673F53",
+            "owner": { "wallet": "test", "path": "m/44'/60'/0'/0/0" },
+            "bundlerUrl": "http://localhost:4337",
+            "factory": "0x91E60e0613810449d098b0b5Ec8b51A0FE8c8985",
+        }))
+        .unwrap();
+
+        // resubmitting the same values is fine
+        let same = json!({
+            "name": "renamed",
+            "address": "0x1306b01bC3e4AD202612D3843387e94737673F53",
+            "factory": "0x91E60e0613810449d098b0b5Ec8b51A0FE8c8985",
+        });
+        assert!(wallet.clone().update(same).await.is_ok());
+
+        for params in [
+            json!({ "address": "0x000000000000000000000000000000000000dEaD" }),
+            json!({ "factory": "0x000000000000000000000000000000000000dEaD" }),
+            json!({ "factoryData": "0x1234" }),
+        ] {
+            assert!(matches!(
+                wallet.clone().update(params).await,
+                Err(Error::ImmutableField(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn packs_gas_values_into_a_word() {
+        let packed = pack_u128s(U256::from(1), U256::from(2));
+
+        assert_eq!(packed[15], 1);
+        assert_eq!(packed[31], 2);
+        assert!(packed[..15].iter().all(|b| *b == 0));
+        assert!(packed[16..31].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn user_operation_hash_depends_on_chain_and_entry_point() {
+        let op = PackedUserOperation {
+            sender: address!("0x000000000000000000000000000000000000dEaD"),
+            nonce: U256::ZERO,
+            factory: None,
+            factory_data: None,
+            call_data: Bytes::new(),
+            call_gas_limit: U256::from(100_000),
+            verification_gas_limit: U256::from(100_000),
+            pre_verification_gas: U256::from(50_000),
+            max_fee_per_gas: U256::from(2_000_000_000u64),
+            max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+            paymaster: None,
+            paymaster_verification_gas_limit: None,
+            paymaster_post_op_gas_limit: None,
+            paymaster_data: None,
+            signature: DUMMY_SIGNATURE,
+        };
+
+        let hash = user_operation_hash(&op, DEFAULT_ENTRY_POINT, 1);
+
+        assert_ne!(hash, user_operation_hash(&op, DEFAULT_ENTRY_POINT, 31337));
+        assert_ne!(hash, user_operation_hash(&op, Address::ZERO, 1));
+
+        // the signature is not part of the hash
+        let signed = PackedUserOperation {
+            signature: Bytes::from(vec![1; 65]),
+            ..op
+        };
+        assert_eq!(hash, user_operation_hash(&signed, DEFAULT_ENTRY_POINT, 1));
+    }
+}
@@ -0,0 +1,144 @@
+use async_trait::async_trait;
+use ethui_types::{Address, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::{wallet::WalletCreate, Error, Result, Signer, Wallet, WalletControl};
+
+/// A list of addresses that are tracked, but can't be used to sign anything
+///
+/// Unlike `Impersonator`, this works on any network, since it never sends transactions.
+/// Each account is identified by its address, which is also used as its path
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchOnlyWallet {
+    name: String,
+    addresses: Vec<Address>,
+
+    #[serde(default)]
+    current: usize,
+}
+
+#[async_trait]
+impl WalletCreate for WatchOnlyWallet {
+    async fn create(params: Json) -> Result<Wallet> {
+        let wallet: Self = serde_json::from_value(params)?;
+        Ok(Wallet::WatchOnly(wallet.validated()?))
+    }
+}
+
+#[async_trait]
+impl WalletControl for WatchOnlyWallet {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn update(mut self, params: Json) -> Result<Wallet> {
+        if let Some(name) = params["name"].as_str() {
+            self.name = name.into();
+        }
+
+        if !params["addresses"].is_null() {
+            self.addresses = serde_json::from_value(params["addresses"].clone())?;
+        }
+
+        Ok(Wallet::WatchOnly(self.validated()?))
+    }
+
+    async fn get_current_address(&self) -> Address {
+        self.addresses[self.current]
+    }
+
+    fn get_current_path(&self) -> String {
+        self.addresses[self.current].to_string()
+    }
+
+    async fn set_current_path(&mut self, path: String) -> Result<()> {
+        self.current = self
+            .addresses
+            .iter()
+            .position(|a| a.to_string() == path)
+            .ok_or(Error::InvalidKey(path))?;
+
+        Ok(())
+    }
+
+    async fn get_all_addresses(&self) -> Vec<(String, Address)> {
+        self.addresses.iter().map(|a| (a.to_string(), *a)).collect()
+    }
+
+    async fn get_address(&self, path: &str) -> Result<Address> {
+        self.addresses
+            .iter()
+            .find(|a| a.to_string() == path)
+            .copied()
+            .ok_or(Error::InvalidKey(path.into()))
+    }
+
+    async fn build_signer(&self, _chain_id: u32, _path: &str) -> Result<Signer> {
+        Err(Error::WatchOnly(self.name.clone()))
+    }
+}
+
+impl WatchOnlyWallet {
+    fn validated(mut self) -> Result<Self> {
+        if self.addresses.is_empty() {
+            return Err(Error::NoAddresses);
+        }
+
+        // duplicates would make paths ambiguous
+        let mut seen = std::collections::HashSet::new();
+        self.addresses.retain(|a| seen.insert(*a));
+
+        if self.current >= self.addresses.len() {
+            self.current = 0;
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::address;
+    use serde_json::json;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn refuses_to_sign() {
+        let wallet = WatchOnlyWallet::create(json!({
+            "name": "vitalik",
+            "addresses": [address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045")],
+        }))
+        .await
+        .unwrap();
+
+        let path = wallet.get_current_path();
+        let err = wallet.build_signer(1, &path).await.unwrap_err();
+
+        assert!(matches!(err, Error::WatchOnly(name) if name == "vitalik"));
+    }
+
+    #[tokio::test]
+    async fn dedups_addresses() {
+        let addr = address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+        let wallet = WatchOnlyWallet::create(json!({
+            "name": "watch",
+            "addresses": [addr, addr],
+        }))
+        .await
+        .unwrap();
+
+        assert_eq!(
+            wallet.get_all_addresses().await,
+            vec![(addr.to_string(), addr)]
+        );
+    }
+
+    #[tokio::test]
+    async fn requires_an_address() {
+        let res = WatchOnlyWallet::create(json!({ "name": "empty", "addresses": [] })).await;
+
+        assert!(matches!(res, Err(Error::NoAddresses)));
+    }
+}
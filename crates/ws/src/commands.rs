@@ -1,8 +1,12 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, net::SocketAddr};
 
-use ethui_types::GlobalState;
+use ethui_types::{Address, GlobalState, B256};
 
-use crate::{Peer, Peers};
+use crate::{
+    pairing::{Pairing, Pairings},
+    stats::PeerStats,
+    Peer, Peers, WsResult,
+};
 
 #[tauri::command]
 pub async fn ws_peers_by_domain() -> HashMap<String, Vec<Peer>> {
@@ -13,3 +17,44 @@ pub async fn ws_peers_by_domain() -> HashMap<String, Vec<Peer>> {
 pub async fn ws_peer_count() -> usize {
     Peers::read().await.count()
 }
+
+#[tauri::command]
+pub async fn ws_peer_stats(socket: SocketAddr) -> WsResult<PeerStats> {
+    Peers::read().await.stats(socket)
+}
+
+#[tauri::command]
+pub async fn ws_disconnect_peer(socket: SocketAddr) -> WsResult<()> {
+    Peers::read().await.disconnect(socket)
+}
+
+#[tauri::command]
+pub async fn ws_disconnect_domain(domain: String) {
+    Peers::read().await.disconnect_domain(&domain)
+}
+
+/// Sends a synthetic `accountsChanged` event to a single peer, for debugging
+#[tauri::command]
+pub async fn ws_peer_emit_accounts_changed(
+    socket: SocketAddr,
+    accounts: Vec<Address>,
+) -> WsResult<()> {
+    Peers::read().await.emit_accounts_changed(socket, accounts)
+}
+
+/// Sends a synthetic `chainChanged` event to a single peer, for debugging
+#[tauri::command]
+pub async fn ws_peer_emit_chain_changed(socket: SocketAddr, chain_id: u64) -> WsResult<()> {
+    Peers::read().await.emit_chain_changed(socket, chain_id)
+}
+
+#[tauri::command]
+pub async fn ws_list_pairings() -> Vec<Pairing> {
+    Pairings::read().await.list().clone()
+}
+
+/// Revokes a client's token. Already open connections are unaffected
+#[tauri::command]
+pub async fn ws_revoke_pairing(token_hash: B256) -> WsResult<()> {
+    Pairings::write().await.revoke(token_hash)
+}
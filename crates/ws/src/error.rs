@@ -8,6 +8,33 @@ pub enum WsError {
 
     #[error(transparent)]
     TauriError(#[from] tauri::Error),
+
+    #[error(transparent)]
+    Dialog(#[from] ethui_dialogs::Error),
+
+    #[error("serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("pairing rejected by user")]
+    PairingRejected,
+
+    #[error("too many pairing requests, try again later")]
+    PairingThrottled,
+
+    #[error("pairings were already initialized")]
+    AlreadyInitialized,
+
+    #[error("peer not found: {0}")]
+    PeerNotFound(std::net::SocketAddr),
 }
 
 pub type WsResult<T> = std::result::Result<T, WsError>;
+
+impl serde::Serialize for WsError {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_ref())
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use async_trait::async_trait;
 use ethui_args::Args;
 use ethui_broadcast::InternalMsg;
@@ -5,15 +7,19 @@ use ethui_types::GlobalState;
 use once_cell::sync::Lazy;
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-use crate::{peers::Peers, server::server_loop};
+use crate::{pairing, peers::Peers, server::server_loop, WsResult};
 
 static PEERS: Lazy<RwLock<Peers>> = Lazy::new(Default::default);
 
-pub async fn init(args: &Args) {
+pub async fn init(args: &Args, pairings: PathBuf) -> WsResult<()> {
     let port = args.ws_port;
 
+    pairing::init(pairings)?;
+
     tokio::spawn(async move { server_loop(port).await });
     tokio::spawn(async { receiver().await });
+
+    Ok(())
 }
 
 #[async_trait]
@@ -35,15 +41,24 @@ async fn receiver() -> ! {
             use InternalMsg::*;
 
             match msg {
-                ChainChanged(internal_id, domain, affinity) => {
+                ChainChanged(internal_id, domain, tab_id, affinity) => {
                     Peers::read()
                         .await
-                        .broadcast_chain_changed(internal_id, domain, affinity)
+                        .broadcast_chain_changed(internal_id, domain, tab_id, affinity)
                         .await
                 }
-                AccountsChanged(accounts) => {
-                    Peers::read().await.broadcast_accounts_changed(accounts)
+                AccountsChanged(accounts, domain) => {
+                    let peers = Peers::read().await;
+                    peers
+                        .broadcast_accounts_changed(accounts.clone(), domain.clone())
+                        .await;
+                    peers
+                        .broadcast_session_accounts_changed(&accounts, domain)
+                        .await;
                 }
+                SessionChanged(domain, session_scopes) => Peers::read()
+                    .await
+                    .broadcast_session_changed(&domain, session_scopes),
                 _ => {}
             }
         }
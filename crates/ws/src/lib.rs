@@ -1,8 +1,10 @@
 pub mod commands;
 mod error;
 mod init;
+pub mod pairing;
 pub mod peers;
 mod server;
+pub mod stats;
 
 pub use error::{WsError, WsResult};
 pub use init::init;
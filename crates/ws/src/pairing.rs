@@ -0,0 +1,230 @@
+//! Pairing of WebSocket clients
+//!
+//! Any local process can connect to the server, and claim to be any domain. So clients (usually
+//! the browser extension) must first request pairing, via `ethui_requestPairing`. Once the user
+//! approves it, a token is issued, which the client presents on later connections via the
+//! `token` query param. Unpaired clients are only allowed read-only methods
+//!
+//! Pairings are bound to the client's `Origin` header, which browsers don't let pages forge.
+//! Only browser extensions may claim to act on behalf of a page. Any other client is identified
+//! by its own `Origin`, regardless of what it claims
+
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use alloy::{hex, primitives::keccak256};
+use async_trait::async_trait;
+use ethui_dialogs::{Dialog, DialogMsg};
+use ethui_types::{GlobalState, UINotify, B256};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{peers::Peer, WsError, WsResult};
+
+static PAIRINGS: OnceCell<RwLock<Pairings>> = OnceCell::new();
+
+/// How long pairing requests are refused for, after the user rejects one
+const REJECTION_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Held while a pairing dialog is open, since any local process can connect and request
+/// pairings. Only one is shown at a time, and others are refused meanwhile
+static PENDING: Lazy<Mutex<()>> = Lazy::new(Default::default);
+
+/// When the user last rejected a pairing
+static LAST_REJECTION: Lazy<std::sync::Mutex<Option<Instant>>> = Lazy::new(Default::default);
+
+/// An approved client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pairing {
+    /// `Origin` header of the client, e.g. `chrome-extension://<id>`
+    /// `None` for non-browser clients
+    pub origin: Option<String>,
+
+    /// the token itself is never stored, only its hash. also used to identify the pairing
+    pub token_hash: B256,
+
+    /// unix timestamp
+    pub created_at: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Pairings {
+    #[serde(skip)]
+    file: PathBuf,
+
+    pairings: Vec<Pairing>,
+}
+
+pub(crate) fn init(pathbuf: PathBuf) -> WsResult<()> {
+    let path = Path::new(&pathbuf);
+
+    let mut pairings: Pairings = if path.exists() {
+        File::open(path)
+            .ok()
+            .and_then(|f| serde_json::from_reader(f).ok())
+            .unwrap_or_default()
+    } else {
+        Default::default()
+    };
+    pairings.file = pathbuf;
+
+    PAIRINGS
+        .set(RwLock::new(pairings))
+        .map_err(|_| WsError::AlreadyInitialized)
+}
+
+/// Whether an `Origin` header belongs to a browser extension
+pub(crate) fn is_extension_origin(origin: &str) -> bool {
+    [
+        "chrome-extension://",
+        "moz-extension://",
+        "safari-web-extension://",
+    ]
+    .iter()
+    .any(|scheme| origin.starts_with(scheme))
+}
+
+#[async_trait]
+impl GlobalState for Pairings {
+    async fn read<'a>() -> RwLockReadGuard<'a, Self> {
+        PAIRINGS.get().unwrap().read().await
+    }
+
+    async fn write<'a>() -> RwLockWriteGuard<'a, Self> {
+        PAIRINGS.get().unwrap().write().await
+    }
+}
+
+impl Pairings {
+    /// Checks if a token was issued to a client with this origin
+    pub fn verify(&self, origin: &Option<String>, token: &str) -> bool {
+        let hash = keccak256(token.as_bytes());
+
+        self.pairings
+            .iter()
+            .any(|p| p.token_hash == hash && p.origin == *origin)
+    }
+
+    pub fn list(&self) -> &Vec<Pairing> {
+        &self.pairings
+    }
+
+    pub fn revoke(&mut self, token_hash: B256) -> WsResult<()> {
+        self.pairings.retain(|p| p.token_hash != token_hash);
+        self.save()
+    }
+
+    fn add(&mut self, pairing: Pairing) -> WsResult<()> {
+        self.pairings.push(pairing);
+        self.save()
+    }
+
+    fn save(&self) -> WsResult<()> {
+        let file = File::create(&self.file)?;
+        serde_json::to_writer_pretty(file, self)?;
+
+        Ok(())
+    }
+}
+
+/// Asks the user to approve a client, and issues a new token for it if approved
+///
+/// Refused without asking while another pairing is pending, or shortly after one was rejected
+pub(crate) async fn request(peer: &Peer) -> WsResult<String> {
+    let Ok(_pending) = PENDING.try_lock() else {
+        return Err(WsError::PairingThrottled);
+    };
+
+    if cooling_down(*LAST_REJECTION.lock().unwrap(), Instant::now()) {
+        return Err(WsError::PairingThrottled);
+    }
+
+    let dialog = Dialog::new(
+        "ws-pair",
+        json!({
+            "origin": peer.client_origin,
+            "claimedOrigin": peer.origin,
+            "url": peer.url,
+        }),
+    );
+    dialog.open().await?;
+
+    let approved = loop {
+        match dialog.recv().await {
+            Some(DialogMsg::Data(msg)) if msg.as_str() == Some("accept") => break true,
+            Some(DialogMsg::Data(msg)) if msg.as_str() == Some("reject") => break false,
+            Some(DialogMsg::Data(_)) => continue,
+            Some(DialogMsg::Close) | None => break false,
+        }
+    };
+
+    if !approved {
+        *LAST_REJECTION.lock().unwrap() = Some(Instant::now());
+        return Err(WsError::PairingRejected);
+    }
+
+    let token = hex::encode(rand::random::<[u8; 32]>());
+
+    Pairings::write().await.add(Pairing {
+        origin: peer.client_origin.clone(),
+        token_hash: keccak256(token.as_bytes()),
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    })?;
+
+    ethui_broadcast::ui_notify(UINotify::PeersUpdated).await;
+
+    Ok(token)
+}
+
+fn cooling_down(last_rejection: Option<Instant>, now: Instant) -> bool {
+    last_rejection.is_some_and(|at| now.duration_since(at) < REJECTION_COOLDOWN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_bound_to_origin() {
+        let token = "secret";
+        let pairings = Pairings {
+            file: Default::default(),
+            pairings: vec![Pairing {
+                origin: Some("chrome-extension://ethui".into()),
+                token_hash: keccak256(token.as_bytes()),
+                created_at: 0,
+            }],
+        };
+
+        assert!(pairings.verify(&Some("chrome-extension://ethui".into()), token));
+        assert!(!pairings.verify(&Some("https://evil.com".into()), token));
+        assert!(!pairings.verify(&None, token));
+        assert!(!pairings.verify(&Some("chrome-extension://ethui".into()), "other"));
+    }
+
+    #[test]
+    fn refuses_pairings_shortly_after_a_rejection() {
+        let now = Instant::now();
+
+        assert!(!cooling_down(None, now));
+        assert!(cooling_down(Some(now), now + Duration::from_secs(1)));
+        assert!(!cooling_down(Some(now), now + REJECTION_COOLDOWN));
+    }
+
+    #[test]
+    fn recognizes_extension_origins() {
+        assert!(is_extension_origin("chrome-extension://abcdef"));
+        assert!(is_extension_origin("moz-extension://abcdef"));
+        assert!(!is_extension_origin("https://chrome-extension.com"));
+        assert!(!is_extension_origin("http://localhost:3000"));
+    }
+}
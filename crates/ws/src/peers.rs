@@ -1,10 +1,18 @@
-use std::{collections::HashMap, net::SocketAddr};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
+use ethui_connections::Store;
 use ethui_networks::Networks;
 use ethui_types::{Address, Affinity, DedupChainId, GlobalState, UINotify};
-use serde::Serialize;
+use serde::{Serialize, Serializer};
 use serde_json::json;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Notify};
+
+use crate::{pairing, stats::PeerStats, WsError, WsResult};
 
 #[derive(Clone, Debug, Serialize)]
 pub struct Peer {
@@ -14,9 +22,24 @@ pub struct Peer {
     pub tab_id: Option<u32>,
     pub title: Option<String>,
     pub socket: SocketAddr,
+
+    /// `Origin` header of the connection, as opposed to the page origin claimed in the query
+    pub client_origin: Option<String>,
+
+    /// whether the client presented a valid pairing token. unpaired clients are read-only
+    pub paired: bool,
+
     #[serde(skip)]
     pub sender: mpsc::UnboundedSender<serde_json::Value>,
 
+    /// signals the connection to close
+    #[serde(skip)]
+    close: Arc<Notify>,
+
+    /// shared with all clones, so the connection can update it without locking `Peers`
+    #[serde(serialize_with = "serialize_stats")]
+    stats: Arc<Mutex<PeerStats>>,
+
     // non-alive peers can represent browser tabs with now web3 connection
     pub alive: bool,
 }
@@ -26,13 +49,23 @@ impl Peer {
         socket: SocketAddr,
         sender: mpsc::UnboundedSender<serde_json::Value>,
         params: &HashMap<String, String>,
+        client_origin: Option<String>,
+        paired: bool,
     ) -> Self {
-        let origin = params
-            .get("origin")
-            .cloned()
-            .unwrap_or(String::from("unknown"));
+        // only extensions can tell which page a connection is for. Pages are bound to their own
+        // `Origin`, and other clients can't claim one at all
+        let (origin, url) = match client_origin.as_deref() {
+            Some(o) if pairing::is_extension_origin(o) => (
+                params
+                    .get("origin")
+                    .cloned()
+                    .unwrap_or(String::from("unknown")),
+                params.get("url").cloned(),
+            ),
+            Some(o) => (o.to_owned(), Some(o.to_owned())),
+            None => (String::from("unknown"), None),
+        };
 
-        let url = params.get("url").cloned();
         let favicon = params.get("favicon").cloned();
         let tab_id = params.get("tabId").cloned().and_then(|id| id.parse().ok());
         let title = params.get("title").cloned();
@@ -46,6 +79,10 @@ impl Peer {
             url,
             tab_id,
             title,
+            client_origin,
+            paired,
+            close: Default::default(),
+            stats: Arc::new(Mutex::new(PeerStats::new())),
         }
     }
 
@@ -57,14 +94,78 @@ impl Peer {
                 .and_then(|url| url.host_str().map(|s| s.to_owned()))
         })
     }
+
+    /// Whether the connection comes from a browser extension
+    pub fn is_extension(&self) -> bool {
+        self.client_origin
+            .as_deref()
+            .is_some_and(pairing::is_extension_origin)
+    }
+
+    pub fn stats(&self) -> PeerStats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    pub(crate) fn update_stats(&self, f: impl FnOnce(&mut PeerStats)) {
+        f(&mut self.stats.lock().unwrap())
+    }
+
+    /// Resolves once the connection is asked to close
+    pub(crate) async fn closed(&self) {
+        self.close.notified().await
+    }
+
+    fn send(&self, msg: serde_json::Value) {
+        self.sender.send(msg).unwrap_or_else(|e| {
+            tracing::warn!("Failed to send message to peer: {}", e);
+        });
+    }
+}
+
+fn serialize_stats<S: Serializer>(
+    stats: &Arc<Mutex<PeerStats>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    stats.lock().unwrap().serialize(serializer)
+}
+
+fn accounts_changed_msg(accounts: Vec<Address>) -> serde_json::Value {
+    json!({
+        "method": "accountsChanged",
+        "params": accounts
+    })
+}
+
+fn session_changed_msg(session_scopes: serde_json::Value) -> serde_json::Value {
+    json!({
+        "method": "wallet_sessionChanged",
+        "params": {
+            "sessionScopes": session_scopes,
+        }
+    })
+}
+
+fn chain_changed_msg(chain_id: u64) -> serde_json::Value {
+    json!({
+        "method": "chainChanged",
+        "params": {
+            "chainId": format!("0x{:x}", chain_id),
+        }
+    })
 }
 
 impl From<Peer> for ethui_rpc::Handler {
     fn from(value: Peer) -> Self {
-        Self::new(value.domain())
+        let mut handler = Self::new(value.domain(), value.tab_id);
+        handler.set_read_only(!value.paired);
+        handler.set_peer(value.socket.to_string());
+        handler
     }
 }
 
+/// How long a closed tab's affinity is kept, so that page reloads don't lose it
+const TAB_CLOSE_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
 /// Tracks a list of peers, usually browser tabs, that connect to the app
 #[derive(Debug, Default)]
 pub struct Peers {
@@ -81,8 +182,36 @@ impl Peers {
     }
 
     /// Removes an existing peer
+    ///
+    /// If it was the last connection of its tab, the tab's affinity is dropped after a grace
+    /// period, so that the tab falls back to its domain's affinity
     pub async fn remove_peer(&mut self, peer: SocketAddr) {
-        self.map.remove(&peer);
+        if let Some(removed) = self.map.remove(&peer) {
+            if let (Some(domain), Some(tab_id)) = (removed.domain(), removed.tab_id) {
+                tokio::spawn(async move {
+                    tokio::time::sleep(TAB_CLOSE_GRACE_PERIOD).await;
+
+                    if !Peers::read().await.has_tab(&domain, tab_id) {
+                        Store::write().await.remove_tab_affinity(&domain, tab_id);
+                    }
+                });
+            }
+        }
+
+        ethui_broadcast::ui_notify(UINotify::PeersUpdated).await;
+    }
+
+    /// Checks if any peer is connected from the given tab
+    fn has_tab(&self, domain: &str, tab_id: u32) -> bool {
+        self.map
+            .values()
+            .any(|p| p.tab_id == Some(tab_id) && p.domain().as_deref() == Some(domain))
+    }
+
+    pub async fn peer_paired(&mut self, peer: SocketAddr) {
+        if let Some(peer) = self.map.get_mut(&peer) {
+            peer.paired = true;
+        }
         ethui_broadcast::ui_notify(UINotify::PeersUpdated).await;
     }
 
@@ -91,12 +220,54 @@ impl Peers {
         ethui_broadcast::ui_notify(UINotify::PeersUpdated).await;
     }
 
-    /// Broadcasts an `accountsChanged` event to all peers
-    pub fn broadcast_accounts_changed(&self, new_accounts: Vec<Address>) {
-        self.broadcast(json!({
-            "method": "accountsChanged",
-            "params": new_accounts
-        }));
+    /// Broadcasts an `accountsChanged` event
+    ///
+    /// If a domain is given, only its peers are notified. Otherwise, all peers not using a
+    /// burner address are
+    pub async fn broadcast_accounts_changed(
+        &self,
+        new_accounts: Vec<Address>,
+        domain: Option<String>,
+    ) {
+        let msg = accounts_changed_msg(new_accounts.clone());
+
+        for (_, peer) in self.map.iter() {
+            if ethui_connections::utils::accounts_match(peer.domain(), &domain).await {
+                peer.send(msg.clone());
+            }
+        }
+    }
+
+    /// Sends a `wallet_sessionChanged` event to the same peers as `broadcast_accounts_changed`,
+    /// if their domain has a CAIP-25 session, since accounts are part of every scope
+    pub async fn broadcast_session_accounts_changed(
+        &self,
+        new_accounts: &[Address],
+        domain: Option<String>,
+    ) {
+        for (_, peer) in self.map.iter() {
+            let Some(peer_domain) = peer.domain() else {
+                continue;
+            };
+
+            if !ethui_connections::utils::accounts_match(Some(peer_domain.clone()), &domain).await {
+                continue;
+            }
+
+            if let Some(session) = Store::read().await.get_session(&peer_domain) {
+                peer.send(session_changed_msg(session.session_scopes(new_accounts)));
+            }
+        }
+    }
+
+    /// Sends a `wallet_sessionChanged` event to all peers of a domain
+    pub fn broadcast_session_changed(&self, domain: &str, session_scopes: serde_json::Value) {
+        let msg = session_changed_msg(session_scopes);
+
+        self.map
+            .values()
+            .filter(|p| p.domain().as_deref() == Some(domain))
+            .for_each(|p| p.send(msg.clone()));
     }
 
     /// Broadcasts a `chainChanged` event to all peers
@@ -104,21 +275,23 @@ impl Peers {
         &self,
         dedup_chain_id: DedupChainId,
         domain: Option<String>,
+        tab_id: Option<u32>,
         affinity: Affinity,
     ) {
         let chain_id = dedup_chain_id.chain_id();
 
         if Networks::read().await.validate_chain_id(chain_id) {
-            let msg = json!({
-                "method": "chainChanged",
-                "params": {
-                    "chainId": format!("0x{:x}", chain_id),
-                }
-            });
+            let msg = chain_changed_msg(chain_id);
 
             for (_, peer) in self.map.iter() {
-                if ethui_connections::utils::affinity_matches(peer.domain(), &domain, affinity)
-                    .await
+                if ethui_connections::utils::affinity_matches(
+                    peer.domain(),
+                    peer.tab_id,
+                    &domain,
+                    tab_id,
+                    affinity,
+                )
+                .await
                 {
                     tracing::info!(
                         event = "peer chain changed",
@@ -126,24 +299,44 @@ impl Peers {
                         chain_id,
                         dedup_id = dedup_chain_id.dedup_id(),
                     );
-                    peer.sender
-                        .send(serde_json::to_value(&msg).unwrap())
-                        .unwrap_or_else(|e| {
-                            tracing::warn!("Failed to send message to peer: {}", e);
-                        });
+                    peer.send(msg.clone());
                 }
             }
         }
     }
 
-    fn broadcast<T: Serialize + std::fmt::Debug>(&self, msg: T) {
-        self.map.iter().for_each(|(_, peer)| {
-            peer.sender
-                .send(serde_json::to_value(&msg).unwrap())
-                .unwrap_or_else(|e| {
-                    tracing::warn!("Failed to send message to peer: {}", e);
-                });
-        });
+    /// Sends an `accountsChanged` event to a single peer, regardless of its actual accounts
+    pub fn emit_accounts_changed(&self, peer: SocketAddr, accounts: Vec<Address>) -> WsResult<()> {
+        self.get(peer)?.send(accounts_changed_msg(accounts));
+        Ok(())
+    }
+
+    /// Sends a `chainChanged` event to a single peer. The domain's affinity is left untouched
+    pub fn emit_chain_changed(&self, peer: SocketAddr, chain_id: u64) -> WsResult<()> {
+        self.get(peer)?.send(chain_changed_msg(chain_id));
+        Ok(())
+    }
+
+    /// Closes a peer's connection. It is removed once the connection shuts down
+    pub fn disconnect(&self, peer: SocketAddr) -> WsResult<()> {
+        self.get(peer)?.close.notify_one();
+        Ok(())
+    }
+
+    /// Closes the connections of all peers of a domain
+    pub fn disconnect_domain(&self, domain: &str) {
+        self.map
+            .values()
+            .filter(|p| p.domain().as_deref() == Some(domain))
+            .for_each(|p| p.close.notify_one());
+    }
+
+    pub fn stats(&self, peer: SocketAddr) -> WsResult<PeerStats> {
+        Ok(self.get(peer)?.stats())
+    }
+
+    fn get(&self, peer: SocketAddr) -> WsResult<&Peer> {
+        self.map.get(&peer).ok_or(WsError::PeerNotFound(peer))
     }
 
     pub(crate) fn by_domain(&self) -> HashMap<String, Vec<Peer>> {
@@ -1,21 +1,44 @@
-use std::{collections::HashMap, net::SocketAddr};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 
 use ethui_types::GlobalState;
-use futures::{stream::SplitSink, SinkExt, StreamExt};
+use futures::{SinkExt, StreamExt};
+use jsonrpc_core::{Call, ErrorCode, Failure, Id, Output, Request, Response, Version};
+use serde_json::json;
 use tokio::{
     net::{TcpListener, TcpStream},
-    sync::mpsc,
+    sync::{mpsc, Semaphore},
 };
-use tokio_tungstenite::{accept_hdr_async, WebSocketStream};
+use tokio_tungstenite::{accept_hdr_async_with_config, WebSocketStream};
 use tracing::warn;
 use tungstenite::{
-    handshake::server::{ErrorResponse, Request, Response},
+    handshake::server::{
+        ErrorResponse, Request as HandshakeRequest, Response as HandshakeResponse,
+    },
+    protocol::WebSocketConfig,
     Message,
 };
 use url::Url;
 
 pub use crate::error::{WsError, WsResult};
-use crate::peers::{Peer, Peers};
+use crate::{
+    pairing::{self, Pairings},
+    peers::{Peer, Peers},
+};
+
+/// Method used by clients to request a pairing token
+const PAIRING_METHOD: &str = "ethui_requestPairing";
+
+/// Largest accepted message, in bytes
+const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Largest accepted batch of requests
+const MAX_BATCH_SIZE: usize = 100;
+
+/// Requests a single peer can have in flight. Any others are rejected until some complete
+const MAX_CONCURRENT_REQUESTS: usize = 16;
+
+/// EIP-1474 "limit exceeded" error code
+const LIMIT_EXCEEDED: i64 = -32005;
 
 pub(crate) async fn server_loop(port: u16) {
     let addr = format!("127.0.0.1:{}", port);
@@ -34,19 +57,37 @@ pub(crate) async fn server_loop(port: u16) {
 
 async fn accept_connection(socket: SocketAddr, stream: TcpStream) {
     let mut query_params: HashMap<String, String> = Default::default();
-    let callback = |req: &Request, res: Response| -> std::result::Result<Response, ErrorResponse> {
+    let mut client_origin: Option<String> = None;
+    let callback = |req: &HandshakeRequest,
+                    res: HandshakeResponse|
+     -> std::result::Result<HandshakeResponse, ErrorResponse> {
         let url = Url::parse(&format!("{}{}", "http://localhost", req.uri())).unwrap();
         query_params = url.query_pairs().into_owned().collect();
+        client_origin = req
+            .headers()
+            .get("origin")
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
         Ok(res)
     };
 
-    let ws_stream = accept_hdr_async(stream, callback)
+    // oversized messages are refused before being buffered, rather than after
+    let config = WebSocketConfig::default()
+        .max_message_size(Some(MAX_MESSAGE_SIZE))
+        .max_frame_size(Some(MAX_MESSAGE_SIZE));
+
+    let ws_stream = accept_hdr_async_with_config(stream, callback, Some(config))
         .await
         .expect("Failed to accept");
     let (snd, rcv) = mpsc::unbounded_channel::<serde_json::Value>();
     let url = query_params.get("url").cloned().unwrap_or_default();
 
-    let peer = Peer::new(socket, snd, &query_params);
+    let paired = match query_params.get("token") {
+        Some(token) => Pairings::read().await.verify(&client_origin, token),
+        None => false,
+    };
+
+    let peer = Peer::new(socket, snd, &query_params, client_origin, paired);
 
     Peers::write().await.add_peer(peer.clone()).await;
     let res = handle_connection(peer, ws_stream, rcv).await;
@@ -74,10 +115,17 @@ async fn handle_connection(
     stream: WebSocketStream<TcpStream>,
     mut rcv: mpsc::UnboundedReceiver<serde_json::Value>,
 ) -> WsResult<()> {
-    let handler: ethui_rpc::Handler = peer.clone().into();
+    let handler: Arc<ethui_rpc::Handler> = Arc::new(peer.clone().into());
+    let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+
+    // extensions are expected to be paired. instead of serving an unpaired one read-only
+    // methods only, ask the user to pair it right away
+    if peer.is_extension() && !peer.paired {
+        tokio::spawn(offer_pairing(peer.clone(), handler.clone()));
+    }
 
     // will be used at most once to mark the peer as live once the first message comes in
-    let mut liveness_checker = Some(peer);
+    let mut liveness_checker = Some(peer.clone());
     let mut interval = tokio::time::interval(std::time::Duration::from_secs(15));
     let (mut ws_sender, mut ws_receiver) = stream.split();
 
@@ -87,14 +135,25 @@ async fn handle_connection(
             Some(msg) = ws_receiver.next() => {
 
                 match msg {
-                    Ok(Message::Text(msg)) => handle_message(msg.to_string(), &handler, &mut ws_sender, &mut liveness_checker).await?,
+                    Ok(Message::Text(msg)) => {
+                        if let Some(p) = liveness_checker.take() {
+                            Peers::write().await.peer_alive(p).await;
+                        }
+
+                        handle_message(msg.as_str(), &peer, &handler, &permits);
+                    }
                     Ok(Message::Close(_)) => break,
                     Ok(_) => continue,
-                    Err(e) => warn!("websocket error: {}", e),
+                    // the stream can't be read from anymore
+                    Err(e) => {
+                        warn!("websocket error: {}", e);
+                        let _ = ws_sender.close().await;
+                        break
+                    }
                 }
             }
 
-            // data sent from provider, or event broadcast
+            // replies to requests, data sent from provider, or event broadcast
             msg = rcv.recv() =>{
                 match msg {
                     Some(msg) => {
@@ -107,9 +166,15 @@ async fn handle_connection(
                 }
             }
 
-            // send a ping every 1 seconds
+            // disconnect requested by the user
+            _ = peer.closed() => {
+                ws_sender.send(Message::Close(None)).await?;
+                break
+            }
+
+            // send a ping every 15 seconds
             _ = interval.tick() => {
-                ws_sender.send(Message::Text("ping".into())).await?;
+                ws_sender.send(Message::Ping(Default::default())).await?;
             }
         }
     }
@@ -117,25 +182,244 @@ async fn handle_connection(
     Ok(())
 }
 
-async fn handle_message(
-    text: String,
+/// Validates an incoming message, and spawns a task to handle it
+///
+/// Requests are handled concurrently, since some of them wait on user input. Replies are sent
+/// through the peer's channel, in whichever order they complete
+fn handle_message(
+    text: &str,
+    peer: &Peer,
+    handler: &Arc<ethui_rpc::Handler>,
+    permits: &Arc<Semaphore>,
+) {
+    let request = match parse(text) {
+        Ok(request) => request,
+        Err(e) => return reply(peer, failure(Id::Null, e)),
+    };
+
+    peer.update_stats(|stats| stats.record_request(&request));
+
+    let Ok(permit) = permits.clone().try_acquire_owned() else {
+        let error = jsonrpc_core::Error {
+            code: ErrorCode::ServerError(LIMIT_EXCEEDED),
+            message: "too many concurrent requests".into(),
+            data: None,
+        };
+        return reply(peer, failure(request_id(&request), error));
+    };
+
+    let peer = peer.clone();
+    let handler = handler.clone();
+
+    tokio::spawn(async move {
+        let _permit = permit;
+
+        let response = dispatch(&request, &peer, &handler).await;
+
+        // notifications get no reply
+        if let Some(response) = response {
+            peer.update_stats(|stats| stats.record_response(&request, &response));
+            reply(&peer, response);
+        }
+    });
+}
+
+/// Handles pairing requests, including those within batches, and passes everything else on to
+/// the handler
+///
+/// Within a batch, pairing is done first, so that the rest of it is already allowed any method
+/// if the user approves
+async fn dispatch(
+    request: &Request,
+    peer: &Peer,
     handler: &ethui_rpc::Handler,
-    sender: &mut SplitSink<WebSocketStream<TcpStream>, Message>,
-    liveness_checker: &mut Option<Peer>,
-) -> WsResult<()> {
-    if text == "pong" {
-        return Ok(());
+) -> Option<Response> {
+    match request {
+        Request::Single(Call::MethodCall(call)) if call.method == PAIRING_METHOD => Some(
+            Response::Single(handle_pairing(peer, handler, call.id.clone()).await),
+        ),
+
+        Request::Batch(calls) if calls.iter().any(is_pairing) => {
+            let mut outputs = vec![];
+            let mut others = vec![];
+
+            for call in calls {
+                match call {
+                    Call::MethodCall(call) if call.method == PAIRING_METHOD => {
+                        outputs.push(handle_pairing(peer, handler, call.id.clone()).await)
+                    }
+                    other => others.push(other.clone()),
+                }
+            }
+
+            if !others.is_empty() {
+                match handler.handle(Request::Batch(others)).await {
+                    Some(Response::Batch(rest)) => outputs.extend(rest),
+                    Some(Response::Single(output)) => outputs.push(output),
+                    None => (),
+                }
+            }
+
+            Some(Response::Batch(outputs))
+        }
+
+        request => handler.handle(request.clone()).await,
+    }
+}
+
+fn is_pairing(call: &Call) -> bool {
+    matches!(call, Call::MethodCall(call) if call.method == PAIRING_METHOD)
+}
+
+/// Parses a message into a JSON-RPC request, enforcing the batch size limit
+///
+/// The message size is already limited by the WebSocket config
+fn parse(text: &str) -> Result<Request, jsonrpc_core::Error> {
+    let value: serde_json::Value =
+        serde_json::from_str(text).map_err(|_| jsonrpc_core::Error::parse_error())?;
+
+    let request: Request =
+        serde_json::from_value(value).map_err(|_| jsonrpc_core::Error::invalid_request())?;
+
+    if let Request::Batch(ref calls) = request {
+        if calls.is_empty() || calls.len() > MAX_BATCH_SIZE {
+            return Err(invalid_request(format!(
+                "batches must have between 1 and {} requests",
+                MAX_BATCH_SIZE
+            )));
+        }
     }
 
-    if let Some(p) = liveness_checker.take() {
-        Peers::write().await.peer_alive(p).await;
+    Ok(request)
+}
+
+/// Pairing is specific to the WebSocket server, so it's handled here instead of in
+/// `ethui_rpc::Handler`
+async fn handle_pairing(peer: &Peer, handler: &ethui_rpc::Handler, id: Id) -> Output {
+    let result = pair(peer, handler)
+        .await
+        .map(|token| json!({ "token": token }));
+
+    Output::from(result, id, Some(Version::V2))
+}
+
+/// Pairs an extension that connected without a token, and sends it the token to use from then
+/// on through an `ethui_paired` notification
+async fn offer_pairing(peer: Peer, handler: Arc<ethui_rpc::Handler>) {
+    match pair(&peer, &handler).await {
+        Ok(token) => peer
+            .sender
+            .send(json!({
+                "jsonrpc": "2.0",
+                "method": "ethui_paired",
+                "params": { "token": token },
+            }))
+            .unwrap_or_else(|e| warn!("Failed to send message to peer: {}", e)),
+        Err(e) => {
+            tracing::debug!(origin = ?peer.client_origin, "extension not paired: {}", e.message)
+        }
     }
+}
 
-    let reply = handler.handle(serde_json::from_str(&text).unwrap()).await;
-    let reply = reply
-        .map(|r| serde_json::to_string(&r).unwrap())
-        .unwrap_or_else(|| serde_json::Value::Null.to_string());
+/// Asks the user to approve a peer, lifting its read-only restriction if approved
+async fn pair(peer: &Peer, handler: &ethui_rpc::Handler) -> Result<String, jsonrpc_core::Error> {
+    match pairing::request(peer).await {
+        Ok(token) => {
+            handler.set_read_only(false);
+            Peers::write().await.peer_paired(peer.socket).await;
 
-    sender.send(reply.into()).await?;
-    Ok(())
+            Ok(token)
+        }
+        Err(e @ WsError::PairingThrottled) => Err(jsonrpc_core::Error {
+            code: ErrorCode::ServerError(LIMIT_EXCEEDED),
+            message: e.to_string(),
+            data: None,
+        }),
+        Err(e) => Err(jsonrpc_core::Error {
+            code: ErrorCode::ServerError(4001),
+            message: e.to_string(),
+            data: None,
+        }),
+    }
+}
+
+fn invalid_request(message: String) -> jsonrpc_core::Error {
+    jsonrpc_core::Error {
+        code: ErrorCode::InvalidRequest,
+        message,
+        data: None,
+    }
+}
+
+/// Id to reply with, when a request is rejected as a whole
+fn request_id(request: &Request) -> Id {
+    match request {
+        Request::Single(Call::MethodCall(call)) => call.id.clone(),
+        _ => Id::Null,
+    }
+}
+
+fn failure(id: Id, error: jsonrpc_core::Error) -> Response {
+    Response::Single(Output::Failure(Failure {
+        jsonrpc: Some(Version::V2),
+        error,
+        id,
+    }))
+}
+
+fn reply(peer: &Peer, response: Response) {
+    let response = serde_json::to_value(response).unwrap();
+
+    peer.sender.send(response).unwrap_or_else(|e| {
+        tracing::warn!("Failed to send message to peer: {}", e);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_code(res: Result<Request, jsonrpc_core::Error>) -> ErrorCode {
+        res.unwrap_err().code
+    }
+
+    #[test]
+    fn parses_single_requests_and_batches() {
+        let single = r#"{"jsonrpc":"2.0","id":1,"method":"eth_chainId","params":[]}"#;
+        assert!(matches!(parse(single), Ok(Request::Single(_))));
+
+        let batch = format!("[{},{}]", single, single);
+        assert!(matches!(parse(&batch), Ok(Request::Batch(calls)) if calls.len() == 2));
+    }
+
+    #[test]
+    fn rejects_malformed_messages() {
+        assert_eq!(error_code(parse("ping")), ErrorCode::ParseError);
+        assert_eq!(error_code(parse(r#"{"id":1"#)), ErrorCode::ParseError);
+        assert_eq!(error_code(parse("42")), ErrorCode::InvalidRequest);
+        assert_eq!(error_code(parse("[]")), ErrorCode::InvalidRequest);
+    }
+
+    #[test]
+    fn enforces_batch_size_limit() {
+        let call = r#"{"jsonrpc":"2.0","id":1,"method":"eth_chainId"}"#;
+        let batch = format!("[{}]", vec![call; MAX_BATCH_SIZE + 1].join(","));
+        assert_eq!(error_code(parse(&batch)), ErrorCode::InvalidRequest);
+    }
+
+    #[test]
+    fn finds_pairing_requests_in_batches() {
+        let batch = r#"[
+            {"jsonrpc":"2.0","id":1,"method":"eth_chainId"},
+            {"jsonrpc":"2.0","id":2,"method":"ethui_requestPairing"}
+        ]"#;
+
+        let Ok(Request::Batch(calls)) = parse(batch) else {
+            panic!("expected a batch");
+        };
+        assert_eq!(
+            calls.iter().map(is_pairing).collect::<Vec<_>>(),
+            vec![false, true]
+        );
+    }
 }
@@ -0,0 +1,160 @@
+use std::{
+    collections::BTreeSet,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use jsonrpc_core::{Call, MethodCall, Output, Params, Request, Response};
+use serde::Serialize;
+
+/// Methods whose result is the id of a new subscription or filter
+const SUBSCRIBE_METHODS: [&str; 4] = [
+    "eth_subscribe",
+    "eth_newFilter",
+    "eth_newBlockFilter",
+    "eth_newPendingTransactionFilter",
+];
+
+/// Methods that take the id of a subscription or filter as their first param, and close it
+const UNSUBSCRIBE_METHODS: [&str; 2] = ["eth_unsubscribe", "eth_uninstallFilter"];
+
+/// Activity of a single peer, for debugging stuck connections
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerStats {
+    /// unix timestamp
+    pub connected_at: u64,
+
+    /// number of calls received, counting each call in a batch
+    pub request_count: u64,
+
+    pub last_method: Option<String>,
+
+    /// unix timestamp
+    pub last_request_at: Option<u64>,
+
+    /// ids of subscriptions and filters the peer opened, and hasn't yet closed
+    pub subscriptions: BTreeSet<String>,
+}
+
+impl PeerStats {
+    pub(crate) fn new() -> Self {
+        Self {
+            connected_at: now(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn record_request(&mut self, request: &Request) {
+        let calls = match request {
+            Request::Single(call) => std::slice::from_ref(call),
+            Request::Batch(calls) => calls.as_slice(),
+        };
+
+        for call in calls {
+            let method = match call {
+                Call::MethodCall(call) => &call.method,
+                Call::Notification(notification) => &notification.method,
+                Call::Invalid { .. } => continue,
+            };
+
+            self.request_count += 1;
+            self.last_method = Some(method.clone());
+            self.last_request_at = Some(now());
+        }
+    }
+
+    /// Keeps track of subscriptions opened or closed by successful calls
+    pub(crate) fn record_response(&mut self, request: &Request, response: &Response) {
+        let calls: Vec<&MethodCall> = match request {
+            Request::Single(Call::MethodCall(call)) => vec![call],
+            Request::Single(_) => vec![],
+            Request::Batch(calls) => calls
+                .iter()
+                .filter_map(|call| match call {
+                    Call::MethodCall(call) => Some(call),
+                    _ => None,
+                })
+                .collect(),
+        };
+
+        let outputs = match response {
+            Response::Single(output) => std::slice::from_ref(output),
+            Response::Batch(outputs) => outputs.as_slice(),
+        };
+
+        for call in calls {
+            let result = outputs.iter().find_map(|output| match output {
+                Output::Success(success) if success.id == call.id => Some(&success.result),
+                _ => None,
+            });
+
+            let Some(result) = result else { continue };
+
+            if SUBSCRIBE_METHODS.contains(&call.method.as_str()) {
+                if let Some(id) = result.as_str() {
+                    self.subscriptions.insert(id.into());
+                }
+            } else if UNSUBSCRIBE_METHODS.contains(&call.method.as_str()) {
+                if let Params::Array(ref params) = call.params {
+                    if let Some(id) = params.first().and_then(|p| p.as_str()) {
+                        self.subscriptions.remove(id);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(json: &str) -> Request {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn response(json: &str) -> Response {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn counts_every_call_in_a_batch() {
+        let mut stats = PeerStats::new();
+
+        stats.record_request(&request(
+            r#"[{"jsonrpc":"2.0","id":1,"method":"eth_chainId"},{"jsonrpc":"2.0","method":"eth_blockNumber"}]"#,
+        ));
+
+        assert_eq!(stats.request_count, 2);
+        assert_eq!(stats.last_method.as_deref(), Some("eth_blockNumber"));
+    }
+
+    #[test]
+    fn tracks_open_subscriptions() {
+        let mut stats = PeerStats::new();
+
+        let new_filter = request(r#"{"jsonrpc":"2.0","id":1,"method":"eth_newBlockFilter"}"#);
+        stats.record_response(
+            &new_filter,
+            &response(r#"{"jsonrpc":"2.0","id":1,"result":"0x1"}"#),
+        );
+        stats.record_response(
+            &new_filter,
+            &response(r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"nope"}}"#),
+        );
+        assert_eq!(stats.subscriptions.len(), 1);
+
+        stats.record_response(
+            &request(r#"{"jsonrpc":"2.0","id":2,"method":"eth_uninstallFilter","params":["0x1"]}"#),
+            &response(r#"{"jsonrpc":"2.0","id":2,"result":true}"#),
+        );
+        assert!(stats.subscriptions.is_empty());
+    }
+}
@@ -22,7 +22,7 @@ import {
   DialogTitle,
 } from "@ethui/ui/components/shadcn/dialog";
 import { Link } from "@tanstack/react-router";
-import { useInvoke } from "#/hooks/useInvoke";
+import { type AddressLabel, useAddressLabel } from "#/hooks/useAddressLabel";
 import { useNetworks } from "#/store/useNetworks";
 import { truncateHex } from "#/utils";
 import { IconAddress } from "./Icons/Address";
@@ -33,23 +33,30 @@ interface Props {
   copyIcon?: boolean;
   contextMenu?: boolean;
   icon?: boolean;
+
+  /// already resolved label, e.g. one sent along with a dialog
+  label?: AddressLabel | null;
 }
 
 export function AddressView({
   address: addr,
   contextMenu = true,
   icon = false,
+  label: givenLabel,
 }: Props) {
   const network = useNetworks((s) => s.current);
   const address = getAddress(addr);
-  const { data: alias, refetch } = useInvoke<string>("settings_get_alias", {
+  const { data: resolvedLabel, refetch } = useAddressLabel(
     address,
-  });
+    network?.dedup_chain_id.chain_id,
+  );
   const [aliasFormOpen, setAliasFormOpen] = useState(false);
 
   if (!network) return;
 
-  const text = alias ? alias : truncateHex(address);
+  const label = resolvedLabel ?? givenLabel;
+  const alias = label?.source === "addressBook" ? label.name : undefined;
+  const text = label ? label.name : truncateHex(address);
   const content = (
     <ClickToCopy text={address}>
       <div className="flex items-center gap-x-1 font-mono text-base">
@@ -125,7 +132,7 @@ function AliasForm({ address, alias, refetch, onSubmit }: AliasFormProps) {
   });
 
   const submit = (data: FieldValues) => {
-    invoke("settings_set_alias", { address, alias: data.alias });
+    invoke("db_set_alias", { address, alias: data.alias });
     refetch();
     onSubmit();
   };
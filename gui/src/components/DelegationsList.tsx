@@ -0,0 +1,67 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useState } from "react";
+import { type Address, zeroAddress } from "viem";
+
+import { ChainView } from "@ethui/ui/components/chain-view";
+import { Button } from "@ethui/ui/components/shadcn/button";
+import { useInvoke } from "#/hooks/useInvoke";
+import { useNetworks } from "#/store/useNetworks";
+import { AddressView } from "./AddressView";
+
+interface Delegation {
+  chainId: number;
+  network: string;
+  delegate?: Address;
+  contractName?: string;
+}
+
+/// EIP-7702 delegations of an account, on every known network
+export function DelegationsList({ address }: { address: Address }) {
+  const current = useNetworks((s) => s.current);
+  const { data: delegations, refetch } = useInvoke<Delegation[]>(
+    "rpc_get_delegations",
+    { address },
+  );
+  const [clearing, setClearing] = useState(false);
+
+  const delegated = (delegations ?? []).filter(({ delegate }) => !!delegate);
+  if (delegated.length === 0) return null;
+
+  // delegations are set and cleared on the current network
+  const clear = async () => {
+    setClearing(true);
+    try {
+      await invoke("rpc_set_delegation", { address, delegate: zeroAddress });
+    } finally {
+      setClearing(false);
+      refetch();
+    }
+  };
+
+  return (
+    <div className="flex w-full flex-col gap-2">
+      <h2 className="font-bold">Delegations</h2>
+      <ul className="flex flex-col gap-2">
+        {delegated.map(({ chainId, network, delegate, contractName }) => (
+          <li key={network} className="flex items-center gap-4">
+            <ChainView chainId={chainId} name={network} />
+            {delegate && <AddressView address={delegate} />}
+            <span className="text-muted-foreground text-sm">
+              {contractName ?? "unknown contract"}
+            </span>
+            {current?.name === network && (
+              <Button
+                size="sm"
+                variant="destructive"
+                disabled={clearing}
+                onClick={clear}
+              >
+                Clear
+              </Button>
+            )}
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+}
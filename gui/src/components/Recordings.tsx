@@ -0,0 +1,182 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useState } from "react";
+
+import { Button } from "@ethui/ui/components/shadcn/button";
+import { Input } from "@ethui/ui/components/shadcn/input";
+import { toast } from "@ethui/ui/hooks/use-toast";
+import { useInvoke } from "#/hooks/useInvoke";
+
+interface Recording {
+  id: number;
+  name: string;
+  domain?: string;
+  createdAt: number;
+  network: { chainId: number; snapshot?: string; forkUrl?: string };
+  steps: unknown[];
+}
+
+type ReplayTarget = "snapshot" | "fresh";
+
+interface ReplayedStep {
+  index: number;
+  hash?: string;
+  outcome:
+    | "unchanged"
+    | "reverted"
+    | "noLongerReverts"
+    | "logsChanged"
+    | "skipped";
+  error?: string;
+  reason?: string;
+}
+
+interface ReplayReport {
+  recordingId: number;
+  steps: ReplayedStep[];
+}
+
+const onError = (err: any) =>
+  toast({
+    title: "Error",
+    description: err.toString(),
+    variant: "destructive",
+  });
+
+/// Records the calls dapps make, and replays stored recordings against anvil
+export function Recordings() {
+  const { data: current, refetch: refetchCurrent } = useInvoke<Recording>(
+    "rpc_recording_current",
+  );
+  const { data: recordings, refetch } =
+    useInvoke<Recording[]>("db_get_recordings");
+  const [name, setName] = useState("");
+  const [domain, setDomain] = useState("");
+  const [busy, setBusy] = useState(false);
+  const [reports, setReports] = useState<Record<number, ReplayReport>>({});
+
+  const run = async (fn: () => Promise<unknown>) => {
+    setBusy(true);
+    try {
+      await fn();
+    } catch (err) {
+      onError(err);
+    } finally {
+      setBusy(false);
+      refetchCurrent();
+      refetch();
+    }
+  };
+
+  const onStart = () =>
+    run(() =>
+      invoke("rpc_recording_start", { name, domain: domain || null }),
+    );
+
+  // waits for the receipts of all recorded transactions
+  const onStop = () => run(() => invoke("rpc_recording_stop"));
+
+  const onReplay = (id: number, target: ReplayTarget) =>
+    run(async () => {
+      const report = await invoke<ReplayReport>("rpc_recording_replay", {
+        id,
+        target,
+      });
+      setReports((reports) => ({ ...reports, [id]: report }));
+    });
+
+  const onRemove = (id: number) =>
+    run(() => invoke("db_remove_recording", { id }));
+
+  return (
+    <div className="m-1 flex flex-col gap-2">
+      <h2 className="font-bold">Recordings</h2>
+
+      {current ? (
+        <div className="flex items-center gap-2">
+          <span>
+            Recording {current.name} ({current.steps.length} calls)
+          </span>
+          <Button size="sm" disabled={busy} onClick={onStop}>
+            Stop
+          </Button>
+        </div>
+      ) : (
+        <div className="flex items-center gap-2">
+          <Input
+            placeholder="Name"
+            value={name}
+            onChange={(e) => setName(e.target.value)}
+          />
+          <Input
+            placeholder="Domain (optional)"
+            value={domain}
+            onChange={(e) => setDomain(e.target.value)}
+          />
+          <Button size="sm" disabled={busy || !name} onClick={onStart}>
+            Record
+          </Button>
+        </div>
+      )}
+
+      <ul className="flex flex-col gap-2">
+        {recordings?.map(({ id, name, domain, network, steps }) => (
+          <li key={id} className="flex flex-col gap-1">
+            <div className="flex items-center gap-2">
+              <span>{name}</span>
+              <span className="text-muted-foreground text-sm">
+                {domain ?? "all domains"} · chain {network.chainId} ·{" "}
+                {steps.length} calls
+              </span>
+              {network.snapshot && (
+                <Button
+                  size="sm"
+                  disabled={busy}
+                  onClick={() => onReplay(id, "snapshot")}
+                >
+                  Replay from snapshot
+                </Button>
+              )}
+              <Button
+                size="sm"
+                disabled={busy}
+                onClick={() => onReplay(id, "fresh")}
+              >
+                Replay on new anvil
+              </Button>
+              <Button
+                size="sm"
+                variant="destructive"
+                disabled={busy}
+                onClick={() => onRemove(id)}
+              >
+                Remove
+              </Button>
+            </div>
+            {reports[id] && <Report report={reports[id]} />}
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+}
+
+function Report({ report }: { report: ReplayReport }) {
+  if (report.steps.length === 0) {
+    return <span className="ml-4 text-sm">No transactions to replay</span>;
+  }
+
+  return (
+    <ul className="ml-4 flex flex-col text-sm">
+      {report.steps.map(({ index, hash, outcome, error, reason }) => (
+        <li key={index} className="flex gap-2">
+          <span>#{index}</span>
+          <span className={outcome === "unchanged" ? "" : "text-destructive"}>
+            {outcome}
+          </span>
+          {(error || reason) && <span>{error ?? reason}</span>}
+          {hash && <span className="truncate font-mono">{hash}</span>}
+        </li>
+      ))}
+    </ul>
+  );
+}
@@ -0,0 +1,68 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useState } from "react";
+
+import { Button } from "@ethui/ui/components/shadcn/button";
+import { toast } from "@ethui/ui/hooks/use-toast";
+import { useEventListener } from "#/hooks/useEventListener";
+import { useInvoke } from "#/hooks/useInvoke";
+
+interface FoundryKeystore {
+  name: string;
+  file: string;
+  wallet?: string;
+}
+
+/// Keystores found in `~/.foundry/keystores`, each importable with a single click
+export function FoundryKeystores() {
+  const { data: keystores, refetch } = useInvoke<FoundryKeystore[]>(
+    "wallets_list_foundry_keystores",
+  );
+  const [importing, setImporting] = useState<string>();
+
+  useEventListener("keystores-changed", refetch);
+
+  if (!keystores || keystores.length === 0) return null;
+
+  // may prompt the user to unlock the keystore, to read its address
+  const onImport = async (file: string) => {
+    setImporting(file);
+    try {
+      await invoke("wallets_import_foundry_keystore", { file });
+    } catch (err: any) {
+      toast({
+        title: "Error",
+        description: err.toString(),
+        variant: "destructive",
+      });
+    } finally {
+      setImporting(undefined);
+      refetch();
+    }
+  };
+
+  return (
+    <div className="flex w-full flex-col gap-2">
+      <h2 className="font-bold">Foundry keystores</h2>
+      <ul className="flex flex-col gap-2">
+        {keystores.map(({ name, file, wallet }) => (
+          <li key={file} className="flex items-center gap-4">
+            <span className="font-mono">{name}</span>
+            {wallet ? (
+              <span className="text-muted-foreground text-sm">
+                imported as {wallet}
+              </span>
+            ) : (
+              <Button
+                size="sm"
+                disabled={!!importing}
+                onClick={() => onImport(file)}
+              >
+                Import
+              </Button>
+            )}
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+}
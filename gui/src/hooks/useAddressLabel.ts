@@ -0,0 +1,22 @@
+import type { Address } from "viem";
+
+import { useEventListener } from "./useEventListener";
+import { useInvoke } from "./useInvoke";
+
+export interface AddressLabel {
+  name: string;
+  source: "addressBook" | "ens";
+}
+
+/// Human-readable name of an address, from the address book or ENS
+export function useAddressLabel(address: Address, chainId?: number) {
+  const query = useInvoke<AddressLabel | null>(
+    "rpc_resolve_address_label",
+    { chainId, address },
+    { enabled: chainId !== undefined },
+  );
+
+  useEventListener("address-book-updated", query.refetch);
+
+  return query;
+}
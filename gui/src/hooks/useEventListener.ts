@@ -5,7 +5,10 @@ type Event =
   | "peers-updated"
   | "settings-changed"
   | "contracts-updated"
-  | "txs-updated";
+  | "txs-updated"
+  | "keystores-changed"
+  | "dev-accounts-detected"
+  | "address-book-updated";
 
 export function useEventListener(event: Event, callback: () => unknown) {
   const view = getCurrentWebviewWindow();
@@ -0,0 +1,61 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useEffect, useRef } from "react";
+import type { Address } from "viem";
+
+import { ToastAction } from "@ethui/ui/components/shadcn/toast";
+import { toast } from "@ethui/ui/hooks/use-toast";
+import { useEventListener } from "./useEventListener";
+import { useInvoke } from "./useInvoke";
+
+interface DevAccounts {
+  network: string;
+  chainId: number;
+  accounts: Address[];
+  defaultMnemonic: boolean;
+  walletExists: boolean;
+}
+
+/// Offers a wallet for the accounts of each newly detected dev node
+export function useNoticeDevAccounts() {
+  const { data: suggestions, refetch } = useInvoke<DevAccounts[]>(
+    "wallets_get_dev_accounts",
+  );
+  const shown = useRef(new Set<string>());
+
+  useEventListener("dev-accounts-detected", refetch);
+
+  useEffect(() => {
+    for (const dev of suggestions ?? []) {
+      if (shown.current.has(dev.network)) continue;
+      shown.current.add(dev.network);
+
+      const kind = dev.defaultMnemonic ? "anvil's default" : "impersonated";
+      const apply = () =>
+        invoke("wallets_apply_dev_accounts", { network: dev.network }).catch(
+          (err) =>
+            toast({
+              title: "Error",
+              description: err.toString(),
+              variant: "destructive",
+            }),
+        );
+
+      toast({
+        title: `${dev.accounts.length} dev accounts found on ${dev.network}`,
+        description: dev.walletExists
+          ? `The ${dev.network} wallet no longer matches them`
+          : `They can be added as a wallet, using ${kind} accounts`,
+        action: (
+          <ToastAction altText="Use accounts" onClick={apply}>
+            {dev.walletExists ? "Refresh wallet" : "Add wallet"}
+          </ToastAction>
+        ),
+        onOpenChange: (open) => {
+          if (open) return;
+          shown.current.delete(dev.network);
+          invoke("wallets_dismiss_dev_accounts", { network: dev.network });
+        },
+      });
+    }
+  }, [suggestions]);
+}
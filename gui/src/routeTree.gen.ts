@@ -29,6 +29,10 @@ import { Route as DialogLErc721AddIdImport } from './routes/dialog/_l/erc721-add
 import { Route as DialogLErc20AddIdImport } from './routes/dialog/_l/erc20-add.$id'
 import { Route as DialogLErc1155AddIdImport } from './routes/dialog/_l/erc1155-add.$id'
 import { Route as DialogLChainSwitchIdImport } from './routes/dialog/_l/chain-switch.$id'
+import { Route as DialogLSecretsUnlockIdImport } from './routes/dialog/_l/secrets-unlock.$id'
+import { Route as DialogLSessionCreateIdImport } from './routes/dialog/_l/session-create.$id'
+import { Route as DialogLWsPairIdImport } from './routes/dialog/_l/ws-pair.$id'
+import { Route as DialogLAuthorizationReviewIdImport } from './routes/dialog/_l/authorization-review.$id'
 import { Route as DialogLChainAddIdImport } from './routes/dialog/_l/chain-add.$id'
 import { Route as HomeLContractsLIndexImport } from './routes/home/_l/contracts/_l/index'
 import { Route as HomeLTransferLEthImport } from './routes/home/_l/transfer/_l.eth'
@@ -183,6 +187,30 @@ const DialogLChainSwitchIdRoute = DialogLChainSwitchIdImport.update({
   getParentRoute: () => DialogLRoute,
 } as any)
 
+const DialogLSecretsUnlockIdRoute = DialogLSecretsUnlockIdImport.update({
+  id: '/secrets-unlock/$id',
+  path: '/secrets-unlock/$id',
+  getParentRoute: () => DialogLRoute,
+} as any)
+
+const DialogLSessionCreateIdRoute = DialogLSessionCreateIdImport.update({
+  id: '/session-create/$id',
+  path: '/session-create/$id',
+  getParentRoute: () => DialogLRoute,
+} as any)
+
+const DialogLWsPairIdRoute = DialogLWsPairIdImport.update({
+  id: '/ws-pair/$id',
+  path: '/ws-pair/$id',
+  getParentRoute: () => DialogLRoute,
+} as any)
+
+const DialogLAuthorizationReviewIdRoute = DialogLAuthorizationReviewIdImport.update({
+  id: '/authorization-review/$id',
+  path: '/authorization-review/$id',
+  getParentRoute: () => DialogLRoute,
+} as any)
+
 const DialogLChainAddIdRoute = DialogLChainAddIdImport.update({
   id: '/chain-add/$id',
   path: '/chain-add/$id',
@@ -370,6 +398,34 @@ declare module '@tanstack/react-router' {
       preLoaderRoute: typeof DialogLChainSwitchIdImport
       parentRoute: typeof DialogLImport
     }
+    '/dialog/_l/secrets-unlock/$id': {
+      id: '/dialog/_l/secrets-unlock/$id'
+      path: '/secrets-unlock/$id'
+      fullPath: '/dialog/secrets-unlock/$id'
+      preLoaderRoute: typeof DialogLSecretsUnlockIdImport
+      parentRoute: typeof DialogLImport
+    }
+    '/dialog/_l/session-create/$id': {
+      id: '/dialog/_l/session-create/$id'
+      path: '/session-create/$id'
+      fullPath: '/dialog/session-create/$id'
+      preLoaderRoute: typeof DialogLSessionCreateIdImport
+      parentRoute: typeof DialogLImport
+    }
+    '/dialog/_l/ws-pair/$id': {
+      id: '/dialog/_l/ws-pair/$id'
+      path: '/ws-pair/$id'
+      fullPath: '/dialog/ws-pair/$id'
+      preLoaderRoute: typeof DialogLWsPairIdImport
+      parentRoute: typeof DialogLImport
+    }
+    '/dialog/_l/authorization-review/$id': {
+      id: '/dialog/_l/authorization-review/$id'
+      path: '/authorization-review/$id'
+      fullPath: '/dialog/authorization-review/$id'
+      preLoaderRoute: typeof DialogLAuthorizationReviewIdImport
+      parentRoute: typeof DialogLImport
+    }
     '/dialog/_l/erc1155-add/$id': {
       id: '/dialog/_l/erc1155-add/$id'
       path: '/erc1155-add/$id'
@@ -581,6 +637,10 @@ declare module '@tanstack/react-router' {
 interface DialogLRouteChildren {
   DialogLChainAddIdRoute: typeof DialogLChainAddIdRoute
   DialogLChainSwitchIdRoute: typeof DialogLChainSwitchIdRoute
+  DialogLSecretsUnlockIdRoute: typeof DialogLSecretsUnlockIdRoute
+  DialogLSessionCreateIdRoute: typeof DialogLSessionCreateIdRoute
+  DialogLWsPairIdRoute: typeof DialogLWsPairIdRoute
+  DialogLAuthorizationReviewIdRoute: typeof DialogLAuthorizationReviewIdRoute
   DialogLErc1155AddIdRoute: typeof DialogLErc1155AddIdRoute
   DialogLErc20AddIdRoute: typeof DialogLErc20AddIdRoute
   DialogLErc721AddIdRoute: typeof DialogLErc721AddIdRoute
@@ -592,6 +652,10 @@ interface DialogLRouteChildren {
 const DialogLRouteChildren: DialogLRouteChildren = {
   DialogLChainAddIdRoute: DialogLChainAddIdRoute,
   DialogLChainSwitchIdRoute: DialogLChainSwitchIdRoute,
+  DialogLSecretsUnlockIdRoute: DialogLSecretsUnlockIdRoute,
+  DialogLSessionCreateIdRoute: DialogLSessionCreateIdRoute,
+  DialogLWsPairIdRoute: DialogLWsPairIdRoute,
+  DialogLAuthorizationReviewIdRoute: DialogLAuthorizationReviewIdRoute,
   DialogLErc1155AddIdRoute: DialogLErc1155AddIdRoute,
   DialogLErc20AddIdRoute: DialogLErc20AddIdRoute,
   DialogLErc721AddIdRoute: DialogLErc721AddIdRoute,
@@ -802,6 +866,10 @@ export interface FileRoutesByFullPath {
   '/home/transactions': typeof HomeLTransactionsRoute
   '/dialog/chain-add/$id': typeof DialogLChainAddIdRoute
   '/dialog/chain-switch/$id': typeof DialogLChainSwitchIdRoute
+  '/dialog/secrets-unlock/$id': typeof DialogLSecretsUnlockIdRoute
+  '/dialog/session-create/$id': typeof DialogLSessionCreateIdRoute
+  '/dialog/ws-pair/$id': typeof DialogLWsPairIdRoute
+  '/dialog/authorization-review/$id': typeof DialogLAuthorizationReviewIdRoute
   '/dialog/erc1155-add/$id': typeof DialogLErc1155AddIdRoute
   '/dialog/erc20-add/$id': typeof DialogLErc20AddIdRoute
   '/dialog/erc721-add/$id': typeof DialogLErc721AddIdRoute
@@ -837,6 +905,10 @@ export interface FileRoutesByTo {
   '/home/transactions': typeof HomeLTransactionsRoute
   '/dialog/chain-add/$id': typeof DialogLChainAddIdRoute
   '/dialog/chain-switch/$id': typeof DialogLChainSwitchIdRoute
+  '/dialog/secrets-unlock/$id': typeof DialogLSecretsUnlockIdRoute
+  '/dialog/session-create/$id': typeof DialogLSessionCreateIdRoute
+  '/dialog/ws-pair/$id': typeof DialogLWsPairIdRoute
+  '/dialog/authorization-review/$id': typeof DialogLAuthorizationReviewIdRoute
   '/dialog/erc1155-add/$id': typeof DialogLErc1155AddIdRoute
   '/dialog/erc20-add/$id': typeof DialogLErc20AddIdRoute
   '/dialog/erc721-add/$id': typeof DialogLErc721AddIdRoute
@@ -872,6 +944,10 @@ export interface FileRoutesById {
   '/home/_l/transactions': typeof HomeLTransactionsRoute
   '/dialog/_l/chain-add/$id': typeof DialogLChainAddIdRoute
   '/dialog/_l/chain-switch/$id': typeof DialogLChainSwitchIdRoute
+  '/dialog/_l/secrets-unlock/$id': typeof DialogLSecretsUnlockIdRoute
+  '/dialog/_l/session-create/$id': typeof DialogLSessionCreateIdRoute
+  '/dialog/_l/ws-pair/$id': typeof DialogLWsPairIdRoute
+  '/dialog/_l/authorization-review/$id': typeof DialogLAuthorizationReviewIdRoute
   '/dialog/_l/erc1155-add/$id': typeof DialogLErc1155AddIdRoute
   '/dialog/_l/erc20-add/$id': typeof DialogLErc20AddIdRoute
   '/dialog/_l/erc721-add/$id': typeof DialogLErc721AddIdRoute
@@ -914,6 +990,10 @@ export interface FileRouteTypes {
     | '/home/transactions'
     | '/dialog/chain-add/$id'
     | '/dialog/chain-switch/$id'
+    | '/dialog/secrets-unlock/$id'
+    | '/dialog/session-create/$id'
+    | '/dialog/ws-pair/$id'
+    | '/dialog/authorization-review/$id'
     | '/dialog/erc1155-add/$id'
     | '/dialog/erc20-add/$id'
     | '/dialog/erc721-add/$id'
@@ -948,6 +1028,10 @@ export interface FileRouteTypes {
     | '/home/transactions'
     | '/dialog/chain-add/$id'
     | '/dialog/chain-switch/$id'
+    | '/dialog/secrets-unlock/$id'
+    | '/dialog/session-create/$id'
+    | '/dialog/ws-pair/$id'
+    | '/dialog/authorization-review/$id'
     | '/dialog/erc1155-add/$id'
     | '/dialog/erc20-add/$id'
     | '/dialog/erc721-add/$id'
@@ -981,6 +1065,10 @@ export interface FileRouteTypes {
     | '/home/_l/transactions'
     | '/dialog/_l/chain-add/$id'
     | '/dialog/_l/chain-switch/$id'
+    | '/dialog/_l/secrets-unlock/$id'
+    | '/dialog/_l/session-create/$id'
+    | '/dialog/_l/ws-pair/$id'
+    | '/dialog/_l/authorization-review/$id'
     | '/dialog/_l/erc1155-add/$id'
     | '/dialog/_l/erc20-add/$id'
     | '/dialog/_l/erc721-add/$id'
@@ -1049,6 +1137,10 @@ export const routeTree = rootRoute
       "children": [
         "/dialog/_l/chain-add/$id",
         "/dialog/_l/chain-switch/$id",
+        "/dialog/_l/secrets-unlock/$id",
+        "/dialog/_l/session-create/$id",
+        "/dialog/_l/ws-pair/$id",
+        "/dialog/_l/authorization-review/$id",
         "/dialog/_l/erc1155-add/$id",
         "/dialog/_l/erc20-add/$id",
         "/dialog/_l/erc721-add/$id",
@@ -1100,6 +1192,22 @@ export const routeTree = rootRoute
       "filePath": "dialog/_l/chain-switch.$id.tsx",
       "parent": "/dialog/_l"
     },
+    "/dialog/_l/secrets-unlock/$id": {
+      "filePath": "dialog/_l/secrets-unlock.$id.tsx",
+      "parent": "/dialog/_l"
+    },
+    "/dialog/_l/session-create/$id": {
+      "filePath": "dialog/_l/session-create.$id.tsx",
+      "parent": "/dialog/_l"
+    },
+    "/dialog/_l/ws-pair/$id": {
+      "filePath": "dialog/_l/ws-pair.$id.tsx",
+      "parent": "/dialog/_l"
+    },
+    "/dialog/_l/authorization-review/$id": {
+      "filePath": "dialog/_l/authorization-review.$id.tsx",
+      "parent": "/dialog/_l"
+    },
     "/dialog/_l/erc1155-add/$id": {
       "filePath": "dialog/_l/erc1155-add.$id.tsx",
       "parent": "/dialog/_l"
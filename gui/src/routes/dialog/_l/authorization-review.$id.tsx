@@ -0,0 +1,137 @@
+import { createFileRoute } from "@tanstack/react-router";
+import type { Abi, AbiFunction, Address } from "viem";
+
+import { ChainView } from "@ethui/ui/components/chain-view";
+import { Button } from "@ethui/ui/components/shadcn/button";
+import { AddressView } from "#/components/AddressView";
+import { Datapoint } from "#/components/Datapoint";
+import { DialogBottom } from "#/components/Dialogs/Bottom";
+import { useDialog } from "#/hooks/useDialog";
+import { useNetworks } from "#/store/useNetworks";
+
+export const Route = createFileRoute("/dialog/_l/authorization-review/$id")({
+  component: AuthorizationReviewDialog,
+});
+
+interface AuthorizationRequest {
+  authority: Address;
+  delegate: Address;
+  chainId: string;
+  nonce: number;
+  contract?: DelegateContract;
+}
+
+interface DelegateContract {
+  name?: string;
+  abi?: Abi;
+
+  /// a contract we already know of, or verified source fetched from etherscan
+  source: "known" | "etherscan";
+}
+
+const SOURCE_LABELS = {
+  known: "known contract",
+  etherscan: "verified on etherscan",
+};
+
+function AuthorizationReviewDialog() {
+  const { id } = Route.useParams();
+  const { data, send } = useDialog<AuthorizationRequest>(id);
+  const networks = useNetworks((s) => s.networks);
+
+  if (!data) return null;
+
+  const chainId = Number(data.chainId);
+  const network = networks.find((n) => n.dedup_chain_id.chain_id === chainId);
+  const clearing = /^0x0{40}$/.test(data.delegate);
+
+  return (
+    <div className="flex flex-col gap-5">
+      <div className="flex w-full items-stretch justify-center self-center">
+        <h1 className="font-xl">
+          {clearing ? "Clear delegation" : "Review delegation"}
+        </h1>
+      </div>
+
+      <div className="self-center">
+        <ChainView chainId={chainId} name={network?.name ?? `${chainId}`} />
+      </div>
+
+      <div className="grid grid-cols-4 gap-5">
+        <Datapoint
+          label="Account"
+          value={<AddressView address={data.authority} />}
+          className="col-span-4"
+        />
+        {!clearing && (
+          <Datapoint
+            label="Delegate contract"
+            value={<AddressView address={data.delegate} />}
+            className="col-span-4"
+          />
+        )}
+        {!clearing && (
+          <Datapoint
+            label="Contract name"
+            value={data.contract?.name ?? "unknown contract"}
+            className="col-span-2"
+          />
+        )}
+        <Datapoint label="Nonce" value={data.nonce} className="col-span-2" />
+      </div>
+
+      {!clearing && <DelegateAbi contract={data.contract} />}
+
+      {!clearing && (
+        <p className="text-destructive text-sm">
+          The delegate contract will run as this account's code, and can move
+          all of its funds. Only continue if you trust it.
+        </p>
+      )}
+
+      <DialogBottom>
+        <div className="m-2 flex items-center justify-center gap-2">
+          <Button variant="destructive" onClick={() => send("reject")}>
+            Reject
+          </Button>
+          <Button type="submit" onClick={() => send("accept")}>
+            Sign
+          </Button>
+        </div>
+      </DialogBottom>
+    </div>
+  );
+}
+
+function DelegateAbi({ contract }: { contract?: DelegateContract }) {
+  const functions = (contract?.abi ?? []).filter(
+    (item): item is AbiFunction => item.type === "function",
+  );
+
+  if (!contract || functions.length === 0) {
+    return (
+      <p className="text-destructive text-sm">
+        The delegate's source is not verified, so what it does can't be shown.
+      </p>
+    );
+  }
+
+  return (
+    <div className="flex flex-col gap-1">
+      <span className="text-sm">
+        Functions ({SOURCE_LABELS[contract.source]})
+      </span>
+      <ul className="max-h-32 overflow-y-auto font-mono text-xs">
+        {functions.map((fn) => {
+          const signature = `${fn.name}(${fn.inputs.map((i) => i.type).join(",")})`;
+          return (
+            <li key={signature}>
+              {signature}
+              {fn.stateMutability === "payable" && " payable"}
+            </li>
+          );
+        })}
+      </ul>
+    </div>
+  );
+}
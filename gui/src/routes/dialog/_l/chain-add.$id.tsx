@@ -1,10 +1,18 @@
 import { createFileRoute } from "@tanstack/react-router";
-import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
-import { isDirty, isValid } from "zod";
+import { useEffect, useState } from "react";
 
 import type { Network } from "@ethui/types/network";
 import { ChainView } from "@ethui/ui/components/chain-view";
 import { Button } from "@ethui/ui/components/shadcn/button";
+import { Input } from "@ethui/ui/components/shadcn/input";
+import {
+  Select,
+  SelectContent,
+  SelectGroup,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@ethui/ui/components/shadcn/select";
 import { Datapoint } from "#/components/Datapoint";
 import { DialogBottom } from "#/components/Dialogs/Bottom";
 import { useDialog } from "#/hooks/useDialog";
@@ -13,29 +21,91 @@ export const Route = createFileRoute("/dialog/_l/chain-add/$id")({
   component: ChainAddDialog,
 });
 
+interface NewNetwork {
+  name: string;
+  dedup_chain_id: { chain_id: number };
+  explorer_url?: string;
+  http_url: string;
+  ws_url?: string;
+  currency: string;
+  decimals: number;
+
+  /// networks already using this chain id
+  existing?: Network[];
+}
+
+interface RpcProbe {
+  url: string;
+}
+
+const NEW_NETWORK = "__new__";
+
 function ChainAddDialog() {
   const { id } = Route.useParams();
-  const { data: network, send } = useDialog<Network>(id);
+  const { data: network, send, listen } = useDialog<NewNetwork>(id);
+  const [target, setTarget] = useState<string>();
+  const [name, setName] = useState<string>();
+  const [probes, setProbes] = useState<RpcProbe[]>();
+  const [anvil, setAnvil] = useState(false);
+  const [loading, setLoading] = useState(false);
+
+  // none of the RPC URLs could be reached
+  useEffect(() => {
+    const unlisten = listen("failed", ({ payload }) => {
+      setProbes(payload as RpcProbe[]);
+      setLoading(false);
+    });
+
+    return () => {
+      unlisten.then((cb) => cb());
+    };
+  }, [listen]);
+
+  // anvil nodes can only be added as separate networks
+  useEffect(() => {
+    const unlisten = listen("anvil", () => {
+      setAnvil(true);
+      setTarget(NEW_NETWORK);
+      setLoading(false);
+    });
+
+    return () => {
+      unlisten.then((cb) => cb());
+    };
+  }, [listen]);
 
   if (!network) return null;
 
+  const chainId = network.dedup_chain_id.chain_id;
+  const existing = network.existing ?? [];
+  const selected = target ?? existing[0]?.name ?? NEW_NETWORK;
+
+  const submit = () => {
+    setLoading(true);
+    setProbes(undefined);
+    setAnvil(false);
+
+    if (existing.length === 0) {
+      send("accept");
+    } else if (selected === NEW_NETWORK) {
+      send({ type: "network", name: name || undefined });
+    } else {
+      send({ type: "endpoint", network: selected });
+    }
+  };
+
   return (
     <div className="flex flex-col gap-5">
       <div className="flex w-full items-stretch justify-center self-center">
-        <h1 className="font-xl">Add new network</h1>
+        <h1 className="font-xl">
+          {existing.length === 0 ? "Add new network" : "Add RPC endpoint"}
+        </h1>
       </div>
 
-      <ChainView
-        chainId={network.dedup_chain_id.chain_id}
-        name={network.name}
-      />
+      <ChainView chainId={chainId} name={network.name} />
 
       <div className="grid grid-cols-4 gap-5">
-        <Datapoint
-          label="Chain ID"
-          value={network.dedup_chain_id.chain_id}
-          className="col-span-2"
-        />
+        <Datapoint label="Chain ID" value={chainId} className="col-span-2" />
         <Datapoint
           label="Currency"
           value={network.currency}
@@ -67,19 +137,60 @@ function ChainAddDialog() {
         )}
       </div>
 
+      {existing.length > 0 && (
+        <div className="flex flex-col gap-2">
+          <span className="text-sm">
+            You already have a network for this chain. Add this RPC to it, or
+            keep it as a separate network.
+          </span>
+          <Select value={selected} onValueChange={setTarget}>
+            <SelectTrigger>
+              <SelectValue />
+            </SelectTrigger>
+
+            <SelectContent>
+              <SelectGroup>
+                {existing.map(({ name }) => (
+                  <SelectItem value={name} key={name}>
+                    <ChainView chainId={chainId} name={name} />
+                  </SelectItem>
+                ))}
+                <SelectItem value={NEW_NETWORK}>Separate network</SelectItem>
+              </SelectGroup>
+            </SelectContent>
+          </Select>
+          {selected === NEW_NETWORK && (
+            <Input
+              placeholder={network.name}
+              value={name ?? ""}
+              onChange={(e) => setName(e.target.value)}
+            />
+          )}
+        </div>
+      )}
+
+      {probes && (
+        <div className="flex flex-col gap-1 text-destructive text-sm">
+          <span>None of the RPC URLs could be reached:</span>
+          {probes.map(({ url }) => (
+            <span key={url}>{url}</span>
+          ))}
+        </div>
+      )}
+
+      {anvil && (
+        <span className="text-destructive text-sm">
+          This RPC is an anvil node, with a state of its own. It can only be
+          added as a separate network.
+        </span>
+      )}
+
       <DialogBottom>
         <div className="m-2 flex items-center justify-center gap-2">
-          <Button
-            variant="destructive"
-            onClick={() => getCurrentWebviewWindow().close()}
-          >
+          <Button variant="destructive" onClick={() => send("reject")}>
             Cancel
           </Button>
-          <Button
-            type="submit"
-            disabled={!isDirty || !isValid}
-            onClick={() => send("accept")}
-          >
+          <Button type="submit" disabled={loading} onClick={submit}>
             Add
           </Button>
         </div>
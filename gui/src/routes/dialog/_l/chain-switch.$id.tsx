@@ -1,9 +1,19 @@
 import { Button } from "@ethui/ui/components/shadcn/button";
+import { Input } from "@ethui/ui/components/shadcn/input";
+import { Label } from "@ethui/ui/components/shadcn/label";
+import {
+  Select,
+  SelectContent,
+  SelectGroup,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@ethui/ui/components/shadcn/select";
 import { createFileRoute } from "@tanstack/react-router";
-import { isDirty, isValid } from "zod";
+import { useState } from "react";
 
+import type { Network } from "@ethui/types/network";
 import { ChainView } from "@ethui/ui/components/chain-view";
-import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { useDialog } from "#/hooks/useDialog";
 import { useNetworks } from "#/store/useNetworks";
 
@@ -12,25 +22,30 @@ export const Route = createFileRoute("/dialog/_l/chain-switch/$id")({
 });
 
 interface NetworkSwitch {
+  domain?: string;
   oldId: number;
   newId: number;
+
+  /// networks sharing `newId`, to pick from. If empty, the user is offered to add one
+  networks: Network[];
 }
 
 function ChainSwitchDialog() {
   const { id } = Route.useParams();
   const { data: switchData, send } = useDialog<NetworkSwitch>(id);
-  const networks = useNetworks((s) => s.networks);
+  const current = useNetworks((s) => s.current);
+  const [selected, setSelected] = useState<string>();
+  const [remember, setRemember] = useState(false);
 
   if (!switchData) return null;
 
-  const from = networks.find(
-    (n) => n.dedup_chain_id.chain_id === switchData.oldId,
-  );
-  const to = networks.find(
-    (n) => n.dedup_chain_id.chain_id === switchData.newId,
-  );
+  const { domain, newId, networks } = switchData;
+
+  if (networks.length === 0) {
+    return <AddNetwork domain={domain} chainId={newId} send={send} />;
+  }
 
-  if (!from || !to) return null;
+  const dedupId = selected ?? networks[0].dedup_chain_id.dedup_id.toString();
 
   return (
     <div className="flex flex-col gap-5">
@@ -38,25 +53,133 @@ function ChainSwitchDialog() {
         <h1 className="font-xl">Switch network</h1>
       </div>
 
-      <div className="flex gap-2 self-center">
-        <ChainView chainId={from.dedup_chain_id.chain_id} name={from.name} />
-        <span>→</span>
-        <ChainView chainId={to?.dedup_chain_id.chain_id} name={to.name} />
+      <p className="text-sm">
+        {domain ?? "This site"} wants to switch to chain {newId}.
+        {networks.length > 1 &&
+          " More than one of your networks uses it, so pick which one to use."}
+      </p>
+
+      <div className="flex items-center gap-2">
+        {current && (
+          <>
+            <ChainView
+              chainId={current.dedup_chain_id.chain_id}
+              name={current.name}
+            />
+            <span>→</span>
+          </>
+        )}
+        <Select value={dedupId} onValueChange={setSelected}>
+          <SelectTrigger>
+            <SelectValue />
+          </SelectTrigger>
+
+          <SelectContent>
+            <SelectGroup>
+              {networks.map(
+                ({ dedup_chain_id: { chain_id, dedup_id }, name }) => (
+                  <SelectItem value={dedup_id.toString()} key={dedup_id}>
+                    <ChainView chainId={chain_id} name={name} />
+                  </SelectItem>
+                ),
+              )}
+            </SelectGroup>
+          </SelectContent>
+        </Select>
       </div>
 
+      {domain && (
+        <div className="flex items-center gap-2">
+          <input
+            id="remember"
+            type="checkbox"
+            checked={remember}
+            onChange={(e) => setRemember(e.target.checked)}
+          />
+          <Label htmlFor="remember">Remember this choice for {domain}</Label>
+        </div>
+      )}
+
       <div className="m-2 flex items-center justify-center gap-2">
+        <Button variant="destructive" onClick={() => send("reject")}>
+          Cancel
+        </Button>
         <Button
-          variant="destructive"
-          onClick={() => getCurrentWebviewWindow().close()}
+          type="submit"
+          onClick={() =>
+            send({ type: "select", dedupId: Number(dedupId), remember })
+          }
         >
+          Switch
+        </Button>
+      </div>
+    </div>
+  );
+}
+
+interface AddNetworkProps {
+  domain?: string;
+  chainId: number;
+  send: (payload: unknown) => void;
+}
+
+/// describes a network for a chain id none of the user's networks use. Its RPC is checked by
+/// the `chain-add` dialog that follows
+function AddNetwork({ domain, chainId, send }: AddNetworkProps) {
+  const [name, setName] = useState("");
+  const [rpcUrl, setRpcUrl] = useState("");
+  const [currency, setCurrency] = useState("ETH");
+
+  const add = () =>
+    send({
+      type: "add",
+      network: {
+        chainName: name,
+        rpcUrls: [rpcUrl],
+        nativeCurrency: { name: currency, symbol: currency, decimals: 18 },
+        blockExplorerUrls: [],
+      },
+    });
+
+  return (
+    <div className="flex flex-col gap-5">
+      <div className="flex w-full items-stretch justify-center self-center">
+        <h1 className="font-xl">Add network</h1>
+      </div>
+
+      <p className="text-sm">
+        {domain ?? "This site"} wants to switch to chain {chainId}, which none
+        of your networks use. You can add it now.
+      </p>
+
+      <div className="flex flex-col gap-2">
+        <Input
+          placeholder="Name"
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+        />
+        <Input
+          placeholder="RPC URL"
+          value={rpcUrl}
+          onChange={(e) => setRpcUrl(e.target.value)}
+        />
+        <Input
+          placeholder="Currency symbol"
+          value={currency}
+          onChange={(e) => setCurrency(e.target.value)}
+        />
+      </div>
+
+      <div className="m-2 flex items-center justify-center gap-2">
+        <Button variant="destructive" onClick={() => send("reject")}>
           Cancel
         </Button>
         <Button
           type="submit"
-          disabled={!isDirty || !isValid}
-          onClick={() => send("accept")}
+          disabled={!name || !rpcUrl || !currency}
+          onClick={add}
         >
-          Switch
+          Add
         </Button>
       </div>
     </div>
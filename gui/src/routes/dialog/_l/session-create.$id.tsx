@@ -0,0 +1,94 @@
+import { createFileRoute } from "@tanstack/react-router";
+
+import { ChainView } from "@ethui/ui/components/chain-view";
+import { Button } from "@ethui/ui/components/shadcn/button";
+import { Datapoint } from "#/components/Datapoint";
+import { DialogBottom } from "#/components/Dialogs/Bottom";
+import { useDialog } from "#/hooks/useDialog";
+import { useNetworks } from "#/store/useNetworks";
+
+export const Route = createFileRoute("/dialog/_l/session-create/$id")({
+  component: SessionCreateDialog,
+});
+
+interface SessionScope {
+  chain: { chain_id: number; dedup_id: number };
+  methods: string[];
+  notifications: string[];
+}
+
+interface SessionRequest {
+  domain: string;
+  scopes: Record<string, SessionScope>;
+}
+
+const SIGNING_METHODS = [
+  "eth_sendTransaction",
+  "eth_sign",
+  "personal_sign",
+  "eth_signTypedData",
+  "eth_signTypedData_v4",
+];
+
+function SessionCreateDialog() {
+  const { id } = Route.useParams();
+  const { data, send } = useDialog<SessionRequest>(id);
+  const networks = useNetworks((s) => s.networks);
+
+  if (!data) return null;
+
+  const scopes = Object.entries(data.scopes);
+  const signs = scopes.some(([, scope]) =>
+    scope.methods.some((m) => SIGNING_METHODS.includes(m)),
+  );
+
+  return (
+    <div className="flex flex-col gap-5">
+      <div className="flex w-full items-stretch justify-center self-center">
+        <h1 className="font-xl">Connect to multiple networks</h1>
+      </div>
+
+      <Datapoint label="Site" value={data.domain} />
+
+      <div className="flex flex-col gap-2">
+        {scopes.map(([scope, { chain, methods }]) => {
+          const network = networks.find(
+            (n) =>
+              n.dedup_chain_id.chain_id === chain.chain_id &&
+              n.dedup_chain_id.dedup_id === chain.dedup_id,
+          );
+
+          return (
+            <div key={scope} className="flex items-center justify-between">
+              <ChainView
+                chainId={chain.chain_id}
+                name={network?.name ?? scope}
+              />
+              <span className="text-muted-foreground text-sm">
+                {methods.length} methods
+              </span>
+            </div>
+          );
+        })}
+      </div>
+
+      {signs && (
+        <p className="text-sm">
+          This site will be able to request transactions and signatures on
+          each of these networks.
+        </p>
+      )}
+
+      <DialogBottom>
+        <div className="m-2 flex items-center justify-center gap-2">
+          <Button variant="destructive" onClick={() => send("reject")}>
+            Reject
+          </Button>
+          <Button type="submit" onClick={() => send("accept")}>
+            Connect
+          </Button>
+        </div>
+      </DialogBottom>
+    </div>
+  );
+}
@@ -28,6 +28,7 @@ import { AddressView } from "#/components/AddressView";
 import { Datapoint } from "#/components/Datapoint";
 import { DialogBottom } from "#/components/Dialogs/Bottom";
 import { IconAddress } from "#/components/Icons/Address";
+import type { AddressLabel } from "#/hooks/useAddressLabel";
 import { useDialog } from "#/hooks/useDialog";
 import type { Dialog } from "#/hooks/useDialog";
 import { useInvoke } from "#/hooks/useInvoke";
@@ -42,6 +43,7 @@ interface TxRequest {
   input: `0x${string}`;
   from: Address;
   to: Address;
+  toLabel?: AddressLabel | null;
   value: string;
   chainId: number;
   walletType:
@@ -134,7 +136,7 @@ function Inner({ dialog, request, network }: InnerProps) {
 
   return (
     <>
-      <Header {...{ from, to, network }} />
+      <Header {...{ from, to, network }} toLabel={request.toLabel} />
 
       {item && (
         <AbiItemFormWithPreview
@@ -168,17 +170,18 @@ function Inner({ dialog, request, network }: InnerProps) {
 interface HeaderProps {
   from: Address;
   to: Address;
+  toLabel?: AddressLabel | null;
   network: Network;
 }
 
-function Header({ from, to, network }: HeaderProps) {
+function Header({ from, to, toLabel, network }: HeaderProps) {
   return (
     <div className=" flex w-full items-stretch justify-between self-center">
       <h1 className="font-xl">
         <div className="m-2 flex items-center gap-2">
           <AddressView address={from} />
           <span>→</span>
-          <AddressView address={to} />
+          <AddressView address={to} label={toLabel} />
         </div>
       </h1>
       <div className="ml-5">
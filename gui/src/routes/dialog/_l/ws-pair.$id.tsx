@@ -0,0 +1,63 @@
+import { createFileRoute } from "@tanstack/react-router";
+
+import { Button } from "@ethui/ui/components/shadcn/button";
+import { Datapoint } from "#/components/Datapoint";
+import { DialogBottom } from "#/components/Dialogs/Bottom";
+import { useDialog } from "#/hooks/useDialog";
+
+export const Route = createFileRoute("/dialog/_l/ws-pair/$id")({
+  component: WsPairDialog,
+});
+
+interface PairingRequest {
+  origin?: string;
+  claimedOrigin?: string;
+  url?: string;
+}
+
+function WsPairDialog() {
+  const { id } = Route.useParams();
+  const { data, send } = useDialog<PairingRequest>(id);
+
+  if (!data) return null;
+
+  return (
+    <div className="flex flex-col gap-5">
+      <div className="flex w-full items-stretch justify-center self-center">
+        <h1 className="font-xl">Connect client</h1>
+      </div>
+
+      <div className="grid grid-cols-4 gap-5">
+        <Datapoint
+          label="Client"
+          value={data.origin ?? "unknown (not a browser)"}
+          className="col-span-4"
+        />
+        {data.claimedOrigin && (
+          <Datapoint
+            label="Claimed site"
+            value={data.claimedOrigin}
+            className="col-span-4"
+          />
+        )}
+      </div>
+
+      <p className="text-sm">
+        Once connected, this client can request accounts, transactions and
+        signatures on behalf of any site. Only continue if you just installed
+        or reconnected the ethui extension.
+      </p>
+
+      <DialogBottom>
+        <div className="m-2 flex items-center justify-center gap-2">
+          <Button variant="destructive" onClick={() => send("reject")}>
+            Reject
+          </Button>
+          <Button type="submit" onClick={() => send("accept")}>
+            Connect
+          </Button>
+        </div>
+      </DialogBottom>
+    </div>
+  );
+}
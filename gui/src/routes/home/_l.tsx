@@ -7,6 +7,7 @@ import { AppNavbar } from "#/components/AppNavbar";
 import { AppSidebar } from "#/components/AppSidebar";
 import { CommandBar, CommandBarProvider } from "#/components/CommandBar";
 import { useNoticeAlchemyKeyMissing } from "#/hooks/useNoticeAlchemyKeyMissing";
+import { useNoticeDevAccounts } from "#/hooks/useNoticeDevAccounts";
 import { useNoticeNewVersion } from "#/hooks/useNoticeNewVersion";
 
 export const Route = createFileRoute("/home/_l")({
@@ -16,6 +17,7 @@ export const Route = createFileRoute("/home/_l")({
 function HomePageLayout() {
   useNoticeAlchemyKeyMissing();
   useNoticeNewVersion();
+  useNoticeDevAccounts();
 
   return (
     <CommandBarProvider>
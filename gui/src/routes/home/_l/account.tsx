@@ -2,6 +2,7 @@ import { createFileRoute } from "@tanstack/react-router";
 
 import { AddressView } from "#/components/AddressView";
 import { BalancesList } from "#/components/BalancesList";
+import { DelegationsList } from "#/components/DelegationsList";
 import { useWallets } from "#/store/useWallets";
 
 export const Route = createFileRoute("/home/_l/account")({
@@ -20,6 +21,7 @@ function Account() {
         <AddressView address={address} />
       </div>
       <BalancesList />
+      <DelegationsList address={address} />
     </>
   );
 }
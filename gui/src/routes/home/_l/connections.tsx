@@ -1,12 +1,13 @@
 import { createFileRoute } from "@tanstack/react-router";
 import { invoke } from "@tauri-apps/api/core";
-import { map } from "lodash-es";
+import { map, uniqBy } from "lodash-es";
 import { useEffect, useState } from "react";
 import { useShallow } from "zustand/shallow";
 
 import type { Affinity, Peer } from "@ethui/types";
 import type { Network } from "@ethui/types/network";
 import { ChainView } from "@ethui/ui/components/chain-view";
+import { Label } from "@ethui/ui/components/shadcn/label";
 import {
   Select,
   SelectContent,
@@ -15,10 +16,15 @@ import {
   SelectTrigger,
   SelectValue,
 } from "@ethui/ui/components/shadcn/select";
+import { Switch } from "@ethui/ui/components/shadcn/switch";
+import { Recordings } from "#/components/Recordings";
 import { useEventListener } from "#/hooks/useEventListener";
 import { useInvoke } from "#/hooks/useInvoke";
 import { useNetworks } from "#/store/useNetworks";
 
+/// key under which the IPC endpoint stores its settings. must match `ethui_ipc::CLIENT`
+const IPC_CLIENT = "client:ipc";
+
 export const Route = createFileRoute("/home/_l/connections")({
   beforeLoad: () => ({
     breadcrumb: "Connections",
@@ -37,31 +43,113 @@ function Connections() {
       {map(peersByDomain, (peers, domain) => (
         <Domain key={domain} domain={domain} peers={peers} />
       ))}
+      <div className="m-1 flex items-center gap-2">
+        <span>IPC socket</span>
+        <div>
+          <AffinityForm domain={IPC_CLIENT} />
+        </div>
+        <ReadOnlySwitch connectionKey={IPC_CLIENT} />
+      </div>
+      <Recordings />
     </div>
   );
 }
 
 function Domain({ domain, peers }: { domain: string; peers: Peer[] }) {
+  const { data: tabScoped, refetch } = useInvoke<boolean>(
+    "connections_is_tab_scoped",
+    { domain },
+  );
+
+  const tabs = uniqBy(
+    peers.filter((p) => p.tab_id !== undefined),
+    (p) => p.tab_id,
+  );
+
+  const onTabScopedChange = async (enabled: boolean) => {
+    await invoke("connections_set_tab_scoped", { domain, enabled });
+    refetch();
+  };
+
   return (
-    <div className=" m-1 flex items-center gap-2">
-      <img className="h-8 w-8" src={peers[0].favicon} alt={domain} />
-      <span> {peers[0].origin}</span>
-      <div>
-        <AffinityForm domain={domain} />
+    <div className="m-1 flex flex-col gap-2">
+      <div className="flex items-center gap-2">
+        <img className="h-8 w-8" src={peers[0].favicon} alt={domain} />
+        <span> {peers[0].origin}</span>
+        {!tabScoped && (
+          <div>
+            <AffinityForm domain={domain} />
+          </div>
+        )}
+        <div className="flex items-center space-x-2">
+          <Switch
+            id={`tab-scoped-${domain}`}
+            checked={!!tabScoped}
+            onCheckedChange={onTabScopedChange}
+          />
+          <Label
+            htmlFor={`tab-scoped-${domain}`}
+            className="cursor-pointer"
+          >
+            Network per tab
+          </Label>
+        </div>
+        <ReadOnlySwitch connectionKey={domain} />
       </div>
+
+      {tabScoped &&
+        tabs.map(({ tab_id, title, url }) => (
+          <div key={tab_id} className="ml-10 flex items-center gap-2">
+            <span className="truncate text-sm">{title || url}</span>
+            <AffinityForm domain={domain} tabId={tab_id} />
+          </div>
+        ))}
     </div>
   );
 }
 
-function AffinityForm({ domain }: { domain: string }) {
+/// restricts a domain, or a client such as the IPC socket, to read-only methods
+function ReadOnlySwitch({ connectionKey }: { connectionKey: string }) {
+  const { data: readOnly, refetch } = useInvoke<boolean>(
+    "connections_is_read_only",
+    { key: connectionKey },
+  );
+
+  const onChange = async (readOnly: boolean) => {
+    await invoke("connections_set_read_only", { key: connectionKey, readOnly });
+    refetch();
+  };
+
+  return (
+    <div className="flex items-center space-x-2">
+      <Switch
+        id={`read-only-${connectionKey}`}
+        checked={!!readOnly}
+        onCheckedChange={onChange}
+      />
+      <Label htmlFor={`read-only-${connectionKey}`} className="cursor-pointer">
+        Read-only
+      </Label>
+    </div>
+  );
+}
+
+interface AffinityFormProps {
+  domain: string;
+
+  /// edits the affinity of a single tab, for tab-scoped domains
+  tabId?: number;
+}
+
+function AffinityForm({ domain, tabId }: AffinityFormProps) {
   const [networks, currentGlobalNetwork] = useNetworks(
     useShallow((s) => [s.networks, s.current]),
   );
   const { data: affinity, refetch } = useInvoke<Affinity>(
-    "connections_affinity_for",
-    {
-      domain,
-    },
+    tabId === undefined
+      ? "connections_affinity_for"
+      : "connections_affinity_for_tab",
+    { domain, tabId },
   );
 
   useEventListener("peers-updated", refetch);
@@ -91,10 +179,11 @@ function AffinityForm({ domain }: { domain: string }) {
     if (value !== "global") {
       affinity = { sticky: [Number.parseInt(value), 0] };
     }
-    invoke("connections_set_affinity", {
-      domain,
-      affinity,
-    });
+    if (tabId === undefined) {
+      invoke("connections_set_affinity", { domain, affinity });
+    } else {
+      invoke("connections_set_tab_affinity", { domain, tabId, affinity });
+    }
     setCurrent(affinity);
   };
 
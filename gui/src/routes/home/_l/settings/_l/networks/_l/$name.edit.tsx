@@ -5,7 +5,7 @@ import { toast } from "@ethui/ui/hooks/use-toast";
 import { zodResolver } from "@hookform/resolvers/zod";
 import { createFileRoute, useRouter } from "@tanstack/react-router";
 import { invoke } from "@tauri-apps/api/core";
-import { useForm } from "react-hook-form";
+import { useFieldArray, useForm } from "react-hook-form";
 import { useNetworks } from "#/store/useNetworks";
 
 export const Route = createFileRoute(
@@ -31,6 +31,15 @@ function Content({ network }: { network: Network }) {
   });
   const router = useRouter();
 
+  const {
+    fields: endpointFields,
+    append,
+    remove: removeEndpoint,
+  } = useFieldArray({
+    control: form.control,
+    name: "endpoints",
+  });
+
   const create = async (data: Network) => {
     try {
       await invoke("networks_update", { oldName: network.name, network: data });
@@ -63,7 +72,35 @@ function Content({ network }: { network: Network }) {
         />
       </div>
 
-      <Form.Text label="HTTP RPC" name="http_url" className="w-full" />
+      {endpointFields.map((field, i) => (
+        <div className="flex items-center self-stretch" key={field.id}>
+          <Form.Text
+            label={i === 0 ? "HTTP RPC" : `HTTP RPC (fallback ${i})`}
+            name={`endpoints.${i}.url`}
+            className="w-full"
+          />
+          {endpointFields.length > 1 && (
+            <Button
+              variant="ghost"
+              onClick={(e) => {
+                e.preventDefault();
+                removeEndpoint(i);
+              }}
+            >
+              Remove
+            </Button>
+          )}
+        </div>
+      ))}
+      <Button
+        color="secondary"
+        onClick={(e) => {
+          e.preventDefault();
+          append({ url: "", priority: endpointFields.length });
+        }}
+      >
+        Add RPC
+      </Button>
       <Form.Text label="WebSockets RPC" name="ws_url" className="w-full" />
       <Form.Text label="Explorer URL" name="explorer_url" className="w-full" />
       <div className="flex flex-row gap-2">
@@ -3,7 +3,10 @@ import { Link, createFileRoute } from "@tanstack/react-router";
 import { useShallow } from "zustand/shallow";
 
 import { ChainView } from "@ethui/ui/components/chain-view";
-import { Plus } from "lucide-react";
+import { Button } from "@ethui/ui/components/shadcn/button";
+import { invoke } from "@tauri-apps/api/core";
+import { Lock, Plus } from "lucide-react";
+import { useInvoke } from "#/hooks/useInvoke";
 import { useNetworks } from "#/store/useNetworks";
 
 export const Route = createFileRoute("/home/_l/settings/_l/networks/_l/")({
@@ -11,30 +14,63 @@ export const Route = createFileRoute("/home/_l/settings/_l/networks/_l/")({
   component: () => <SettingsNetworks />,
 });
 
+interface SecretsInfo {
+  exists: boolean;
+  locked: boolean;
+  names: string[];
+  exposed: string[];
+}
+
 function SettingsNetworks() {
   const networks = useNetworks(useShallow((s) => s.networks));
+  const { data: secrets, refetch } = useInvoke<SecretsInfo>(
+    "networks_get_secrets",
+  );
+
+  const lock = async () => {
+    await invoke("networks_lock_secrets");
+    refetch();
+  };
 
   if (!networks) return <>Loading</>;
 
   // TODO: add network button
   return (
-    <div className="flex flex-wrap gap-2">
-      {networks.map(({ dedup_chain_id: { chain_id }, name }) => (
+    <div className="flex flex-col gap-2">
+      {secrets && secrets.exposed.length > 0 && (
+        <p className="border border-destructive p-2 text-sm">
+          The RPC URLs of {secrets.exposed.join(", ")} seem to include API keys,
+          stored in plaintext. They'll be moved into encrypted secrets once a
+          secrets password is set or entered.
+        </p>
+      )}
+      {secrets?.exists && !secrets.locked && (
+        <div className="flex items-center gap-2 text-sm">
+          Secrets are unlocked.
+          <Button size="sm" variant="outline" onClick={lock}>
+            <Lock />
+            Lock
+          </Button>
+        </div>
+      )}
+      <div className="flex flex-wrap gap-2">
+        {networks.map(({ dedup_chain_id: { chain_id }, name }) => (
+          <Link
+            to={`/home/settings/networks/${name}/edit`}
+            key={name}
+            className="border p-4 hover:bg-accent"
+          >
+            <ChainView chainId={chain_id} name={name} />
+          </Link>
+        ))}
         <Link
-          to={`/home/settings/networks/${name}/edit`}
-          key={name}
-          className="border p-4 hover:bg-accent"
+          to="/home/settings/networks/new"
+          className="flex gap-2 border p-4 align-baseline hover:bg-accent"
         >
-          <ChainView chainId={chain_id} name={name} />
+          <Plus />
+          Add new
         </Link>
-      ))}
-      <Link
-        to="/home/settings/networks/new"
-        className="flex gap-2 border p-4 align-baseline hover:bg-accent"
-      >
-        <Plus />
-        Add new
-      </Link>
+      </div>
     </div>
   );
 }
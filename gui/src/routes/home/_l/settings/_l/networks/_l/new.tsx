@@ -1,4 +1,4 @@
-import { type Network, networkSchema } from "@ethui/types/network";
+import { type NewNetwork, newNetworkSchema } from "@ethui/types/network";
 import { Form } from "@ethui/ui/components/form";
 import { Button } from "@ethui/ui/components/shadcn/button";
 import { toast } from "@ethui/ui/hooks/use-toast";
@@ -21,9 +21,9 @@ function Content() {
   const router = useRouter();
   const [loading, setLoading] = useState(false);
 
-  const form = useForm<Network>({
+  const form = useForm<NewNetwork>({
     mode: "onBlur",
-    resolver: zodResolver(networkSchema),
+    resolver: zodResolver(newNetworkSchema),
   });
 
   const httpUrl = form.watch("http_url");
@@ -53,7 +53,7 @@ function Content() {
     fetchChainId();
   }, [httpUrl, userChainId, form.setValue, form.clearErrors]);
 
-  const onSubmit = async (data: Network) => {
+  const onSubmit = async (data: NewNetwork) => {
     try {
       setLoading(true);
       data.dedup_chain_id.dedup_id = 0;
@@ -1,8 +1,14 @@
 import { createFileRoute } from "@tanstack/react-router";
 
+import { FoundryKeystores } from "#/components/Settings/FoundryKeystores";
 import { SettingsWallets } from "#/components/Settings/Wallets";
 
 export const Route = createFileRoute("/home/_l/settings/_l/wallets/_l/")({
   beforeLoad: () => ({ breadcrumb: "Wallets" }),
-  component: () => <SettingsWallets />,
+  component: () => (
+    <div className="flex flex-col gap-6">
+      <SettingsWallets />
+      <FoundryKeystores />
+    </div>
+  ),
 });
@@ -34,7 +34,8 @@ const store: StateCreator<Store> = (set, get) => ({
     });
     const contractsWithAlias = await Promise.all(
       contracts.map(async (c) => {
-        const alias = await invoke<string | undefined>("settings_get_alias", {
+        const alias = await invoke<string | undefined>("db_get_alias", {
+          chainId,
           address: c.address,
         });
         return { ...c, alias };
@@ -97,6 +98,9 @@ export const useContracts = create<Store>()(subscribeWithSelector(store));
 event.listen("contracts-updated", async () => {
   await useContracts.getState().reload();
 });
+event.listen("address-book-updated", async () => {
+  await useContracts.getState().reload();
+});
 
 useNetworks.subscribe(
   (s) => s.current?.dedup_chain_id.chain_id,
@@ -65,7 +65,7 @@ const store: StateCreator<Store> = (set, get) => ({
 
 export const useWallets = create<Store>()(subscribeWithSelector(store));
 
-event.listen("settings-changed", async () => {
+event.listen("address-book-updated", async () => {
   await useWallets.getState().reload();
 });
 event.listen("wallets-changed", async () => {
@@ -95,7 +95,7 @@ const fetchAllWalletInfo = async (wallets: Wallet[]): Promise<WalletInfo[]> =>
               key,
               address,
               walletName: wallet.name,
-              alias: await invoke<string>("settings_get_alias", { address }),
+              alias: await invoke<string>("db_get_alias", { address }),
             })),
           ),
         };
@@ -0,0 +1,25 @@
+CREATE TABLE address_book (
+  address VARCHAR NOT NULL COLLATE NOCASE,
+  -- 0 means the entry applies to all networks
+  chain_id INTEGER NOT NULL DEFAULT 0,
+  name VARCHAR NOT NULL,
+  -- JSON array of strings
+  tags VARCHAR NOT NULL DEFAULT '[]',
+  notes VARCHAR,
+
+  PRIMARY KEY (address, chain_id)
+);
+
+CREATE TABLE ens_names (
+  name VARCHAR PRIMARY KEY NOT NULL COLLATE NOCASE,
+  -- NULL if the name did not resolve
+  address VARCHAR COLLATE NOCASE,
+  resolved_at INTEGER NOT NULL
+);
+
+CREATE TABLE ens_reverse (
+  address VARCHAR PRIMARY KEY NOT NULL COLLATE NOCASE,
+  -- NULL if the address has no (valid) primary name
+  name VARCHAR,
+  resolved_at INTEGER NOT NULL
+);
@@ -0,0 +1,19 @@
+CREATE TABLE rpc_traffic (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  -- unix timestamp, in milliseconds
+  timestamp INTEGER NOT NULL,
+  -- 'dapp' or 'upstream'
+  source VARCHAR NOT NULL,
+  peer VARCHAR,
+  origin VARCHAR,
+  chain_id INTEGER,
+  url VARCHAR,
+  method VARCHAR NOT NULL,
+  -- JSON values
+  params VARCHAR NOT NULL,
+  result VARCHAR,
+  error VARCHAR,
+  latency_ms INTEGER NOT NULL
+);
+
+CREATE INDEX rpc_traffic_timestamp ON rpc_traffic (timestamp);
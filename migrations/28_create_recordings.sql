@@ -0,0 +1,9 @@
+CREATE TABLE recordings (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name VARCHAR NOT NULL,
+  domain VARCHAR,
+  created_at INTEGER NOT NULL,
+  -- JSON values
+  network VARCHAR NOT NULL,
+  steps VARCHAR NOT NULL
+);
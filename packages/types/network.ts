@@ -34,16 +34,41 @@ const rpcAndChainIdSchema = z
     }
   });
 
-export const networkSchema = z.intersection(
-  z.object({
-    name: z.string().min(1),
-    explorer_url: z.string().optional().nullable(),
-    ws_url: z.string().nullable().optional(),
-    currency: z.string().min(1),
-    decimals: z.number(),
-    warnings: z.string().optional(),
-  }),
+const networkFieldsSchema = z.object({
+  name: z.string().min(1),
+  explorer_url: z.string().optional().nullable(),
+  ws_url: z.string().nullable().optional(),
+  currency: z.string().min(1),
+  decimals: z.number(),
+  warnings: z.string().optional(),
+});
+
+// a network being created, with a single RPC
+export const newNetworkSchema = z.intersection(
+  networkFieldsSchema,
   rpcAndChainIdSchema,
 );
 
+export type NewNetwork = z.infer<typeof newNetworkSchema>;
+
+// health and secrets are kept as they are, so they survive a round trip through a form
+export const rpcEndpointSchema = z
+  .object({
+    url: z.string().min(1).url(),
+    priority: z.coerce.number().nonnegative(),
+  })
+  .passthrough();
+
+export type RpcEndpoint = z.infer<typeof rpcEndpointSchema>;
+
+export const networkSchema = networkFieldsSchema.extend({
+  dedup_chain_id: z.object({
+    chain_id: z.coerce.number().positive(),
+    dedup_id: z.coerce.number(),
+  }),
+  endpoints: z
+    .array(rpcEndpointSchema)
+    .min(1, "a network needs at least one RPC"),
+});
+
 export type Network = z.infer<typeof networkSchema>;